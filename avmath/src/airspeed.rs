@@ -0,0 +1,60 @@
+//! Airspeed quantities carrying the context of how they are measured
+
+use uom::si::f64::Ratio;
+
+/// Airspeed as a ratio of the local speed of sound
+///
+/// Kept distinct from a plain [`Ratio`] so that a Mach number is not
+/// accidentally combined with unrelated ratios, such as throttle positions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MachNumber(Ratio);
+
+impl MachNumber {
+    /// Constructs a new Mach number
+    #[inline(always)]
+    pub fn new<N>(v: f64) -> Self
+    where
+        N: uom::si::ratio::Unit + uom::Conversion<f64, T = f64>,
+    {
+        Self(Ratio::new::<N>(v))
+    }
+
+    /// Retrieve the value in the requested measurement unit
+    #[inline(always)]
+    pub fn get<N>(self) -> f64
+    where
+        N: uom::si::ratio::Unit + uom::Conversion<f64, T = f64>,
+    {
+        self.0.get::<N>()
+    }
+
+    /// Interprets a raw ratio as a Mach number
+    #[inline(always)]
+    pub fn interpret(ratio: Ratio) -> Self {
+        Self(ratio)
+    }
+
+    /// Removes the context that this is a Mach number
+    #[inline(always)]
+    pub fn remove_context(self) -> Ratio {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uom::si::ratio::{percent, ratio};
+
+    #[test]
+    fn construction_round_trips_through_units() {
+        let mach = MachNumber::new::<ratio>(0.72);
+
+        assert_eq!(0.72, mach.get::<ratio>());
+        assert_eq!(MachNumber::new::<percent>(72.), mach);
+        assert_eq!(Ratio::new::<ratio>(0.72), mach.remove_context());
+        assert_eq!(mach, MachNumber::interpret(mach.remove_context()));
+        assert!(MachNumber::new::<ratio>(0.5) < mach);
+    }
+}
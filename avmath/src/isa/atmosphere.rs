@@ -31,6 +31,16 @@ impl Layer {
     //     layers().get(index)
     // }
 
+    /// Returns the lowest layer of the standard atmosphere
+    pub(crate) fn base() -> &'static Layer {
+        &LAYERS[0]
+    }
+
+    /// Returns the highest layer of the standard atmosphere
+    pub(crate) fn top() -> &'static Layer {
+        &LAYERS[LAYERS.len() - 1]
+    }
+
     /// Returns the atmospheric layer associated with a given altitude
     pub fn find_by_altitude(altitude: GeopotentialAltitude) -> Option<&'static Layer> {
         use once_cell::sync::OnceCell;
@@ -84,6 +94,15 @@ impl Layer {
 
         Some(&LAYERS[idx])
     }
+
+    /// The lapse rate for standard temperatures within this layer
+    ///
+    /// Isothermal layers have no lapse rate, and so return a lapse rate of
+    /// zero. Calculations that divide by the lapse rate must treat isothermal
+    /// layers separately rather than relying on this value.
+    pub fn effective_lapse_rate(&self) -> LapseRate {
+        self.lapse_rate.unwrap_or_default()
+    }
 }
 
 fn construct_layers() -> [Layer; 8] {
@@ -184,3 +203,28 @@ fn construct_layers() -> [Layer; 8] {
 }
 
 static LAYERS: Lazy<[Layer; 8]> = Lazy::new(construct_layers);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isothermal_layer_has_zero_effective_lapse_rate() {
+        let tropopause =
+            Layer::find_by_altitude(GeopotentialAltitude::new::<kilometer>(15.)).unwrap();
+
+        assert_eq!(None, tropopause.lapse_rate);
+        assert_eq!(LapseRate::default(), tropopause.effective_lapse_rate());
+    }
+
+    #[test]
+    fn lapsing_layer_has_its_lapse_rate() {
+        let troposphere =
+            Layer::find_by_altitude(GeopotentialAltitude::new::<kilometer>(5.)).unwrap();
+
+        assert_eq!(
+            TemperatureInterval::new::<diff_kelvin>(-6.5) / Length::new::<kilometer>(1.),
+            troposphere.effective_lapse_rate()
+        );
+    }
+}
@@ -1,12 +1,14 @@
 //! Implementations of units based on the International Standard of Units
 //! using quantities relevant to the field of aviation
 
-use crate::constants;
+use crate::{calculations, constants};
 use uom::si::f64::*;
 use uom::si::{
-    length::foot,
-    pressure::{inch_of_mercury, pascal},
+    length::{foot, meter},
+    pressure::inch_of_mercury,
     ratio::ratio,
+    temperature_interval::kelvin as diff_kelvin,
+    thermodynamic_temperature::kelvin,
 };
 
 mod atmosphere;
@@ -19,7 +21,7 @@ pub use atmosphere::Layer;
 ///
 /// This is the ruler-measured value for altitude. Calculations for standard
 /// atmosphere may require conversion into a [`GeopotentialAltitude`].
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GeometricAltitude(Length);
 
@@ -127,6 +129,23 @@ impl std::ops::Div<Ratio> for GeometricAltitude {
         Self(self.0 / rhs.get::<ratio>())
     }
 }
+
+impl From<Length> for GeometricAltitude {
+    /// Interprets a raw length as a geometric altitude
+    #[inline(always)]
+    fn from(length: Length) -> Self {
+        Self::interpret(length)
+    }
+}
+
+impl From<GeometricAltitude> for Length {
+    /// Removes the context that this is a geometric altitude
+    #[inline(always)]
+    fn from(alt: GeometricAltitude) -> Self {
+        alt.remove_context()
+    }
+}
+
 /// Height above mean sea level corrected for variations variations in gravity
 ///
 /// Most standard calculations are based on geopotential altitudes. To obtain
@@ -221,19 +240,17 @@ impl GeopotentialAltitude {
 
     /// Given an altimeter setting, produces the pressure measured by a
     /// station at this altitude
-    #[doc(hidden)]
+    ///
+    /// The altimeter setting offsets this altitude to a pressure altitude,
+    /// and the standard pressure at that pressure altitude is found with the
+    /// barometric formula for its layer of the standard atmosphere, treating
+    /// isothermal layers separately. Unlike
+    /// [`to_pressure_asos`](Self::to_pressure_asos), the result remains valid
+    /// above the troposphere. Returns `None` for pressure altitudes outside
+    /// the standard atmosphere.
     pub fn to_pressure(self, altimeter: AltimeterSetting) -> Option<Pressure> {
-        let layer = Layer::find_by_altitude(self)?;
-
-        let lapse_rate = layer.lapse_rate.unwrap_or_default();
-        let k1 = (-constants::Rd_over_standard_gravity_msl() * -lapse_rate).get::<ratio>();
-        let k2 = (lapse_rate * self.0 / layer.base_temperature).get::<ratio>();
-
-        Some(Pressure::new::<pascal>(
-            (altimeter.remove_context().get::<pascal>().powf(k1)
-                + layer.pressure.start.get::<pascal>().powf(k1) * k2)
-                .powf(k1.recip()),
-        ))
+        let pressure_altitude = altimeter.to_pressure_altitude(self.0);
+        calculations::standard_pressure(GeopotentialAltitude(pressure_altitude.remove_context()))
     }
 
     /// Using the method used by ASOS stations and given an altimeter setting,
@@ -301,6 +318,22 @@ impl std::ops::Div<Ratio> for GeopotentialAltitude {
     }
 }
 
+impl From<Length> for GeopotentialAltitude {
+    /// Interprets a raw length as a gravity-corrected altitude over mean sea level
+    #[inline(always)]
+    fn from(length: Length) -> Self {
+        Self::interpret(length)
+    }
+}
+
+impl From<GeopotentialAltitude> for Length {
+    /// Removes the context that this is a gravity-corrected altitude over mean sea level
+    #[inline(always)]
+    fn from(alt: GeopotentialAltitude) -> Self {
+        alt.remove_context()
+    }
+}
+
 impl From<GeometricAltitude> for GeopotentialAltitude {
     fn from(alt: GeometricAltitude) -> Self {
         Self::interpret(
@@ -320,7 +353,7 @@ impl From<GeopotentialAltitude> for GeometricAltitude {
 }
 
 /// Altitude above mean sea level corrected for non-standard pressure
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PressureAltitude(Length);
 
@@ -379,6 +412,18 @@ impl PressureAltitude {
     pub fn remove_context(self) -> Length {
         self.0
     }
+
+    /// Classifies the altitude into a band using the default thresholds
+    #[inline]
+    pub fn band(self) -> AltitudeBand {
+        self.band_with(&AltitudeBandThresholds::default())
+    }
+
+    /// Classifies the altitude into a band using the thresholds provided
+    #[inline]
+    pub fn band_with(self, thresholds: &AltitudeBandThresholds) -> AltitudeBand {
+        thresholds.classify(self.0)
+    }
 }
 
 impl std::ops::Sub for PressureAltitude {
@@ -388,6 +433,22 @@ impl std::ops::Sub for PressureAltitude {
     }
 }
 
+impl From<Length> for PressureAltitude {
+    /// Interprets a raw length as a pressure-corrected altitude over mean sea level
+    #[inline(always)]
+    fn from(length: Length) -> Self {
+        Self::interpret(length)
+    }
+}
+
+impl From<PressureAltitude> for Length {
+    /// Removes the context that this is a pressure-corrected altitude over mean sea level
+    #[inline(always)]
+    fn from(alt: PressureAltitude) -> Self {
+        alt.remove_context()
+    }
+}
+
 /// Pressure altitude corrected for non-standard temperature and pressure
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -448,6 +509,85 @@ impl DensityAltitude {
     pub fn remove_context(self) -> Length {
         self.0
     }
+
+    /// Computes the density altitude of dry air from the pressure altitude
+    /// and outside air temperature
+    ///
+    /// The density of dry air at the standard pressure for the pressure
+    /// altitude and the given temperature is found, and the standard altitude
+    /// with that density is returned. Near sea level, this is close to the
+    /// rule of thumb of 120 ft for every degree Celsius above standard.
+    ///
+    /// Outside the altitudes covered by the standard atmosphere, the pressure
+    /// altitude is returned unchanged.
+    pub fn from_conditions(
+        pressure_altitude: PressureAltitude,
+        oat: ThermodynamicTemperature,
+    ) -> Self {
+        let fallback = Self(pressure_altitude.remove_context());
+        let pressure = match calculations::standard_pressure(GeopotentialAltitude::interpret(
+            pressure_altitude.remove_context(),
+        )) {
+            Some(pressure) => pressure,
+            None => return fallback,
+        };
+        let density = calculations::standard_density_dry_air(pressure, oat);
+        match Layer::find_by_density(density) {
+            Some(layer) => Self::from_density_in_layer(density, layer),
+            None => fallback,
+        }
+    }
+
+    /// Computes the density altitude of air with the given density
+    ///
+    /// The standard altitude with that density is returned. Beyond the
+    /// densities covered by the standard atmosphere, the lowest or highest
+    /// layer is extended.
+    pub fn from_density(density: MassDensity) -> Self {
+        let layer = Layer::find_by_density(density).unwrap_or_else(|| {
+            if density > Layer::base().density.start {
+                Layer::base()
+            } else {
+                Layer::top()
+            }
+        });
+        Self::from_density_in_layer(density, layer)
+    }
+
+    /// Finds the altitude within `layer` at which the standard density is
+    /// `density`
+    fn from_density_in_layer(density: MassDensity, layer: &Layer) -> Self {
+        let relative_density = (density / layer.density.start).get::<ratio>();
+        let layer_height: Length = if let Some(lapse_rate) = layer.lapse_rate {
+            // Within the layer, ρ/ρb = (T/Tb)^(-g₀/(Rd·L) - 1)
+            let density_exp =
+                (-constants::standard_gravity_msl_over_Rd() / lapse_rate).get::<ratio>() - 1.;
+            let relative_temperature = relative_density.powf(density_exp.recip());
+            let temperature_change = TemperatureInterval::new::<diff_kelvin>(
+                layer.base_temperature.get::<kelvin>() * (relative_temperature - 1.),
+            );
+            temperature_change / lapse_rate
+        } else {
+            // Within an isothermal layer, ρ/ρb = exp(-g₀·Δh/(Rd·Tb))
+            layer.base_temperature
+                * -constants::Rd_over_standard_gravity_msl()
+                * relative_density.ln()
+        };
+
+        Self(layer.altitude.start.remove_context() + layer_height)
+    }
+
+    /// Classifies the altitude into a band using the default thresholds
+    #[inline]
+    pub fn band(self) -> AltitudeBand {
+        self.band_with(&AltitudeBandThresholds::default())
+    }
+
+    /// Classifies the altitude into a band using the thresholds provided
+    #[inline]
+    pub fn band_with(self, thresholds: &AltitudeBandThresholds) -> AltitudeBand {
+        thresholds.classify(self.0)
+    }
 }
 
 impl std::ops::Sub for DensityAltitude {
@@ -457,6 +597,77 @@ impl std::ops::Sub for DensityAltitude {
     }
 }
 
+impl From<Length> for DensityAltitude {
+    /// Interprets a raw length as a pressure- and temperature-corrected altitude over mean sea level
+    #[inline(always)]
+    fn from(length: Length) -> Self {
+        Self::interpret(length)
+    }
+}
+
+impl From<DensityAltitude> for Length {
+    /// Removes the context that this is a pressure- and temperature-corrected altitude over mean sea level
+    #[inline(always)]
+    fn from(alt: DensityAltitude) -> Self {
+        alt.remove_context()
+    }
+}
+
+/// A coarse classification of altitude, used for grouping data
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AltitudeBand {
+    /// Near sea level
+    SeaLevel,
+    /// Low altitude
+    Low,
+    /// Medium altitude
+    Mid,
+    /// High altitude
+    High,
+}
+
+/// The altitudes at which each altitude band begins
+///
+/// An altitude at or above a threshold belongs to that band. Altitudes below
+/// the `low` threshold are considered to be at sea level.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AltitudeBandThresholds {
+    /// The altitude at which the low band begins
+    pub low: Length,
+    /// The altitude at which the mid band begins
+    pub mid: Length,
+    /// The altitude at which the high band begins
+    pub high: Length,
+}
+
+impl AltitudeBandThresholds {
+    /// Classifies an altitude into a band
+    pub fn classify(&self, altitude: Length) -> AltitudeBand {
+        if altitude >= self.high {
+            AltitudeBand::High
+        } else if altitude >= self.mid {
+            AltitudeBand::Mid
+        } else if altitude >= self.low {
+            AltitudeBand::Low
+        } else {
+            AltitudeBand::SeaLevel
+        }
+    }
+}
+
+impl Default for AltitudeBandThresholds {
+    /// Low from 1,000 ft, mid from 10,000 ft, and high from FL250
+    fn default() -> Self {
+        Self {
+            low: Length::new::<foot>(1_000.),
+            mid: Length::new::<foot>(10_000.),
+            high: Length::new::<foot>(25_000.),
+        }
+    }
+}
+
 /// Altimeter setting
 ///
 /// An altimeter set to the QNH value will display
@@ -525,4 +736,183 @@ impl AltimeterSetting {
     pub fn remove_context(self) -> Pressure {
         self.0
     }
+
+    /// The standard altimeter setting (29.92 inHg / 1013.25 hPa)
+    #[inline(always)]
+    pub fn standard() -> Self {
+        Self(constants::standard_pressure_msl())
+    }
+
+    /// Converts an altitude indicated by an altimeter using this setting into
+    /// a pressure altitude
+    ///
+    /// Applies the standard atmosphere relationship for the troposphere
+    /// between the altimeter setting and the standard setting. An altimeter
+    /// using the standard setting indicates pressure altitude directly.
+    pub fn to_pressure_altitude(self, indicated_altitude: Length) -> PressureAltitude {
+        let layer = Layer::find_by_altitude(GeopotentialAltitude(Length::new::<foot>(0.)))
+            .expect("the standard atmosphere includes mean sea level");
+        let lapse_rate = layer.effective_lapse_rate().abs();
+
+        let exponent = (constants::Rd_over_standard_gravity_msl() * lapse_rate).get::<ratio>();
+        let relative_pressure = (self.0 / constants::standard_pressure_msl()).get::<ratio>();
+        let temperature_height: Length = layer.base_temperature / lapse_rate;
+
+        PressureAltitude(
+            indicated_altitude + temperature_height * (1. - relative_pressure.powf(exponent)),
+        )
+    }
+}
+
+/// The spacing, in meters, between rows of the [`standard_table`]
+pub const STANDARD_TABLE_INTERVAL: f64 = 500.;
+
+/// Tabulates the standard temperature, pressure, and dry air density
+///
+/// Rows are computed from the layer model every [`STANDARD_TABLE_INTERVAL`]
+/// meters of geopotential altitude, from the base of the lowest layer of the
+/// standard atmosphere up to the top of the highest.
+pub fn standard_table() -> impl Iterator<
+    Item = (
+        GeopotentialAltitude,
+        ThermodynamicTemperature,
+        Pressure,
+        MassDensity,
+    ),
+> {
+    let base = Layer::base().altitude.start.remove_context();
+    (0..).map_while(move |row| {
+        let altitude = GeopotentialAltitude(
+            base + Length::new::<meter>(f64::from(row) * STANDARD_TABLE_INTERVAL),
+        );
+        let temperature = calculations::standard_temperature(altitude)?;
+        let pressure = calculations::standard_pressure(altitude)?;
+        let density = calculations::standard_density_dry_air(pressure, temperature);
+        Some((altitude, temperature, pressure, density))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! length_round_trip_tests {
+        ($($name:ident: $ty:ty,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    for &v in &[-1_000., 0., 0.1, 12_345.678, 45_000.] {
+                        let length = Length::new::<foot>(v);
+                        let alt: $ty = length.into();
+                        assert_eq!(alt, <$ty>::interpret(length));
+                        assert_eq!(Length::from(alt), length);
+                        assert_eq!(alt.get::<meter>(), length.get::<meter>());
+                    }
+                }
+            )*
+        };
+    }
+
+    length_round_trip_tests! {
+        geometric_altitude_length_round_trip: GeometricAltitude,
+        geopotential_altitude_length_round_trip: GeopotentialAltitude,
+        pressure_altitude_length_round_trip: PressureAltitude,
+        density_altitude_length_round_trip: DensityAltitude,
+    }
+
+    #[test]
+    fn pressure_altitudes_are_classified_into_bands() {
+        let cases = [
+            (-500., AltitudeBand::SeaLevel),
+            (999., AltitudeBand::SeaLevel),
+            (1_000., AltitudeBand::Low),
+            (8_500., AltitudeBand::Low),
+            (10_000., AltitudeBand::Mid),
+            (24_999., AltitudeBand::Mid),
+            (25_000., AltitudeBand::High),
+            (45_000., AltitudeBand::High),
+        ];
+
+        for &(v, band) in &cases {
+            assert_eq!(band, PressureAltitude::new::<foot>(v).band(), "{} ft", v);
+        }
+    }
+
+    #[test]
+    fn density_altitudes_are_classified_with_custom_thresholds() {
+        let thresholds = AltitudeBandThresholds {
+            low: Length::new::<foot>(0.),
+            mid: Length::new::<foot>(5_000.),
+            high: Length::new::<foot>(18_000.),
+        };
+
+        let classify = |v| DensityAltitude::new::<foot>(v).band_with(&thresholds);
+        assert_eq!(AltitudeBand::SeaLevel, classify(-1.));
+        assert_eq!(AltitudeBand::Low, classify(0.));
+        assert_eq!(AltitudeBand::Mid, classify(12_000.));
+        assert_eq!(AltitudeBand::High, classify(18_000.));
+        assert_eq!(
+            AltitudeBand::Mid,
+            DensityAltitude::new::<foot>(12_000.).band()
+        );
+    }
+
+    #[test]
+    fn density_altitude_matches_pressure_altitude_at_standard_temperature() {
+        for &v in &[-1_000., 0., 5_000., 25_000., 41_000.] {
+            let pressure_altitude = PressureAltitude::new::<foot>(v);
+            let isa =
+                calculations::standard_temperature(GeopotentialAltitude::new::<foot>(v)).unwrap();
+            let density_altitude = DensityAltitude::from_conditions(pressure_altitude, isa);
+            assert!(
+                (density_altitude.get::<foot>() - v).abs() < 1.,
+                "{} ft: {:?}",
+                v,
+                density_altitude
+            );
+        }
+    }
+
+    #[test]
+    fn density_altitude_follows_rule_of_thumb() {
+        use uom::si::temperature_interval::degree_celsius;
+
+        for &(v, deviation) in &[(0., 20.), (5_000., 10.), (5_000., -15.), (8_000., 25.)] {
+            let pressure_altitude = PressureAltitude::new::<foot>(v);
+            let isa =
+                calculations::standard_temperature(GeopotentialAltitude::new::<foot>(v)).unwrap();
+            let oat = isa + TemperatureInterval::new::<degree_celsius>(deviation);
+            let density_altitude = DensityAltitude::from_conditions(pressure_altitude, oat);
+
+            // Density altitude ≈ PA + 120 ft/°C × (OAT - ISA)
+            let rule_of_thumb = v + 120. * deviation;
+            let correction = 120. * deviation;
+            assert!(
+                (density_altitude.get::<foot>() - rule_of_thumb).abs() < 0.1 * correction.abs(),
+                "{} ft, ISA{:+} °C: {:?} vs {} ft",
+                v,
+                deviation,
+                density_altitude,
+                rule_of_thumb
+            );
+        }
+    }
+
+    #[test]
+    fn standard_altimeter_setting_indicates_pressure_altitude() {
+        for &v in &[-1_000., 0., 12_345.678, 45_000.] {
+            let indicated = Length::new::<foot>(v);
+            let pressure_altitude = AltimeterSetting::standard().to_pressure_altitude(indicated);
+            assert!((pressure_altitude.get::<foot>() - v).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn high_altimeter_setting_lowers_pressure_altitude() {
+        let setting = AltimeterSetting::new::<inch_of_mercury>(30.92);
+        let pressure_altitude = setting.to_pressure_altitude(Length::new::<foot>(5_000.));
+
+        // Roughly 1,000 feet per inch of mercury near sea level
+        assert!((pressure_altitude.get::<foot>() - 4_087.).abs() < 10.);
+    }
 }
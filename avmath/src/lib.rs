@@ -15,7 +15,11 @@
 // #[macro_use]
 // extern crate uom;
 
+pub mod airspeed;
 pub mod calculations;
 pub mod constants;
 pub mod isa;
+pub mod readings;
 pub mod si;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
@@ -0,0 +1,52 @@
+//! Atmospheric readings shared between consumers of instrument data
+//!
+//! Aircraft models typically gather instrument readings into a structure of
+//! their own. Implementing [`AtmosphericReadings`] for that structure allows
+//! it to be passed directly to calculations that only need the atmospheric
+//! state.
+
+use crate::airspeed::MachNumber;
+use crate::constants;
+use crate::isa::PressureAltitude;
+use uom::si::f64::*;
+
+/// Readings describing the atmosphere around the aircraft
+pub trait AtmosphericReadings {
+    /// Aircraft speed represented as a ratio of the speed of sound
+    fn mach_number(&self) -> MachNumber;
+
+    /// Ambient air density
+    fn ambient_density(&self) -> MassDensity;
+
+    /// Pressure altitude
+    fn pressure_altitude(&self) -> PressureAltitude;
+}
+
+/// Ratio of the ambient density to the standard density at mean sea level (σ)
+///
+/// ## Examples
+///
+/// ```
+/// # use avmath::{airspeed::MachNumber, constants, isa::PressureAltitude, readings::{density_ratio, AtmosphericReadings}};
+/// # use uom::si::{f64::*, length::foot, ratio::ratio};
+/// struct SeaLevel;
+///
+/// impl AtmosphericReadings for SeaLevel {
+///     fn mach_number(&self) -> MachNumber {
+///         MachNumber::new::<ratio>(0.)
+///     }
+///
+///     fn ambient_density(&self) -> MassDensity {
+///         constants::standard_density_msl()
+///     }
+///
+///     fn pressure_altitude(&self) -> PressureAltitude {
+///         PressureAltitude::new::<foot>(0.)
+///     }
+/// }
+///
+/// assert_eq!(Ratio::new::<ratio>(1.), density_ratio(&SeaLevel));
+/// ```
+pub fn density_ratio<R: AtmosphericReadings + ?Sized>(readings: &R) -> Ratio {
+    readings.ambient_density() / constants::standard_density_msl()
+}
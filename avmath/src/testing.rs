@@ -0,0 +1,96 @@
+//! Utilities for comparing floating-point values in tests
+//!
+//! Available when the `testing` feature is enabled.
+
+/// Compares two values by equalizing their magnitudes and determining whether
+/// the values are equal over the requested number of significant figures
+///
+/// The final digit is allowed to be off by one unit above or below to account for
+/// potential rounding errors.
+///
+/// ## Examples
+///
+/// ```
+/// # use avmath::testing::are_equal_in_significant_figures;
+/// assert!(are_equal_in_significant_figures(1013.25, 1013.24, 5));
+/// assert!(are_equal_in_significant_figures(9.56859e-1, 9.568589e-1, 6));
+/// assert!(!are_equal_in_significant_figures(9.56859e-1, 9.56857e-1, 6));
+/// assert!(!are_equal_in_significant_figures(1013.25, 1012.0, 5));
+/// ```
+pub fn are_equal_in_significant_figures(expected: f64, actual: f64, figures: u8) -> bool {
+    let magnitude_expected = expected.abs().log10();
+    let bonus_expected = if magnitude_expected < 0. { 0. } else { 1. };
+    let normalized_expected = (expected
+        * 10_f64.powf(figures as f64 - magnitude_expected.trunc() - bonus_expected))
+    .round();
+
+    let magnitude_actual = actual.abs().log10();
+    let bonus_actual = if magnitude_actual < 0. { 0. } else { 1. };
+    let normalized_actual =
+        (actual * 10_f64.powf(figures as f64 - magnitude_actual.trunc() - bonus_actual)).round();
+
+    (normalized_expected - normalized_actual).abs() <= 1. // Allows for a rounding error in the
+}
+
+/// Asserts that two values are equal to a certain number of significant figures
+#[track_caller]
+pub fn assert_equal_in_significant_figures(expected: f64, actual: f64, figures: u8) {
+    if !are_equal_in_significant_figures(expected, actual, figures) {
+        println!("Expected: {}", expected);
+        println!("Actual: {}", actual);
+        panic!(
+            "Expected and actual values differ within first {} significant figures",
+            figures
+        );
+    }
+}
+
+/// Checks whether two values are equal within a certain tolerance
+///
+/// ## Examples
+///
+/// ```
+/// # use avmath::testing::are_equal_within_epsilon;
+/// assert!(are_equal_within_epsilon(1.0, 1.05, 0.1));
+/// assert!(!are_equal_within_epsilon(1.0, 1.2, 0.1));
+/// ```
+pub fn are_equal_within_epsilon(expected: f64, actual: f64, epsilon: f64) -> bool {
+    (expected - actual).abs() < epsilon
+}
+
+/// Asserts that two values are equal with a certain error range
+#[track_caller]
+pub fn assert_equal_within_epsilon(expected: f64, actual: f64, epsilon: f64) {
+    if !are_equal_within_epsilon(expected, actual, epsilon) {
+        println!("Expected: {}", expected);
+        println!("Actual: {}", actual);
+        panic!("Expected and actual values differ by more than {}", epsilon);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic]
+    pub fn significant_figures_test_panic() {
+        assert_equal_in_significant_figures(9.56859_e34, 9.56857_e34, 6)
+    }
+
+    #[test]
+    pub fn significant_figures_test_no_panic_beyond_significant() {
+        assert_equal_in_significant_figures(9.56859_e34, 9.568587_e34, 6)
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn significant_figures_test_panic_low_magnitude() {
+        assert_equal_in_significant_figures(9.56859_e-1, 9.56857_e-1, 6)
+    }
+
+    #[test]
+    pub fn significant_figures_test_no_panic_beyond_significant_low_magnitude() {
+        assert_equal_in_significant_figures(9.56859_e-1, 9.568589_e-1, 6)
+    }
+}
@@ -19,3 +19,8 @@ pub use types::*;
 pub mod once_cell {
     pub use once_cell::sync::Lazy;
 }
+
+#[doc(hidden)]
+pub mod simconnect {
+    pub use simconnect_sys::{ffi::DataType, DataDefinition};
+}
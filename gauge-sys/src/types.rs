@@ -2,6 +2,9 @@ use crate::ffi;
 
 /// A unit used with the Gauge API.
 pub trait Unit {
+    /// The name of the unit as understood by the simulator
+    const NAME: &'static str;
+
     /// Obtains a raw FFI identifer for this unit type
     fn as_raw_unit() -> ffi::RawUnit;
 }
@@ -19,11 +22,14 @@ macro_rules! gauge_unit {
         }
 
         impl $crate::Unit for $ty {
+            const NAME: &'static str = $name;
+
             #[inline]
             fn as_raw_unit() -> $crate::ffi::RawUnit {
-                static RAW_UNIT_VALUE: $crate::once_cell::Lazy<$crate::ffi::RawUnit> = $crate::once_cell::Lazy::new(|| unsafe {
-                    $crate::ffi::RawUnit::from_units_enum_str($ty::UNIT_NAME)
-                });
+                static RAW_UNIT_VALUE: $crate::once_cell::Lazy<$crate::ffi::RawUnit> =
+                    $crate::once_cell::Lazy::new(|| unsafe {
+                        $crate::ffi::RawUnit::from_units_enum_str($ty::UNIT_NAME)
+                    });
                 *RAW_UNIT_VALUE
             }
         }
@@ -35,6 +41,10 @@ macro_rules! gauge_unit {
 pub trait AircraftVariable {
     /// The Gauge API Unit
     type Unit: Unit;
+
+    /// The name of the aircraft variable as understood by the simulator
+    const NAME: &'static str;
+
     /// Obtains the raw FFI identifier for this aircraft variable
     fn as_raw_aircraft_variable() -> ffi::RawAircraftVariable;
 }
@@ -57,6 +67,8 @@ macro_rules! aircraft_variable {
         impl $crate::AircraftVariable for $ty {
             type Unit = $unit;
 
+            const NAME: &'static str = $name;
+
             #[inline]
             fn as_raw_aircraft_variable() -> $crate::ffi::RawAircraftVariable {
                 static RAW_UNIT_VALUE: $crate::once_cell::Lazy<$crate::ffi::RawAircraftVariable> =
@@ -74,8 +86,29 @@ macro_rules! aircraft_variable {
 /// Provides access to an aircraft variable that is indexed
 ///
 /// Many variables relating to aircraft engines will be indexed.
+///
+/// When an `epsilon` is provided, a SimConnect data definition is also made
+/// available through a `data_definition` function on the variable, using the
+/// same variable name and unit.
 #[macro_export]
 macro_rules! indexed_aircraft_variable {
+    ($ty:ident ( $unit:ty ): $name:literal; $description:literal; epsilon: $epsilon:expr) => {
+        $crate::indexed_aircraft_variable!($ty($unit): $name; $description);
+
+        impl $ty {
+            /// Produces a SimConnect data definition for the variable at the index specified
+            #[inline]
+            pub const fn data_definition(index: u32) -> $crate::simconnect::DataDefinition {
+                $crate::simconnect::DataDefinition {
+                    name: <Self as $crate::AircraftVariable>::NAME,
+                    index: Some(index),
+                    unit: <$unit as $crate::Unit>::NAME,
+                    datum_type: $crate::simconnect::DataType::Float64,
+                    epsilon: $epsilon,
+                }
+            }
+        }
+    };
     ($ty:ident ( $unit:ty ): $name:literal; $description:literal) => {
         $crate::aircraft_variable!($ty($unit): $name; $description);
 
@@ -90,8 +123,29 @@ macro_rules! indexed_aircraft_variable {
 }
 
 /// Provides access to variables that do not require indexing
+///
+/// When an `epsilon` is provided, a SimConnect data definition is also made
+/// available through a `data_definition` function on the variable, using the
+/// same variable name and unit.
 #[macro_export]
 macro_rules! unindexed_aircraft_variable {
+    ($ty:ident ( $unit:ty ): $name:literal; $description:literal; epsilon: $epsilon:expr) => {
+        $crate::unindexed_aircraft_variable!($ty($unit): $name; $description);
+
+        impl $ty {
+            /// Produces a SimConnect data definition for the variable
+            #[inline]
+            pub const fn data_definition() -> $crate::simconnect::DataDefinition {
+                $crate::simconnect::DataDefinition {
+                    name: <Self as $crate::AircraftVariable>::NAME,
+                    index: None,
+                    unit: <$unit as $crate::Unit>::NAME,
+                    datum_type: $crate::simconnect::DataType::Float64,
+                    epsilon: $epsilon,
+                }
+            }
+        }
+    };
     ($ty:ident ( $unit:ty ): $name:literal; $description:literal) => {
         $crate::aircraft_variable!($ty($unit): $name; $description);
 
@@ -129,18 +183,22 @@ macro_rules! named_variable {
             ///
             /// The value must be convertible into a raw 64-bit float
             #[inline]
-            fn set_raw(value: <Self as $crate::NamedVariable>::Value)
-            {
-                $crate::ffi::RawNamedVariable::set(<Self as $crate::NamedVariable>::as_raw_named_variable(), value.into())
+            fn set_raw(value: <Self as $crate::NamedVariable>::Value) {
+                $crate::ffi::RawNamedVariable::set(
+                    <Self as $crate::NamedVariable>::as_raw_named_variable(),
+                    value.into(),
+                )
             }
 
             /// Reads the variable as a raw value
             ///
             /// The value must be convertible from a raw 64-bit float
             #[inline]
-            fn read_raw() -> <Self as $crate::NamedVariable>::Value
-            {
-                $crate::ffi::RawNamedVariable::get(<Self as $crate::NamedVariable>::as_raw_named_variable()).into()
+            fn read_raw() -> <Self as $crate::NamedVariable>::Value {
+                $crate::ffi::RawNamedVariable::get(
+                    <Self as $crate::NamedVariable>::as_raw_named_variable(),
+                )
+                .into()
             }
         }
 
@@ -149,11 +207,45 @@ macro_rules! named_variable {
 
             #[inline]
             fn as_raw_named_variable() -> $crate::ffi::RawNamedVariable {
-                static RAW_UNIT_VALUE: $crate::once_cell::Lazy<$crate::ffi::RawNamedVariable> = $crate::once_cell::Lazy::new(|| unsafe {
-                    $crate::ffi::RawNamedVariable::register_new($ty::VARIABLE_NAME)
-                });
+                static RAW_UNIT_VALUE: $crate::once_cell::Lazy<$crate::ffi::RawNamedVariable> =
+                    $crate::once_cell::Lazy::new(|| unsafe {
+                        $crate::ffi::RawNamedVariable::register_new($ty::VARIABLE_NAME)
+                    });
                 *RAW_UNIT_VALUE
             }
         }
     };
 }
+
+#[cfg(test)]
+#[allow(dead_code)]
+mod tests {
+    use crate::{AircraftVariable, Unit};
+
+    crate::gauge_unit!(Percent: "Percent"; "A percentage");
+    crate::indexed_aircraft_variable!(Throttle(Percent): "GENERAL ENG THROTTLE LEVER POSITION"; "Engine throttle lever position"; epsilon: 0.5);
+    crate::unindexed_aircraft_variable!(Flaps(Percent): "FLAPS HANDLE PERCENT"; "Flaps handle position"; epsilon: 0.);
+
+    #[test]
+    fn indexed_data_definition_matches_declaration() {
+        let def = Throttle::data_definition(2);
+
+        assert_eq!(def.name, "GENERAL ENG THROTTLE LEVER POSITION");
+        assert_eq!(def.name, <Throttle as AircraftVariable>::NAME);
+        assert_eq!(def.index, Some(2));
+        assert_eq!(def.unit, "Percent");
+        assert_eq!(def.unit, <Percent as Unit>::NAME);
+        assert_eq!(def.datum_type, crate::simconnect::DataType::Float64);
+        assert!((def.epsilon - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn unindexed_data_definition_matches_declaration() {
+        let def = Flaps::data_definition();
+
+        assert_eq!(def.name, <Flaps as AircraftVariable>::NAME);
+        assert_eq!(def.index, None);
+        assert_eq!(def.unit, <Percent as Unit>::NAME);
+        assert_eq!(def.epsilon, 0.);
+    }
+}
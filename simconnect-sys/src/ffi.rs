@@ -48,6 +48,11 @@ extern "C" {
         event_id: RawEventId,
         maskable: bool,
     ) -> HResult;
+    pub fn SimConnect_SubscribeToSystemEvent(
+        handle: SimConnectHandle,
+        event_id: RawEventId,
+        system_event_name: *const c_char,
+    ) -> HResult;
     pub fn SimConnect_SetNotificationGroupPriority(
         handle: SimConnectHandle,
         group_id: RawNotificationGroupId,
@@ -62,6 +67,11 @@ extern "C" {
         unit_size: u32,
         data_set: *const c_void,
     ) -> HResult;
+    pub fn SimConnect_RequestFacilitiesList(
+        handle: SimConnectHandle,
+        list_type: RawFacilityListType,
+        request_id: RawRequestId,
+    ) -> HResult;
 }
 
 type DispatchProc = extern "C" fn(*const ReceiveHeader, u32, *mut c_void);
@@ -116,10 +126,90 @@ pub struct ReceiveEvent {
     pub data: u32,
 }
 
+impl ReceiveEvent {
+    /// Interprets the event data as a signed axis value, as sent with
+    /// `AXIS_*_SET` events
+    pub fn as_axis_i32(&self) -> i32 {
+        self.data as i32
+    }
+
+    /// Interprets the event data as an unsigned set value, as sent with
+    /// `*_SET` events
+    pub fn as_throttle_u32(&self) -> u32 {
+        self.data
+    }
+}
+
+/// The header of a list of facilities, followed by `array_size` entries
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct ReceiveFacilitiesList {
+    pub header: ReceiveHeader,
+    pub request_id: RawRequestId,
+    pub array_size: u32,
+    pub entry_number: u32,
+    pub out_of: u32,
+}
+
+/// An airport within the reality bubble, as listed in an airport list
+///
+/// SimConnect packs facility entries without padding, so fields must be
+/// copied out rather than borrowed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(C, packed)]
+pub struct FacilityAirport {
+    pub icao: [u8; 9],
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Elevation of the airport in meters
+    pub altitude: f64,
+}
+
+impl FacilityAirport {
+    pub fn icao(&self) -> &str {
+        let null = self
+            .icao
+            .iter()
+            .copied()
+            .position(|x| x == 0x00)
+            .unwrap_or(self.icao.len());
+        let slice = &self.icao[0..null];
+        std::str::from_utf8(slice).expect("ICAO identifier should be valid UTF-8")
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct RawDataDefinitionId(pub u32);
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct RawRequestId(pub u32);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct RawFacilityListType(u32);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum FacilityListType {
+    Airport,
+    Waypoint,
+    Ndb,
+    Vor,
+}
+
+impl FacilityListType {
+    #[inline]
+    pub fn to_ffi(self) -> RawFacilityListType {
+        self.to_u32().map(RawFacilityListType).unwrap()
+    }
+
+    #[inline]
+    pub fn from_ffi(raw: RawFacilityListType) -> Option<Self> {
+        Self::from_u32(raw.0)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct RawObjectId(u32);
@@ -274,6 +364,23 @@ impl DataType {
     pub fn from_ffi(raw: RawDataType) -> Option<Self> {
         Self::from_u32(raw.0)
     }
+
+    /// The size in bytes of a datum of this type
+    ///
+    /// Variable-length strings and structured types do not have a size that
+    /// can be checked, and return `None`.
+    pub fn size(self) -> Option<usize> {
+        match self {
+            Self::Int32 | Self::Float32 => Some(4),
+            Self::Int64 | Self::Float64 | Self::String8 => Some(8),
+            Self::String32 => Some(32),
+            Self::String64 => Some(64),
+            Self::String128 => Some(128),
+            Self::String256 => Some(256),
+            Self::String260 => Some(260),
+            _ => None,
+        }
+    }
 }
 
 impl Default for DataType {
@@ -351,3 +458,49 @@ impl fmt::Display for HResult {
         write!(f, "{:08x}{}", self.0, info)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event_with_data(data: u32) -> ReceiveEvent {
+        ReceiveEvent {
+            header: ReceiveHeader {
+                size: size_of::<ReceiveEvent>() as u32,
+                version: 0,
+                message_type: RawMessageType(MessageType::Event as u32),
+            },
+            group_id: RawNotificationGroupId(0),
+            event_id: RawEventId(0),
+            data,
+        }
+    }
+
+    #[test]
+    fn axis_data_is_signed() {
+        assert_eq!(0, event_with_data(0).as_axis_i32());
+        assert_eq!(16384, event_with_data(16384).as_axis_i32());
+        assert_eq!(-16384, event_with_data(-16384_i32 as u32).as_axis_i32());
+        assert_eq!(-1, event_with_data(u32::MAX).as_axis_i32());
+    }
+
+    #[test]
+    fn facility_list_types_match_simconnect_ids() {
+        assert_eq!(RawFacilityListType(0), FacilityListType::Airport.to_ffi());
+        assert_eq!(RawFacilityListType(3), FacilityListType::Vor.to_ffi());
+        assert_eq!(
+            Some(FacilityListType::Waypoint),
+            FacilityListType::from_ffi(RawFacilityListType(1))
+        );
+        assert_eq!(None, FacilityListType::from_ffi(RawFacilityListType(4)));
+        assert_eq!(RawMessageType(18), MessageType::AirportList.to_ffi());
+        assert_eq!(33, size_of::<FacilityAirport>());
+    }
+
+    #[test]
+    fn throttle_data_covers_full_unsigned_range() {
+        assert_eq!(0, event_with_data(0).as_throttle_u32());
+        assert_eq!(16383, event_with_data(16383).as_throttle_u32());
+        assert_eq!(u32::MAX, event_with_data(u32::MAX).as_throttle_u32());
+    }
+}
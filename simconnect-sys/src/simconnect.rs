@@ -6,6 +6,7 @@ use std::ffi::{CStr, CString};
 #[derive(Debug)]
 pub struct SimConnect {
     raw: ffi::SimConnectHandle,
+    max_messages_per_dispatch: Option<usize>,
 }
 
 impl SimConnect {
@@ -29,12 +30,26 @@ impl SimConnect {
         };
 
         if result.is_success() {
-            Ok(SimConnect { raw: handle })
+            Ok(SimConnect {
+                raw: handle,
+                max_messages_per_dispatch: None,
+            })
         } else {
             Err(result)
         }
     }
 
+    /// Limits the number of messages handled by a single call to
+    /// [`dispatch`](Self::dispatch)
+    ///
+    /// Any messages remaining once the limit has been reached are left
+    /// queued for the next call. This bounds the time spent dispatching when
+    /// a large number of events arrive at once.
+    pub fn with_max_messages_per_dispatch(mut self, max: usize) -> Self {
+        self.max_messages_per_dispatch = Some(max);
+        self
+    }
+
     /// Registers a notification group with the SimConnect API
     ///
     /// Defines the types of messages and groups that SimConnect
@@ -51,6 +66,34 @@ impl SimConnect {
         Ok(())
     }
 
+    /// Subscribes to the system events declared by an event type
+    ///
+    /// System events, such as `SimStart`, are delivered outside of any
+    /// notification group, using the definition's name as the system event
+    /// name.
+    pub fn subscribe_to_system_events<E: EventType>(&self) -> Result<(), ffi::HResult> {
+        for def in E::event_definitions() {
+            let n = if let Ok(value) = CStr::from_bytes_with_nul(def.name.as_bytes()) {
+                Cow::Borrowed(value)
+            } else {
+                Cow::Owned(CString::new(def.name).unwrap())
+            };
+
+            unsafe {
+                let result = ffi::SimConnect_SubscribeToSystemEvent(
+                    self.raw,
+                    def.event.to_ffi(),
+                    n.as_ptr(),
+                );
+                if !result.is_success() {
+                    println!("Error subscribing to system event");
+                    return Err(result);
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn register_notification_group<G: NotificationGroup>(
         &self,
         group_def: &NotificationGroupDefinition<G>,
@@ -102,9 +145,20 @@ impl SimConnect {
     ///
     /// Used to define the shape of data that will be sent to the
     /// SimConnect API from the client.
+    ///
+    /// The group is rejected if the declared data definitions do not match
+    /// the size of the group's data structure.
     pub fn register_data_definition<G: DataDefinitionGroup>(&self) -> Result<(), ffi::HResult> {
+        if let Err(mismatch) = G::check_size() {
+            println!("Error registering data definition: {}", mismatch);
+            debug_assert!(false, "{}", mismatch);
+            return Err(ffi::HResult::E_FAIL);
+        }
+
         for def in G::data_definitions() {
-            let n = if let Ok(value) = CStr::from_bytes_with_nul(def.name.as_bytes()) {
+            let n = if let Some(index) = def.index {
+                Cow::Owned(CString::new(format!("{}:{}", def.name, index)).unwrap())
+            } else if let Ok(value) = CStr::from_bytes_with_nul(def.name.as_bytes()) {
                 Cow::Borrowed(value)
             } else {
                 Cow::Owned(CString::new(def.name).unwrap())
@@ -123,7 +177,7 @@ impl SimConnect {
                     n.as_ptr(),
                     u.as_ptr(),
                     def.datum_type.to_ffi(),
-                    0.,
+                    def.epsilon,
                     UNSPECIFIED,
                 );
                 if !result.is_success() {
@@ -145,7 +199,7 @@ impl SimConnect {
                 ffi::RawObjectId::USER,
                 ffi::DataSetFlag::Default.to_ffi(),
                 0,
-                std::mem::size_of::<D>() as u32,
+                size_of::<D>() as u32,
                 data as *const D as *const std::ffi::c_void,
             );
             if !result.is_success() {
@@ -156,8 +210,37 @@ impl SimConnect {
         Ok(())
     }
 
+    /// Requests a list of the facilities of a given type within the reality
+    /// bubble
+    ///
+    /// Airport lists are delivered to
+    /// [`SimConnectDispatcher::handle_airport_list`], tagged with
+    /// `request_id`. Large lists may arrive across several messages.
+    pub fn request_facilities_list(
+        &self,
+        list_type: ffi::FacilityListType,
+        request_id: ffi::RawRequestId,
+    ) -> Result<(), ffi::HResult> {
+        unsafe {
+            let result =
+                ffi::SimConnect_RequestFacilitiesList(self.raw, list_type.to_ffi(), request_id);
+            if !result.is_success() {
+                println!("Error requesting a facilities list");
+                return Err(result);
+            }
+        }
+        Ok(())
+    }
+
     /// Requests a next message from the SimConnect API
-    pub fn dispatch<D: std::fmt::Debug + SimConnectDispatcher>(&self, dispatcher: &mut D) {
+    ///
+    /// Messages are dispatched until none remain or the configured maximum
+    /// number of messages has been handled. An error is returned if
+    /// SimConnect fails for any reason other than having no more messages.
+    pub fn dispatch<D: std::fmt::Debug + SimConnectDispatcher>(
+        &self,
+        dispatcher: &mut D,
+    ) -> Result<(), ffi::HResult> {
         // The `CallDispatch` API is currently broken. In the meantime, the
         // `GetNextDispatch` API is being used as an alternative.
 
@@ -171,41 +254,92 @@ impl SimConnect {
         //     ffi::SimConnect_CallDispatch(self.raw, handle_dispatch_callback::<D>, dispatcher as *mut D as *mut std::ffi::c_void);
         // }
 
+        dispatch_messages(
+            || self.next_dispatch(),
+            dispatcher,
+            self.max_messages_per_dispatch,
+        )
+    }
+
+    /// Gets the next queued message, or `None` when no messages are queued
+    fn next_dispatch(&self) -> Result<Option<(*const ffi::ReceiveHeader, u32)>, ffi::HResult> {
         let mut header_ptr: *const ffi::ReceiveHeader = std::ptr::null();
         let mut size = 0_u32;
-        #[allow(unused_variables)]
-        let mut loops = 0_usize;
 
-        loop {
-            unsafe {
-                let result = ffi::SimConnect_GetNextDispatch(
-                    self.raw,
-                    (&mut header_ptr) as *mut *const ffi::ReceiveHeader,
-                    &mut size as *mut u32,
-                );
+        let result = unsafe {
+            ffi::SimConnect_GetNextDispatch(
+                self.raw,
+                (&mut header_ptr) as *mut *const ffi::ReceiveHeader,
+                &mut size as *mut u32,
+            )
+        };
 
-                if !result.is_success() {
-                    if result != ffi::HResult::E_FAIL {
-                        println!(
-                            "Error when trying to get next dispatch: {:#08x}",
-                            result.raw()
-                        );
-                    }
-                    break;
-                } else {
-                    loops += 1;
-
-                    if handle_dispatch(header_ptr, size, dispatcher) == Loop::Break {
-                        break;
-                    }
-                }
-            }
+        if result.is_success() {
+            Ok(Some((header_ptr, size)))
+        } else if result == ffi::HResult::E_FAIL {
+            Ok(None)
+        } else {
+            println!(
+                "Error when trying to get next dispatch: {:#08x}",
+                result.raw()
+            );
+            Err(result)
         }
+    }
+}
+
+/// Notes whether SimConnect has confirmed that a connection is open
+///
+/// `SimConnect_Open` returns before the connection is ready for use, which is
+/// signalled by an `Open` message. A dispatcher can forward
+/// [`handle_open`](SimConnectDispatcher::handle_open) to a watcher while
+/// handling every other message as usual, deferring any setup that needs the
+/// connection until [`is_open`](Self::is_open).
+#[derive(Debug, Default)]
+pub struct OpenWatcher {
+    opened: bool,
+}
+
+impl OpenWatcher {
+    /// Whether the `Open` message has been received
+    pub fn is_open(&self) -> bool {
+        self.opened
+    }
+}
 
-        //println!("Handled {} messages", loops);
+impl SimConnectDispatcher for OpenWatcher {
+    fn handle_open(&mut self, _event: &ffi::ReceiveOpen) {
+        self.opened = true;
     }
 }
 
+/// Handles messages obtained from `next_message` until none remain, handling
+/// at most `max_messages` when provided
+///
+/// `next_message` returns a pointer to the next message and its size, or
+/// `None` when no messages are queued.
+fn dispatch_messages<D: std::fmt::Debug + SimConnectDispatcher>(
+    mut next_message: impl FnMut() -> Result<Option<(*const ffi::ReceiveHeader, u32)>, ffi::HResult>,
+    dispatcher: &mut D,
+    max_messages: Option<usize>,
+) -> Result<(), ffi::HResult> {
+    let mut handled = 0;
+
+    while max_messages.is_none_or(|max| handled < max) {
+        let (header_ptr, size) = match next_message()? {
+            Some(message) => message,
+            None => break,
+        };
+        handled += 1;
+
+        if handle_dispatch(header_ptr, size, dispatcher) == Loop::Break {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Loop {
     Continue,
@@ -254,10 +388,32 @@ fn handle_dispatch<D: std::fmt::Debug + SimConnectDispatcher>(
 /// Tread carefully. This is basically std::mem::transmute with a size check.
 /// `ptr` is assumed to be non-null.
 unsafe fn convert_with_static_size<T>(ptr: &*const ffi::ReceiveHeader, size: u32) -> &T {
-    assert_eq!(std::mem::size_of::<T>(), size as usize);
+    assert_eq!(size_of::<T>(), size as usize);
     &*(*ptr as *const T)
 }
 
+/// ## Safety
+///
+/// `ptr` is assumed to be non-null and followed by `size` readable bytes.
+unsafe fn convert_facilities_list<T>(
+    ptr: &*const ffi::ReceiveHeader,
+    size: u32,
+) -> (&ffi::ReceiveFacilitiesList, &[T]) {
+    let header_size = size_of::<ffi::ReceiveFacilitiesList>();
+    assert!(size as usize >= header_size);
+    let list = &*(*ptr as *const ffi::ReceiveFacilitiesList);
+    assert_eq!(
+        header_size + list.array_size as usize * size_of::<T>(),
+        size as usize
+    );
+
+    let entries = (*ptr as *const u8).add(header_size) as *const T;
+    (
+        list,
+        std::slice::from_raw_parts(entries, list.array_size as usize),
+    )
+}
+
 /// ## Safety
 ///
 /// * `header_ptr` is assumed to be non-null
@@ -305,6 +461,11 @@ unsafe fn handle_dispatch_inner<D: std::fmt::Debug + SimConnectDispatcher>(
             );
             dispatcher.handle_open(message);
         }
+        ffi::MessageType::AirportList => {
+            let (list, airports) =
+                convert_facilities_list::<ffi::FacilityAirport>(&header_ptr, size);
+            dispatcher.handle_airport_list(list, airports);
+        }
         ignored => {
             println!("Nothing to do for {:?}!", ignored);
         }
@@ -321,6 +482,40 @@ pub trait SimConnectDispatcher {
 
     /// Receives an event with new data
     fn handle_event(&mut self, event: &ffi::ReceiveEvent) {}
+
+    /// Receives a list of airports requested with
+    /// [`SimConnect::request_facilities_list`]
+    fn handle_airport_list(
+        &mut self,
+        list: &ffi::ReceiveFacilitiesList,
+        airports: &[ffi::FacilityAirport],
+    ) {
+    }
+}
+
+/// Finds the airport closest to a position given in degrees
+///
+/// Distances are compared on an equirectangular projection, which is
+/// sufficient to rank the airports within the reality bubble.
+pub fn nearest_airport(
+    airports: &[ffi::FacilityAirport],
+    latitude: f64,
+    longitude: f64,
+) -> Option<&ffi::FacilityAirport> {
+    let distance = |airport: &ffi::FacilityAirport| {
+        let (airport_latitude, airport_longitude) = (airport.latitude, airport.longitude);
+        let d_latitude = airport_latitude - latitude;
+        let mut d_longitude = (airport_longitude - longitude).abs();
+        if d_longitude > 180. {
+            d_longitude = 360. - d_longitude;
+        }
+        let d_longitude = d_longitude * latitude.to_radians().cos();
+        d_latitude * d_latitude + d_longitude * d_longitude
+    };
+
+    airports
+        .iter()
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
 }
 
 const UNSPECIFIED: u32 = 0xFFFFFFFF;
@@ -340,11 +535,20 @@ pub struct DataDefinition {
     /// The name of the variable to be updated
     pub name: &'static str,
 
+    /// The index of the variable, if it is an indexed variable
+    ///
+    /// When present, the index is appended to the name as `NAME:index`.
+    pub index: Option<u32>,
+
     /// The name of the unit type for the variable
     pub unit: &'static str,
 
     /// The data type used for passing the data value
     pub datum_type: ffi::DataType,
+
+    /// The minimum change in value required before SimConnect will consider
+    /// the value to have changed
+    pub epsilon: f64,
 }
 
 /// A group of data definitions that can be registered simultaneously
@@ -358,12 +562,55 @@ pub trait DataDefinitionGroup: Sized {
     /// Iterates through the data definitions
     fn data_definitions() -> Self::DataDefsIter;
 
+    /// Verifies that the sizes of the declared data definitions add up to the
+    /// size of the group's data structure
+    ///
+    /// Data is sent to SimConnect as the raw bytes of the structure, so a
+    /// mismatch would silently corrupt the values received. Groups containing
+    /// definitions without a known size cannot be checked and are assumed to
+    /// be valid.
+    fn check_size() -> Result<(), DataDefinitionSizeMismatch> {
+        let declared: Option<usize> = Self::data_definitions()
+            .into_iter()
+            .map(|def| def.datum_type.size())
+            .sum();
+        let actual = size_of::<Self>();
+
+        match declared {
+            Some(declared) if declared != actual => {
+                Err(DataDefinitionSizeMismatch { declared, actual })
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Registers the data definitions with a SimConnect instance
     fn register(simconnect: &SimConnect) -> Result<(), ffi::HResult> {
         simconnect.register_data_definition::<Self>()
     }
 }
 
+/// The declared data definitions for a group do not match the size of the
+/// group's data structure
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DataDefinitionSizeMismatch {
+    /// The sum of the sizes of the declared data definitions
+    pub declared: usize,
+
+    /// The size of the data structure
+    pub actual: usize,
+}
+
+impl std::fmt::Display for DataDefinitionSizeMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "declared data definitions total {} bytes, but the data structure is {} bytes",
+            self.declared, self.actual
+        )
+    }
+}
+
 /// A notification group definition
 #[derive(Debug)]
 pub struct NotificationGroupDefinition<Group> {
@@ -419,3 +666,313 @@ pub trait EventType: Sized + 'static {
     /// Iterates through the event definitions
     fn event_definitions() -> Self::EventsIter;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct CountingDispatcher {
+        events: Vec<u32>,
+        open: OpenWatcher,
+    }
+
+    impl SimConnectDispatcher for CountingDispatcher {
+        fn handle_open(&mut self, event: &ffi::ReceiveOpen) {
+            self.open.handle_open(event);
+        }
+
+        fn handle_event(&mut self, event: &ffi::ReceiveEvent) {
+            self.events.push(event.data);
+        }
+    }
+
+    fn queued_events(count: u32) -> Vec<ffi::ReceiveEvent> {
+        (0..count)
+            .map(|data| ffi::ReceiveEvent {
+                header: ffi::ReceiveHeader {
+                    size: size_of::<ffi::ReceiveEvent>() as u32,
+                    version: 0,
+                    message_type: ffi::RawMessageType(ffi::MessageType::Event as u32),
+                },
+                group_id: ffi::RawNotificationGroupId(0),
+                event_id: ffi::RawEventId(0),
+                data,
+            })
+            .collect()
+    }
+
+    fn dispatch_queue(
+        queue: &[ffi::ReceiveEvent],
+        position: &mut usize,
+        dispatcher: &mut CountingDispatcher,
+        max_messages: Option<usize>,
+    ) {
+        dispatch_messages(
+            || {
+                let message = queue.get(*position).map(|event| {
+                    (
+                        event as *const ffi::ReceiveEvent as *const ffi::ReceiveHeader,
+                        event.header.size,
+                    )
+                });
+                *position += message.is_some() as usize;
+                Ok(message)
+            },
+            dispatcher,
+            max_messages,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn dispatch_handles_all_queued_messages_without_cap() {
+        let queue = queued_events(50);
+        let mut position = 0;
+        let mut dispatcher = CountingDispatcher::default();
+
+        dispatch_queue(&queue, &mut position, &mut dispatcher, None);
+
+        assert_eq!((0..50).collect::<Vec<_>>(), dispatcher.events);
+    }
+
+    #[test]
+    fn dispatch_defers_messages_beyond_cap() {
+        let queue = queued_events(50);
+        let mut position = 0;
+        let mut dispatcher = CountingDispatcher::default();
+
+        dispatch_queue(&queue, &mut position, &mut dispatcher, Some(16));
+        assert_eq!((0..16).collect::<Vec<_>>(), dispatcher.events);
+
+        dispatch_queue(&queue, &mut position, &mut dispatcher, Some(16));
+        dispatch_queue(&queue, &mut position, &mut dispatcher, Some(16));
+        dispatch_queue(&queue, &mut position, &mut dispatcher, Some(16));
+        assert_eq!((0..50).collect::<Vec<_>>(), dispatcher.events);
+    }
+
+    #[test]
+    fn dispatch_surfaces_errors() {
+        let mut dispatcher = CountingDispatcher::default();
+        let result = dispatch_messages(|| Err(ffi::HResult::E_FAIL), &mut dispatcher, None);
+        assert_eq!(Err(ffi::HResult::E_FAIL), result);
+    }
+
+    fn open_message() -> ffi::ReceiveOpen {
+        let version = ffi::Version {
+            version_major: 0,
+            version_minor: 0,
+            build_major: 0,
+            build_minor: 0,
+        };
+        ffi::ReceiveOpen {
+            header: ffi::ReceiveHeader {
+                size: size_of::<ffi::ReceiveOpen>() as u32,
+                version: 0,
+                message_type: ffi::RawMessageType(ffi::MessageType::Open as u32),
+            },
+            application_name: [0; 256],
+            application_version: version,
+            simconnect_version: version,
+            reserved1: 0,
+            reserved2: 0,
+        }
+    }
+
+    #[test]
+    fn open_is_watched_without_dropping_other_messages() {
+        let events = queued_events(3);
+        let open = open_message();
+        let mut queue = events
+            .iter()
+            .map(|event| {
+                (
+                    event as *const ffi::ReceiveEvent as *const ffi::ReceiveHeader,
+                    event.header.size,
+                )
+            })
+            .collect::<Vec<_>>();
+        queue.insert(
+            2,
+            (
+                &open as *const ffi::ReceiveOpen as *const ffi::ReceiveHeader,
+                open.header.size,
+            ),
+        );
+        let mut position = 0;
+        let mut dispatcher = CountingDispatcher::default();
+        let mut dispatch = |dispatcher: &mut CountingDispatcher| {
+            dispatch_messages(
+                || {
+                    let message = queue.get(position).copied();
+                    position += message.is_some() as usize;
+                    Ok(message)
+                },
+                dispatcher,
+                Some(2),
+            )
+        };
+
+        assert_eq!(Ok(()), dispatch(&mut dispatcher));
+        assert!(!dispatcher.open.is_open());
+        assert_eq!(vec![0, 1], dispatcher.events);
+
+        assert_eq!(Ok(()), dispatch(&mut dispatcher));
+        assert!(dispatcher.open.is_open());
+        assert_eq!(vec![0, 1, 2], dispatcher.events);
+    }
+
+    #[derive(Debug, Default)]
+    struct AirportDispatcher {
+        request_id: Option<ffi::RawRequestId>,
+        airports: Vec<(String, f64)>,
+    }
+
+    impl SimConnectDispatcher for AirportDispatcher {
+        fn handle_airport_list(
+            &mut self,
+            list: &ffi::ReceiveFacilitiesList,
+            airports: &[ffi::FacilityAirport],
+        ) {
+            self.request_id = Some(list.request_id);
+            self.airports.extend(
+                airports
+                    .iter()
+                    .map(|a| (a.icao().to_owned(), { a.altitude })),
+            );
+        }
+    }
+
+    fn airport(icao: &str, latitude: f64, longitude: f64, altitude: f64) -> ffi::FacilityAirport {
+        let mut ident = [0_u8; 9];
+        ident[..icao.len()].copy_from_slice(icao.as_bytes());
+        ffi::FacilityAirport {
+            icao: ident,
+            latitude,
+            longitude,
+            altitude,
+        }
+    }
+
+    #[repr(C)]
+    struct AirportList {
+        list: ffi::ReceiveFacilitiesList,
+        airports: [ffi::FacilityAirport; 2],
+    }
+
+    #[test]
+    fn airport_list_is_delivered_to_dispatcher() {
+        let message = AirportList {
+            list: ffi::ReceiveFacilitiesList {
+                header: ffi::ReceiveHeader {
+                    size: (size_of::<ffi::ReceiveFacilitiesList>()
+                        + 2 * size_of::<ffi::FacilityAirport>()) as u32,
+                    version: 0,
+                    message_type: ffi::MessageType::AirportList.to_ffi(),
+                },
+                request_id: ffi::RawRequestId(7),
+                array_size: 2,
+                entry_number: 0,
+                out_of: 1,
+            },
+            airports: [
+                airport("KSEA", 47.449, -122.309, 132.6),
+                airport("KBFI", 47.530, -122.302, 5.5),
+            ],
+        };
+
+        let mut sent = false;
+        let mut dispatcher = AirportDispatcher::default();
+        dispatch_messages(
+            || {
+                let next = (!sent).then_some((
+                    &message as *const AirportList as *const ffi::ReceiveHeader,
+                    message.list.header.size,
+                ));
+                sent = true;
+                Ok(next)
+            },
+            &mut dispatcher,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(Some(ffi::RawRequestId(7)), dispatcher.request_id);
+        assert_eq!(
+            vec![("KSEA".to_owned(), 132.6), ("KBFI".to_owned(), 5.5)],
+            dispatcher.airports
+        );
+    }
+
+    #[test]
+    fn nearest_airport_is_found_by_position() {
+        let airports = [
+            airport("KSEA", 47.449, -122.309, 132.6),
+            airport("KBFI", 47.530, -122.302, 5.5),
+        ];
+
+        let nearest = nearest_airport(&airports, 47.52, -122.31).unwrap();
+        assert_eq!("KBFI", nearest.icao());
+        assert_eq!(None, nearest_airport(&[], 47.52, -122.31));
+    }
+
+    const FLOAT: DataDefinition = DataDefinition {
+        name: "GENERAL ENG THROTTLE LEVER POSITION",
+        index: Some(1),
+        unit: "Percent",
+        datum_type: ffi::DataType::Float64,
+        epsilon: 0.,
+    };
+
+    #[repr(C)]
+    struct Matched {
+        _throttle_1: f64,
+        _throttle_2: f64,
+    }
+
+    impl DataDefinitionGroup for Matched {
+        type DataDefsIter = &'static [DataDefinition];
+
+        fn group_id() -> ffi::RawDataDefinitionId {
+            ffi::RawDataDefinitionId(0)
+        }
+
+        fn data_definitions() -> Self::DataDefsIter {
+            &[FLOAT, FLOAT]
+        }
+    }
+
+    #[repr(C)]
+    struct Mismatched {
+        _throttle_1: f64,
+        _throttle_2: f64,
+    }
+
+    impl DataDefinitionGroup for Mismatched {
+        type DataDefsIter = &'static [DataDefinition];
+
+        fn group_id() -> ffi::RawDataDefinitionId {
+            ffi::RawDataDefinitionId(1)
+        }
+
+        fn data_definitions() -> Self::DataDefsIter {
+            &[FLOAT]
+        }
+    }
+
+    #[test]
+    fn matched_group_passes_size_check() {
+        assert_eq!(Ok(()), Matched::check_size());
+    }
+
+    #[test]
+    fn mismatched_group_fails_size_check() {
+        assert_eq!(
+            Err(DataDefinitionSizeMismatch {
+                declared: 8,
+                actual: 16
+            }),
+            Mismatched::check_size()
+        );
+    }
+}
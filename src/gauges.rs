@@ -1,33 +1,112 @@
 use crate::interop;
 use simconnect_sys::{ffi::HResult, EventType, NotificationGroup};
-use std::sync::Arc;
-use uom::si::{f64::Time, time::second};
+use std::{fmt, sync::Arc};
+use uom::num_traits::clamp;
+use uom::si::{f64::Time, force::poundal, time::second};
 use wt_cj4::{
-    control_params::{ThrottleAxis, ThrottleMode, ThrottlePercent},
+    control_params::{ThrottleAxis, ThrottleAxisStep, ThrottleMode, ThrottlePercent, ThrustValue},
     engines::{EngineData, EngineNumber},
-    Aircraft, EngineReadings, Environment, Instruments, Snapshot,
+    Aircraft, AircraftSystems, CommandedOutputs, Engine, EngineReadings, Environment,
+    FadecController, Instruments, RawAxisEvent, Snapshot, SystemOutputs, ThrustAsymmetry,
+    SNAPSHOT_SCHEMA_VERSION,
 };
 
+/// The stage of a gauge update that failed
+#[derive(Debug)]
+#[must_use]
+pub enum UpdateError {
+    /// Dispatching messages received from SimConnect failed
+    Dispatch(HResult),
+
+    /// Registering with SimConnect once the connection opened failed
+    Register(HResult),
+
+    /// The aircraft systems could not be stepped with the non-finite frame
+    /// time provided
+    Step(Time),
+
+    /// Sending throttle commands to the simulator through SimConnect failed
+    SimConnectUpdate(HResult),
+}
+
+impl fmt::Display for UpdateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Dispatch(err) => write!(f, "failed to dispatch SimConnect messages: {}", err),
+            Self::Register(err) => write!(f, "failed to register with SimConnect: {}", err),
+            Self::Step(delta_t) => write!(
+                f,
+                "cannot step systems with a frame time of {} s",
+                delta_t.get::<second>()
+            ),
+            Self::SimConnectUpdate(err) => {
+                write!(f, "failed to update SimConnect user data: {}", err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for UpdateError {}
+
+/// The most SimConnect messages handled in a single frame before deferring the
+/// remainder to the next frame
+const MAX_MESSAGES_PER_FRAME: usize = 64;
+
+/// A FADEC configuration applied to both engines when present
+const FADEC_CONFIG_PATH: &str = r#"\work\fadec.json"#;
+
+/// The default minimum simulation time between steps of the control loop,
+/// in seconds
+///
+/// By default, the control loop is stepped on every frame. A longer interval
+/// can be set at runtime through `FADEC_CONTROL_INTERVAL`, in which case frames
+/// arriving faster than the interval are accumulated into the next step.
+pub const DEFAULT_MIN_CONTROL_INTERVAL: f64 = 0.;
+
 #[derive(Debug)]
 pub struct FdGauge {
     simconnect: Arc<simconnect_sys::SimConnect>,
-    state: Aircraft,
+    open_watcher: simconnect_sys::OpenWatcher,
+    registered: bool,
+    systems: AircraftSystems,
     sim_start: Option<Time>,
     recorder: Option<wt_flight_recorder::FlightDataRecorder<Snapshot>>,
+    axis_recorder: Option<wt_flight_recorder::FlightDataRecorder<RawAxisEvent>>,
+    axis_events: AxisEventLog,
+    record_sampler: RecordSampler,
+    pending_marker: Option<u32>,
+    min_control_interval: Time,
+    control_limiter: ControlRateLimiter,
+    dropped_publishes: DroppedPublishes,
 }
 
 impl FdGauge {
     pub fn new() -> Result<Self, HResult> {
-        let simconnect = Arc::new(simconnect_sys::SimConnect::new("FdGauge")?);
+        let simconnect = Arc::new(
+            simconnect_sys::SimConnect::new("FdGauge")?
+                .with_max_messages_per_dispatch(MAX_MESSAGES_PER_FRAME),
+        );
 
-        simconnect.register_notification_group_enum::<interop::NotificationGroup>()?;
-        simconnect.register_data_definition::<interop::EngineDataControl>()?;
+        let mut aircraft =
+            Aircraft::new_with_lever(interop::Throttle::read_by_index(EngineNumber::Engine1));
+        if let Some(fadec) = load_fadec_config() {
+            aircraft.engines.update(|_, e| e.fadec = fadec);
+        }
 
         let gauge = FdGauge {
             simconnect,
-            state: Aircraft::default(),
+            open_watcher: simconnect_sys::OpenWatcher::default(),
+            registered: false,
+            systems: AircraftSystems::new(aircraft),
             sim_start: None,
             recorder: None,
+            axis_recorder: None,
+            axis_events: AxisEventLog::default(),
+            record_sampler: RecordSampler::default(),
+            pending_marker: None,
+            min_control_interval: Time::new::<second>(DEFAULT_MIN_CONTROL_INTERVAL),
+            control_limiter: ControlRateLimiter::default(),
+            dropped_publishes: DroppedPublishes::default(),
         };
 
         println!("All set up: {:?}", gauge);
@@ -35,15 +114,47 @@ impl FdGauge {
         Ok(gauge)
     }
 
-    pub fn on_update(&mut self, draw_data: &gauge_sys::ffi::GaugeDrawData) -> Result<(), ()> {
+    /// Resets the named variables written by the gauge to the state of a
+    /// freshly loaded panel
+    ///
+    /// Named variables persist across a kill and reinstall of the gauge, and
+    /// are otherwise only rewritten after the first update of the new gauge.
+    pub fn reset_persisted_state() {
+        set_named_variables(&CommandedOutputs::default());
+    }
+
+    pub fn on_update(
+        &mut self,
+        draw_data: &gauge_sys::ffi::GaugeDrawData,
+    ) -> Result<(), UpdateError> {
         {
             let sc = Arc::clone(&self.simconnect);
-            sc.dispatch(self);
+            sc.dispatch(self).map_err(UpdateError::Dispatch)?;
             // let mut dispatcher = FdGaugeDispatcher(self);
             // self.simconnect.dispatch(&mut dispatcher);
         }
 
-        let delta_t = Time::new::<second>(draw_data.dt);
+        // Nothing can be sent to the simulator until SimConnect confirms that
+        // the connection is open, which may take several frames
+        if !self.registered {
+            if !self.open_watcher.is_open() {
+                return Ok(());
+            }
+            // Registration is only attempted once, as a partial registration
+            // cannot be repeated
+            self.registered = true;
+            register(&self.simconnect).map_err(UpdateError::Register)?;
+        }
+
+        let delta_t = match frame_time(draw_data.dt)? {
+            Some(delta_t) => delta_t,
+            None => return Ok(()),
+        };
+        let interval = interop::ControlInterval::read().unwrap_or(self.min_control_interval);
+        let delta_t = match self.control_limiter.step(delta_t, interval) {
+            Some(delta_t) => delta_t,
+            None => return Ok(()),
+        };
         let start_time = *self
             .sim_start
             .get_or_insert(Time::new::<second>(draw_data.t));
@@ -53,14 +164,16 @@ impl FdGauge {
             mach_number: interop::AirspeedMach::read(),
             ambient_density: interop::AmbientDensity::read(),
             geometric_altitude: interop::GeometricAltitude::read(),
-            pressure_altitude: interop::PressureAltitude::read(),
+            pressure_altitude: interop::PressureAltitude::read_with_fallback(),
             airspeed_indicated: interop::AirspeedIndicated::read(),
             airspeed_true: interop::AirspeedTrue::read(),
             vertical_speed: interop::VerticalSpeed::read(),
+            on_ground: interop::OnGround::read(),
         };
 
         let engines = EngineData::new_from(|e| EngineReadings {
             thrust: interop::Thrust::read_by_index(e),
+            fuel_flow: interop::FuelFlow::read_by_index(e),
         });
 
         let environment = Environment {
@@ -68,74 +181,155 @@ impl FdGauge {
             engines,
         };
 
-        self.step(&environment, delta_t);
+        self.systems
+            .set_center_detent(interop::CenterDetent::read());
+        self.systems
+            .set_thrust_target_rate(interop::ThrustTargetRate::read());
+        let prior_asymmetry = self.systems.thrust_asymmetry();
+        let outputs = self.systems.update(&environment, delta_t);
 
-        self.record(environment, sim_time, delta_t);
+        self.log_thrust_asymmetry(prior_asymmetry);
+        log_mode_transitions(&outputs);
 
-        self.update_sim();
+        let commanded = outputs.commanded();
+        self.update_sim(&commanded)?;
 
+        self.record(environment, commanded, sim_time, delta_t);
         Ok(())
     }
 
-    fn step(&mut self, environment: &Environment, delta_t: Time) {
-        self.state
-            .engines
-            .zip(&environment.engines, |_, engine, input| {
-                engine.mode = select_throttle_mode(engine.physical_throttle);
-                let (_, throttle_command) = engine.fadec.get_desired_throttle(
-                    engine.physical_throttle.to_ratio(),
-                    engine.mode,
-                    input.thrust,
-                    environment.instruments.mach_number,
-                    environment.instruments.ambient_density,
-                    environment.instruments.pressure_altitude,
-                    delta_t,
+    fn log_thrust_asymmetry(&self, prior: ThrustAsymmetry) {
+        let asymmetry = self.systems.thrust_asymmetry();
+
+        asymmetry.lagging.for_each(|n, lagging| {
+            if lagging && !prior.lagging[n] {
+                println!(
+                    "{:?} is lagging: thrust shortfall {:.1} pdl vs {:.1} pdl",
+                    n,
+                    asymmetry.shortfall[n].get::<poundal>(),
+                    asymmetry.shortfall[n.opposite()].get::<poundal>(),
                 );
-                engine.engine_throttle = throttle_command;
-                engine.visual_throttle =
-                    calculate_throttle_position(engine.mode, engine.physical_throttle);
-            });
+            }
+        });
     }
 
-    fn record(&mut self, environment: Environment, sim_time: Time, delta_t: Time) {
+    fn record(
+        &mut self,
+        environment: Environment,
+        commanded: CommandedOutputs,
+        sim_time: Time,
+        delta_t: Time,
+    ) {
         match (
             interop::FlightDataRecorderEnabled::read(),
             self.recorder.is_some(),
         ) {
-            (false, true) => self.recorder = None,
-            (true, false) => self.recorder = initialize_flight_data_recorder(),
+            (false, true) => {
+                self.recorder = None;
+                self.axis_recorder = None;
+                self.axis_events.clear();
+            }
+            (true, false) => {
+                self.recorder = initialize_flight_data_recorder();
+                self.axis_recorder = initialize_axis_event_recorder();
+                self.record_sampler = RecordSampler::default();
+            }
             _ => {}
         }
 
-        if let Some(r) = &mut self.recorder {
-            r.publish(&Snapshot {
-                aircraft: self.state,
+        if let Err(err) = self.publish(environment, commanded, sim_time, delta_t) {
+            if self.dropped_publishes.push(&err) {
+                eprintln!("Dropping flight data recorder publishes: {}", err);
+            }
+            interop::FlightDataRecorderDropped::set(self.dropped_publishes.count());
+        }
+    }
+
+    /// Publishes pending axis events and, if sampled, a snapshot to the
+    /// enabled recorders
+    fn publish(
+        &mut self,
+        environment: Environment,
+        commanded: CommandedOutputs,
+        sim_time: Time,
+        delta_t: Time,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Raw input events are never sampled so that spurious events are not
+        // hidden between samples
+        if let Some(r) = self.axis_recorder.as_mut() {
+            for event in self.axis_events.drain(sim_time) {
+                r.publish(&event)?;
+            }
+        }
+
+        // Frames with a marker are always recorded so that the marker is not
+        // lost to sampling
+        let marker = self.pending_marker.take();
+        let sampled = self.recorder.is_some()
+            && (self
+                .record_sampler
+                .sample(delta_t, interop::FlightDataRecorderRate::read_interval())
+                || marker.is_some());
+        if let Some(r) = self.recorder.as_mut().filter(|_| sampled) {
+            r.publish(&take_snapshot(
+                &self.systems,
                 environment,
+                commanded,
                 sim_time,
                 delta_t,
-            })
-            .ok();
+                marker,
+            ))?;
         }
+
+        Ok(())
     }
 
-    fn update_sim(&self) {
-        self.state.engines.for_each(|n, e| {
-            interop::Throttle::set_position(n, e.visual_throttle);
-            interop::Throttle::set_mode(n, e.mode);
-        });
+    fn update_sim(&self, commanded: &CommandedOutputs) -> Result<(), UpdateError> {
+        set_named_variables(commanded);
 
-        let update = interop::EngineDataControl {
-            throttle_engine1: self.state.engines[EngineNumber::Engine1].engine_throttle,
-            throttle_engine2: self.state.engines[EngineNumber::Engine2].engine_throttle,
-        };
+        send_engine_controls(commanded, |update| self.simconnect.update_user_data(update))
+    }
 
-        if let Err(err) = self.simconnect.update_user_data(&update) {
-            println!("Error updating simconnect user data: {:?}", err);
+    /// Resynchronizes the simulation clock and starts a new recorder session
+    /// so that a restarted flight is not appended to the prior recording
+    fn handle_sim_start(&mut self) {
+        self.sim_start = None;
+        self.record_sampler = RecordSampler::default();
+        self.control_limiter = ControlRateLimiter::default();
+        self.pending_marker = None;
+        if let Some(err) = self.dropped_publishes.last_error() {
+            eprintln!(
+                "{} publishes to the flight data recorder have failed, most recently: {}",
+                self.dropped_publishes.count(),
+                err
+            );
+        }
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(err) = recorder.start_new_session() {
+                eprintln!(
+                    "Error starting a new flight data recorder session: {:?}",
+                    err
+                );
+                self.recorder = None;
+            }
+        }
+        self.axis_events.clear();
+        if let Some(recorder) = self.axis_recorder.as_mut() {
+            if let Err(err) = recorder.start_new_session() {
+                eprintln!(
+                    "Error starting a new axis event recorder session: {:?}",
+                    err
+                );
+                self.axis_recorder = None;
+            }
         }
     }
 
     fn handle_axis_event(&mut self, event: &simconnect_sys::ffi::ReceiveEvent) {
         //println!("Received event!");
+        if self.axis_recorder.is_some() {
+            self.axis_events.push(event);
+        }
         if let Some(group) = interop::NotificationGroup::from_ffi(event.group_id) {
             // println!("Picked a group: {:?}", group);
             match group {
@@ -146,84 +340,12 @@ impl FdGauge {
                         //     "Associated data: {} {} {:x}",
                         //     event.data, event.data as i32, event.data
                         // );
-                        match event_type {
-                            interop::ThrottleEventType::AxisThrottleSet
-                            | interop::ThrottleEventType::AxisThrottleSetEx => {
-                                self.state.engines.update(|_, eng| {
-                                    eng.physical_throttle =
-                                        ThrottleAxis::from_raw_i32(event.data as i32)
-                                });
-                            }
-                            interop::ThrottleEventType::AxisThrottle1Set
-                            | interop::ThrottleEventType::AxisThrottle1SetEx => {
-                                self.state.engines.engine1.physical_throttle =
-                                    ThrottleAxis::from_raw_i32(event.data as i32);
-                            }
-                            interop::ThrottleEventType::AxisThrottle2Set
-                            | interop::ThrottleEventType::AxisThrottle2SetEx => {
-                                self.state.engines.engine2.physical_throttle =
-                                    ThrottleAxis::from_raw_i32(event.data as i32);
-                            }
-                            interop::ThrottleEventType::ThrottleSet => {
-                                self.state.engines.update(|_, eng| {
-                                    eng.physical_throttle = ThrottleAxis::from_raw_u32(event.data)
-                                });
-                            }
-                            interop::ThrottleEventType::Throttle1Set => {
-                                self.state.engines.engine1.physical_throttle =
-                                    ThrottleAxis::from_raw_u32(event.data);
-                            }
-                            interop::ThrottleEventType::Throttle2Set => {
-                                self.state.engines.engine2.physical_throttle =
-                                    ThrottleAxis::from_raw_u32(event.data);
-                            }
-                            interop::ThrottleEventType::ThrottleFull => {
-                                self.state.engines.update(|_, eng| {
-                                    eng.physical_throttle = ThrottleAxis::MAX;
-                                });
-                            }
-                            interop::ThrottleEventType::Throttle1Full => {
-                                self.state.engines.engine1.physical_throttle = ThrottleAxis::MAX;
-                            }
-                            interop::ThrottleEventType::Throttle2Full => {
-                                self.state.engines.engine2.physical_throttle = ThrottleAxis::MAX;
-                            }
-                            interop::ThrottleEventType::ThrottleCut => {
-                                self.state.engines.update(|_, eng| {
-                                    eng.physical_throttle = ThrottleAxis::MIN;
-                                });
-                            }
-                            interop::ThrottleEventType::Throttle1Cut => {
-                                self.state.engines.engine1.physical_throttle = ThrottleAxis::MIN;
-                            }
-                            interop::ThrottleEventType::Throttle2Cut => {
-                                self.state.engines.engine2.physical_throttle = ThrottleAxis::MIN;
-                            }
-                            interop::ThrottleEventType::ThrottleIncr
-                            | interop::ThrottleEventType::IncreaseThrottle => {
-                                self.state.engines.update(|_, eng| {
-                                    eng.physical_throttle.inc();
-                                });
-                            }
-                            interop::ThrottleEventType::Throttle1Incr => {
-                                self.state.engines.engine1.physical_throttle.inc();
-                            }
-                            interop::ThrottleEventType::Throttle2Incr => {
-                                self.state.engines.engine2.physical_throttle.inc();
-                            }
-                            interop::ThrottleEventType::ThrottleDecr
-                            | interop::ThrottleEventType::DecreaseThrottle => {
-                                self.state.engines.update(|_, eng| {
-                                    eng.physical_throttle.dec();
-                                });
-                            }
-                            interop::ThrottleEventType::Throttle1Decr => {
-                                self.state.engines.engine1.physical_throttle.dec();
-                            }
-                            interop::ThrottleEventType::Throttle2Decr => {
-                                self.state.engines.engine2.physical_throttle.dec();
-                            }
-                        }
+                        apply_throttle_event(
+                            &mut self.systems.aircraft_mut().engines,
+                            event_type,
+                            event,
+                            interop::ThrottleStep::read(),
+                        );
 
                         // let last = self.last_throttle_axis.get();
                         // println!(
@@ -238,6 +360,220 @@ impl FdGauge {
     }
 }
 
+/// The time elapsed during a frame, or `None` if no time elapsed
+///
+/// The simulator delivers frames without elapsed time while paused, which are
+/// skipped rather than treated as failures.
+fn frame_time(dt: f64) -> Result<Option<Time>, UpdateError> {
+    if !dt.is_finite() {
+        return Err(UpdateError::Step(Time::new::<second>(dt)));
+    }
+
+    Ok((dt > 0.).then(|| Time::new::<second>(dt)))
+}
+
+/// Sends the engine throttle commands to the simulator using `update`
+fn send_engine_controls(
+    commanded: &CommandedOutputs,
+    update: impl FnOnce(&interop::EngineDataControl) -> Result<(), HResult>,
+) -> Result<(), UpdateError> {
+    let controls = interop::EngineDataControl {
+        throttle_engine1: commanded.engine_throttle[EngineNumber::Engine1],
+        throttle_engine2: commanded.engine_throttle[EngineNumber::Engine2],
+    };
+
+    update(&controls).map_err(UpdateError::SimConnectUpdate)
+}
+
+/// Writes the named variables displayed for the commanded outputs
+fn set_named_variables(commanded: &CommandedOutputs) {
+    send_lever_status(commanded, |n, position, mode| {
+        interop::Throttle::set_position(n, position);
+        interop::Throttle::set_mode(n, mode);
+    });
+    send_fadec_status(commanded, |n, thrust_target, active| {
+        interop::Throttle::set_thrust_target(n, thrust_target);
+        interop::Throttle::set_fadec_active(n, active);
+    });
+}
+
+fn send_lever_status(
+    commanded: &CommandedOutputs,
+    mut set: impl FnMut(EngineNumber, ThrottlePercent, ThrottleMode),
+) {
+    EngineNumber::iter().into_iter().for_each(|n| {
+        set(
+            n,
+            commanded.throttle_position[n],
+            commanded.throttle_mode[n],
+        )
+    });
+}
+
+fn send_fadec_status(
+    commanded: &CommandedOutputs,
+    mut set: impl FnMut(EngineNumber, ThrustValue, bool),
+) {
+    EngineNumber::iter()
+        .into_iter()
+        .for_each(|n| set(n, commanded.thrust_target[n], commanded.fadec_active[n]));
+}
+
+fn log_mode_transitions(outputs: &SystemOutputs) {
+    outputs.mode_transitions.for_each(|n, transition| {
+        if let Some(transition) = transition {
+            println!(
+                "{:?} FADEC mode changed: {:?} -> {:?}",
+                n, transition.from, transition.to
+            );
+        }
+    });
+}
+
+/// Captures the current state of the aircraft systems for recording
+fn take_snapshot(
+    systems: &AircraftSystems,
+    environment: Environment,
+    commanded: CommandedOutputs,
+    sim_time: Time,
+    delta_t: Time,
+    marker: Option<u32>,
+) -> Snapshot {
+    Snapshot {
+        schema_version: SNAPSHOT_SCHEMA_VERSION,
+        aircraft: *systems.aircraft(),
+        environment,
+        sim_time,
+        delta_t,
+        thrust_asymmetry: systems.thrust_asymmetry(),
+        marker,
+        overspeed_protection: systems
+            .aircraft()
+            .engines
+            .map(|_, e| e.fadec.is_overspeed_protection_active()),
+        turbine_temperature_limit: systems
+            .aircraft()
+            .engines
+            .map(|_, e| e.fadec.is_turbine_temperature_limited()),
+        readings_valid: environment.engines.map(|_, r| r.is_valid()),
+        thrust_limit_source: systems
+            .aircraft()
+            .engines
+            .map(|_, e| e.fadec.thrust_limit_source()),
+        commanded: Some(commanded),
+    }
+}
+
+/// A count of publishes that failed to reach the flight data recorder
+///
+/// A failed publish does not fail the frame. Only the first failure is
+/// logged, but the count and most recent error remain available afterwards so
+/// that a recorder that keeps failing can be noticed.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct DroppedPublishes {
+    count: u64,
+    last_error: Option<String>,
+}
+
+impl DroppedPublishes {
+    /// Counts a failed publish, keeping its error as the most recent
+    ///
+    /// Returns whether this is the first failed publish.
+    fn push(&mut self, err: &impl fmt::Display) -> bool {
+        self.count += 1;
+        self.last_error = Some(err.to_string());
+        self.count == 1
+    }
+
+    /// The number of publishes that have failed
+    fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The error from the most recent failed publish, if any
+    fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+}
+
+/// Decimates control steps down to the rate at which snapshots are recorded
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct RecordSampler {
+    since_last: Option<Time>,
+}
+
+impl RecordSampler {
+    /// Advances the sampler by a control step, returning whether the step
+    /// should be recorded
+    ///
+    /// The first step is always recorded. Afterwards, a step is recorded once
+    /// at least `interval` has elapsed since the last recorded step, within
+    /// half a step. An interval of zero records every step.
+    fn sample(&mut self, delta_t: Time, interval: Time) -> bool {
+        let elapsed = match self.since_last {
+            Some(since_last) => since_last + delta_t,
+            None => {
+                self.since_last = Some(Time::new::<second>(0.));
+                return true;
+            }
+        };
+
+        if elapsed + delta_t / 2. >= interval {
+            self.since_last = Some(clamp(elapsed - interval, Time::new::<second>(0.), interval));
+            true
+        } else {
+            self.since_last = Some(elapsed);
+            false
+        }
+    }
+}
+
+/// Limits the rate at which the control loop is stepped
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct ControlRateLimiter {
+    pending: Time,
+}
+
+impl ControlRateLimiter {
+    /// Advances the limiter by a frame, returning the time to step the control
+    /// loop by if it should be stepped
+    ///
+    /// Frame times are accumulated until at least `interval` has elapsed,
+    /// within half a frame, so that no simulation time is lost between steps.
+    fn step(&mut self, delta_t: Time, interval: Time) -> Option<Time> {
+        self.pending += delta_t;
+        if self.pending + delta_t / 2. >= interval {
+            Some(std::mem::take(&mut self.pending))
+        } else {
+            None
+        }
+    }
+}
+
+/// Registers the events and data used by the gauge once the connection is
+/// open
+fn register(simconnect: &simconnect_sys::SimConnect) -> Result<(), HResult> {
+    simconnect.register_notification_group_enum::<interop::NotificationGroup>()?;
+    simconnect.register_notification_group_enum::<interop::RecorderNotificationGroup>()?;
+    simconnect.subscribe_to_system_events::<interop::SystemEventType>()?;
+    simconnect.register_data_definition::<interop::EngineDataControl>()
+}
+
+/// Loads the FADEC configuration from the work folder, if one is present
+fn load_fadec_config() -> Option<FadecController> {
+    let config = std::fs::read_to_string(FADEC_CONFIG_PATH).ok()?;
+    match FadecController::from_config(&config) {
+        Ok(fadec) => {
+            println!("Loaded FADEC configuration from {}", FADEC_CONFIG_PATH);
+            Some(fadec)
+        }
+        Err(err) => {
+            eprintln!("Error loading FADEC configuration: {}", err);
+            None
+        }
+    }
+}
+
 fn initialize_flight_data_recorder() -> Option<wt_flight_recorder::FlightDataRecorder<Snapshot>> {
     match wt_flight_recorder::FlightDataRecorder::new() {
         Ok(recorder) => Some(recorder),
@@ -248,38 +584,573 @@ fn initialize_flight_data_recorder() -> Option<wt_flight_recorder::FlightDataRec
     }
 }
 
+fn initialize_axis_event_recorder() -> Option<wt_flight_recorder::FlightDataRecorder<RawAxisEvent>>
+{
+    match wt_flight_recorder::FlightDataRecorder::new_stream("axis") {
+        Ok(recorder) => Some(recorder),
+        Err(err) => {
+            eprintln!("Error creating axis event recorder: {:?}", err);
+            None
+        }
+    }
+}
+
+/// Raw input events received since the last control step, awaiting recording
+#[derive(Debug, Default)]
+struct AxisEventLog {
+    pending: Vec<simconnect_sys::ffi::ReceiveEvent>,
+}
+
+impl AxisEventLog {
+    fn push(&mut self, event: &simconnect_sys::ffi::ReceiveEvent) {
+        self.pending.push(*event);
+    }
+
+    fn clear(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Removes the pending events in the order received, stamping each with
+    /// the simulation time of the current control step
+    fn drain(&mut self, sim_time: Time) -> impl Iterator<Item = RawAxisEvent> + '_ {
+        self.pending.drain(..).map(move |event| RawAxisEvent {
+            sim_time,
+            group_id: event.group_id.0,
+            event_id: event.event_id.0,
+            data: event.data,
+        })
+    }
+}
+
 impl simconnect_sys::SimConnectDispatcher for FdGauge {
+    fn handle_open(&mut self, event: &simconnect_sys::ffi::ReceiveOpen) {
+        self.open_watcher.handle_open(event);
+    }
+
     fn handle_event(&mut self, event: &simconnect_sys::ffi::ReceiveEvent) {
         //println!("Received event! Passing it along...");
         //println!("What am I? {:?}", self as *const Self);
+        if let Some(interop::SystemEventType::SimStart) =
+            interop::SystemEventType::from_ffi(event.event_id)
+        {
+            self.handle_sim_start();
+            return;
+        }
+
+        if let Some(interop::RecorderNotificationGroup::Recorder) =
+            interop::RecorderNotificationGroup::from_ffi(event.group_id)
+        {
+            if let Some(interop::RecorderEventType::Marker) =
+                interop::RecorderEventType::from_ffi(event.event_id)
+            {
+                self.pending_marker = Some(event.data);
+            }
+            return;
+        }
+
         self.handle_axis_event(event)
     }
 }
 
+/// Applies a throttle event to the physical throttle position of the affected
+/// engines
+fn apply_throttle_event(
+    engines: &mut EngineData<Engine>,
+    event_type: interop::ThrottleEventType,
+    event: &simconnect_sys::ffi::ReceiveEvent,
+    step: ThrottleAxisStep,
+) {
+    match event_type {
+        interop::ThrottleEventType::AxisThrottleSet
+        | interop::ThrottleEventType::AxisThrottleSetEx => {
+            engines.update(|_, eng| {
+                eng.physical_throttle = ThrottleAxis::from_raw_i32(event.as_axis_i32())
+            });
+        }
+        interop::ThrottleEventType::AxisThrottle1Set
+        | interop::ThrottleEventType::AxisThrottle1SetEx => {
+            engines.engine1.physical_throttle = ThrottleAxis::from_raw_i32(event.as_axis_i32());
+        }
+        interop::ThrottleEventType::AxisThrottle2Set
+        | interop::ThrottleEventType::AxisThrottle2SetEx => {
+            engines.engine2.physical_throttle = ThrottleAxis::from_raw_i32(event.as_axis_i32());
+        }
+        interop::ThrottleEventType::ThrottleSet => {
+            engines.update(|_, eng| {
+                eng.physical_throttle = ThrottleAxis::from_raw_u32(event.as_throttle_u32())
+            });
+        }
+        interop::ThrottleEventType::Throttle1Set => {
+            engines.engine1.physical_throttle = ThrottleAxis::from_raw_u32(event.as_throttle_u32());
+        }
+        interop::ThrottleEventType::Throttle2Set => {
+            engines.engine2.physical_throttle = ThrottleAxis::from_raw_u32(event.as_throttle_u32());
+        }
+        interop::ThrottleEventType::ThrottleFull => {
+            engines.update(|_, eng| {
+                eng.physical_throttle = ThrottleAxis::MAX;
+            });
+        }
+        interop::ThrottleEventType::Throttle1Full => {
+            engines.engine1.physical_throttle = ThrottleAxis::MAX;
+        }
+        interop::ThrottleEventType::Throttle2Full => {
+            engines.engine2.physical_throttle = ThrottleAxis::MAX;
+        }
+        interop::ThrottleEventType::ThrottleCut => {
+            engines.update(|_, eng| {
+                eng.physical_throttle = ThrottleAxis::MIN;
+            });
+        }
+        interop::ThrottleEventType::Throttle1Cut => {
+            engines.engine1.physical_throttle = ThrottleAxis::MIN;
+        }
+        interop::ThrottleEventType::Throttle2Cut => {
+            engines.engine2.physical_throttle = ThrottleAxis::MIN;
+        }
+        interop::ThrottleEventType::ThrottleIncr | interop::ThrottleEventType::IncreaseThrottle => {
+            engines.update(|_, eng| {
+                eng.physical_throttle = eng.physical_throttle.inc_by(step);
+            });
+        }
+        interop::ThrottleEventType::Throttle1Incr => {
+            let engine = &mut engines.engine1;
+            engine.physical_throttle = engine.physical_throttle.inc_by(step);
+        }
+        interop::ThrottleEventType::Throttle2Incr => {
+            let engine = &mut engines.engine2;
+            engine.physical_throttle = engine.physical_throttle.inc_by(step);
+        }
+        interop::ThrottleEventType::ThrottleDecr | interop::ThrottleEventType::DecreaseThrottle => {
+            engines.update(|_, eng| {
+                eng.physical_throttle = eng.physical_throttle.dec_by(step);
+            });
+        }
+        interop::ThrottleEventType::Throttle1Decr => {
+            let engine = &mut engines.engine1;
+            engine.physical_throttle = engine.physical_throttle.dec_by(step);
+        }
+        interop::ThrottleEventType::Throttle2Decr => {
+            let engine = &mut engines.engine2;
+            engine.physical_throttle = engine.physical_throttle.dec_by(step);
+        }
+    }
+}
+
 impl Drop for FdGauge {
     fn drop(&mut self) {
         gauge_sys::ffi::unregister_named_variables();
     }
 }
 
-fn select_throttle_mode(axis: ThrottleAxis) -> ThrottleMode {
-    if axis > ThrottleAxis::CLIMB_MAX {
-        ThrottleMode::Takeoff
-    } else if axis > ThrottleAxis::CRUISE_MAX {
-        ThrottleMode::Climb
-    } else if axis > ThrottleAxis::UNDEF_MAX {
-        ThrottleMode::Cruise
-    } else {
-        ThrottleMode::Undefined
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use avmath::airspeed::MachNumber;
+    use avmath::isa::{GeometricAltitude, PressureAltitude};
+    use std::collections::HashMap;
+    use uom::si::{
+        f64::{Force, MassDensity},
+        length::foot,
+        mass_density::slug_per_cubic_foot,
+        ratio::ratio,
+    };
+    use wt_systems::testing::assert_equal_within_epsilon;
+
+    fn throttle_event(event_type: interop::ThrottleEventType) -> simconnect_sys::ffi::ReceiveEvent {
+        simconnect_sys::ffi::ReceiveEvent {
+            header: simconnect_sys::ffi::ReceiveHeader {
+                size: size_of::<simconnect_sys::ffi::ReceiveEvent>() as u32,
+                version: 0,
+                message_type: simconnect_sys::ffi::RawMessageType(
+                    simconnect_sys::ffi::MessageType::Event as u32,
+                ),
+            },
+            group_id: interop::NotificationGroup::Throttle.to_ffi(),
+            event_id: event_type.to_ffi(),
+            data: 0,
+        }
     }
-}
 
-fn calculate_throttle_position(mode: ThrottleMode, axis: ThrottleAxis) -> ThrottlePercent {
-    let target_throttle = match mode {
-        ThrottleMode::Takeoff => ThrottleAxis::TAKEOFF,
-        ThrottleMode::Climb => ThrottleAxis::CLIMB,
-        ThrottleMode::Cruise | ThrottleMode::Undefined => axis,
-    };
+    #[test]
+    fn throttle2_decrement_only_changes_engine2() {
+        let lever = ThrottleAxis::from_raw(0.);
+        let mut engines = Aircraft::new_with_lever(lever).engines;
+        let step = ThrottleAxisStep::default();
+        let event_type = interop::ThrottleEventType::Throttle2Decr;
+
+        apply_throttle_event(&mut engines, event_type, &throttle_event(event_type), step);
+
+        assert_eq!(lever, engines.engine1.physical_throttle);
+        assert_eq!(lever.dec_by(step), engines.engine2.physical_throttle);
+        assert!(engines.engine2.physical_throttle < lever);
+    }
+
+    #[test]
+    fn throttle_cut_without_reverse_limit_reports_undefined() {
+        let mut systems = AircraftSystems::new(Aircraft::new_with_lever(ThrottleAxis::CLIMB));
+        let event_type = interop::ThrottleEventType::ThrottleCut;
+        apply_throttle_event(
+            &mut systems.aircraft_mut().engines,
+            event_type,
+            &throttle_event(event_type),
+            ThrottleAxisStep::default(),
+        );
+
+        let outputs = systems.update(&environment(), Time::new::<second>(1. / 20.));
+
+        let mut sent = Vec::new();
+        send_lever_status(&outputs.commanded(), |n, _, mode| sent.push((n, mode)));
+        assert_eq!(
+            vec![
+                (EngineNumber::Engine1, ThrottleMode::Undefined),
+                (EngineNumber::Engine2, ThrottleMode::Undefined),
+            ],
+            sent
+        );
+    }
+
+    #[test]
+    fn injected_axis_events_are_drained_verbatim_in_order() {
+        let mut log = AxisEventLog::default();
+        let events = [
+            (interop::ThrottleEventType::Throttle1Set, 16_384),
+            (interop::ThrottleEventType::Throttle2Set, u32::MAX),
+            (interop::ThrottleEventType::Throttle2Decr, 0),
+            (interop::ThrottleEventType::Throttle1Set, 1),
+        ];
+        for &(event_type, data) in &events {
+            log.push(&simconnect_sys::ffi::ReceiveEvent {
+                data,
+                ..throttle_event(event_type)
+            });
+        }
+        let sim_time = Time::new::<second>(12.5);
+
+        let recorded: Vec<RawAxisEvent> = log.drain(sim_time).collect();
+
+        let expected: Vec<RawAxisEvent> = events
+            .iter()
+            .map(|&(event_type, data)| RawAxisEvent {
+                sim_time,
+                group_id: interop::NotificationGroup::Throttle.to_ffi().0,
+                event_id: event_type.to_ffi().0,
+                data,
+            })
+            .collect();
+        assert_eq!(expected, recorded);
+        assert_eq!(0, log.drain(sim_time).count());
+    }
+
+    #[test]
+    fn recorder_samples_at_configured_interval() {
+        let mut sampler = RecordSampler::default();
+        let delta_t = Time::new::<second>(1. / 20.);
+        let interval = Time::new::<second>(1. / 5.);
+
+        let recorded: Vec<usize> = (0..20)
+            .filter(|_| sampler.sample(delta_t, interval))
+            .collect();
+
+        assert_eq!(vec![0, 4, 8, 12, 16], recorded);
+    }
+
+    #[test]
+    fn recorder_samples_every_step_without_interval() {
+        let mut sampler = RecordSampler::default();
+        let delta_t = Time::new::<second>(1. / 20.);
+
+        assert!((0..20).all(|_| sampler.sample(delta_t, Time::new::<second>(0.))));
+    }
+
+    #[test]
+    fn failed_publishes_are_counted_with_last_error() {
+        let mut dropped = DroppedPublishes::default();
+        assert_eq!(0, dropped.count());
+        assert_eq!(None, dropped.last_error());
+
+        let failures: [Box<dyn std::error::Error>; 2] = ["disk full".into(), "file closed".into()];
+        let first: Vec<bool> = failures.iter().map(|err| dropped.push(err)).collect();
 
-    ThrottlePercent::from(target_throttle)
+        assert_eq!(vec![true, false], first);
+
+        assert_eq!(2, dropped.count());
+        assert_eq!(Some("file closed"), dropped.last_error());
+    }
+
+    fn control_steps(interval: Time) -> Vec<Time> {
+        let mut limiter = ControlRateLimiter::default();
+        let delta_t = Time::new::<second>(1. / 60.);
+
+        (0..120)
+            .filter_map(|_| limiter.step(delta_t, interval))
+            .collect()
+    }
+
+    #[test]
+    fn doubling_control_interval_halves_step_rate() {
+        let fast_steps = control_steps(Time::new::<second>(0.05));
+        let slow_steps = control_steps(Time::new::<second>(0.1));
+
+        assert_eq!(40, fast_steps.len());
+        assert_eq!(fast_steps.len() / 2, slow_steps.len());
+        assert_equal_within_epsilon(
+            slow_steps.iter().map(|t| t.get::<second>()).sum::<f64>(),
+            2.,
+            1e-9,
+        );
+    }
+
+    #[test]
+    fn control_loop_steps_every_frame_without_interval() {
+        assert_eq!(120, control_steps(Time::new::<second>(0.)).len());
+        assert_eq!(
+            120,
+            control_steps(Time::new::<second>(DEFAULT_MIN_CONTROL_INTERVAL)).len()
+        );
+    }
+
+    #[test]
+    fn paused_frames_are_skipped_and_non_finite_frames_fail() {
+        assert_eq!(
+            Some(Time::new::<second>(1. / 30.)),
+            frame_time(1. / 30.).unwrap()
+        );
+        assert_eq!(None, frame_time(0.).unwrap());
+        assert_eq!(None, frame_time(-0.1).unwrap());
+        assert!(matches!(frame_time(f64::NAN), Err(UpdateError::Step(_))));
+        assert!(matches!(
+            frame_time(f64::INFINITY),
+            Err(UpdateError::Step(_))
+        ));
+    }
+
+    #[test]
+    fn simconnect_update_failure_is_reported() {
+        let outputs = SystemOutputs {
+            engine_throttle: EngineData::new(ThrottlePercent::MAX),
+            ..SystemOutputs::default()
+        };
+
+        let result = send_engine_controls(&outputs.commanded(), |controls| {
+            assert_eq!(ThrottlePercent::MAX, controls.throttle_engine1);
+            assert_eq!(ThrottlePercent::MAX, controls.throttle_engine2);
+            Err(HResult::E_FAIL)
+        });
+
+        match result {
+            Err(UpdateError::SimConnectUpdate(err)) => assert_eq!(HResult::E_FAIL, err),
+            other => panic!("expected a SimConnect update error, got {:?}", other),
+        }
+    }
+
+    fn environment() -> Environment {
+        Environment {
+            instruments: Instruments {
+                mach_number: MachNumber::new::<ratio>(0.4),
+                ambient_density: Default::default(),
+                geometric_altitude: GeometricAltitude::new::<foot>(0.),
+                pressure_altitude: PressureAltitude::new::<foot>(0.),
+                airspeed_indicated: Default::default(),
+                airspeed_true: Default::default(),
+                vertical_speed: Default::default(),
+                on_ground: false,
+            },
+            engines: EngineData::new(EngineReadings {
+                thrust: Default::default(),
+                fuel_flow: Default::default(),
+            }),
+        }
+    }
+
+    #[test]
+    fn marker_is_recorded_only_on_its_frame() {
+        let systems = AircraftSystems::new(Aircraft::default());
+        let delta_t = Time::new::<second>(1. / 20.);
+        let mut pending_marker = None;
+
+        let markers: Vec<Option<u32>> = (0..4)
+            .map(|frame| {
+                if frame == 2 {
+                    pending_marker = Some(7);
+                }
+                let sim_time = delta_t * frame as f64;
+                take_snapshot(
+                    &systems,
+                    environment(),
+                    CommandedOutputs::default(),
+                    sim_time,
+                    delta_t,
+                    pending_marker.take(),
+                )
+                .marker
+            })
+            .collect();
+
+        assert_eq!(vec![None, None, Some(7), None], markers);
+    }
+
+    #[test]
+    fn recorded_commands_match_values_sent() {
+        let mut systems = AircraftSystems::new(Aircraft::default());
+        systems
+            .aircraft_mut()
+            .engines
+            .update(|_, e| e.physical_throttle = ThrottleAxis::CLIMB);
+        let outputs = systems.update(&environment(), Time::new::<second>(1. / 20.));
+        let commanded = outputs.commanded();
+
+        let mut sent = None;
+        send_engine_controls(&commanded, |controls| {
+            sent = Some((controls.throttle_engine1, controls.throttle_engine2));
+            Ok(())
+        })
+        .unwrap();
+
+        let snapshot = take_snapshot(
+            &systems,
+            environment(),
+            commanded,
+            Time::new::<second>(0.),
+            Time::new::<second>(1. / 20.),
+            None,
+        );
+        let recorded = snapshot.commanded.unwrap();
+
+        assert_eq!(
+            Some((
+                recorded.engine_throttle[EngineNumber::Engine1],
+                recorded.engine_throttle[EngineNumber::Engine2]
+            )),
+            sent
+        );
+        assert_eq!(outputs.visual_throttle, recorded.throttle_position);
+        assert_eq!(EngineData::new(ThrottleMode::Climb), recorded.throttle_mode);
+    }
+
+    #[test]
+    fn displayed_fadec_status_matches_fadec_target_and_state() {
+        let mut systems = AircraftSystems::new(Aircraft::default());
+        systems.aircraft_mut().engines.engine1.physical_throttle = ThrottleAxis::CLIMB;
+        systems.aircraft_mut().engines.engine2.physical_throttle = ThrottleAxis::from_raw(0.);
+        let mut environment = environment();
+        environment.instruments.ambient_density = MassDensity::new::<slug_per_cubic_foot>(0.0017);
+        environment
+            .engines
+            .update(|_, e| e.thrust = Force::new::<poundal>(1_000.));
+
+        let mut outputs = SystemOutputs::default();
+        for _ in 0..20 {
+            outputs = systems.update(&environment, Time::new::<second>(1. / 20.));
+        }
+
+        let mut sent = Vec::new();
+        send_fadec_status(&outputs.commanded(), |n, thrust_target, active| {
+            sent.push((n, thrust_target, active))
+        });
+
+        let engines = &systems.aircraft().engines;
+        assert_eq!(
+            vec![
+                (
+                    EngineNumber::Engine1,
+                    engines.engine1.commanded_thrust,
+                    true
+                ),
+                (
+                    EngineNumber::Engine2,
+                    engines.engine2.commanded_thrust,
+                    false
+                ),
+            ],
+            sent
+        );
+        assert!(engines.engine1.fadec.is_active());
+        assert!(!engines.engine2.fadec.is_active());
+        assert_ne!(ThrustValue::MIN, engines.engine1.commanded_thrust);
+    }
+
+    #[test]
+    fn reinstall_resets_lever_and_fadec_status() {
+        fn write(named: &mut HashMap<String, f64>, commanded: &CommandedOutputs) {
+            send_lever_status(commanded, |n, position, mode| {
+                named.insert(format!("{:?} Pos", n), position.into());
+                named.insert(format!("{:?} MODE", n), mode.into());
+            });
+            send_fadec_status(commanded, |n, thrust_target, active| {
+                named.insert(
+                    format!("{:?} THRUST_TARGET", n),
+                    thrust_target.to_pound_force(),
+                );
+                named.insert(
+                    format!("{:?} FADEC_ACTIVE", n),
+                    if active { 1. } else { 0. },
+                );
+            });
+        }
+
+        let mut systems = AircraftSystems::new(Aircraft::default());
+        systems
+            .aircraft_mut()
+            .engines
+            .update(|_, e| e.physical_throttle = ThrottleAxis::CLIMB);
+        let outputs = systems.update(&environment(), Time::new::<second>(1. / 20.));
+
+        let mut named = HashMap::new();
+        write(&mut named, &outputs.commanded());
+        assert_eq!(8, named.len());
+        assert!(named.values().any(|&v| v != 0.));
+
+        // The gauge is killed, and the reinstalled gauge resets what its
+        // predecessor wrote
+        write(&mut named, &CommandedOutputs::default());
+        assert_eq!(8, named.len());
+        assert!(named.values().all(|&v| v == 0.), "{:?}", named);
+    }
+
+    #[test]
+    fn non_finite_thrust_is_held_and_recorded() {
+        let mut systems = AircraftSystems::new(Aircraft::default());
+        systems
+            .aircraft_mut()
+            .engines
+            .update(|_, e| e.physical_throttle = ThrottleAxis::CLIMB);
+        let delta_t = Time::new::<second>(1. / 20.);
+        let mut environment = environment();
+        environment.instruments.ambient_density = MassDensity::new::<slug_per_cubic_foot>(0.0017);
+        environment
+            .engines
+            .update(|_, e| e.thrust = Force::new::<poundal>(1_000.));
+
+        let mut outputs = SystemOutputs::default();
+        for _ in 0..5 {
+            outputs = systems.update(&environment, delta_t);
+        }
+        let before = outputs.commanded().engine_throttle;
+
+        environment.engines.engine2.thrust = Force::new::<poundal>(f64::NAN);
+        let outputs = systems.update(&environment, delta_t);
+        let commanded = outputs.commanded();
+
+        assert!(commanded
+            .engine_throttle
+            .engine2
+            .to_ratio()
+            .value
+            .is_finite());
+        assert_eq!(before.engine2, commanded.engine_throttle.engine2);
+
+        let snapshot = take_snapshot(
+            &systems,
+            environment,
+            commanded,
+            Time::new::<second>(0.),
+            delta_t,
+            None,
+        );
+        assert_eq!(
+            EngineData::new_from(|e| e == EngineNumber::Engine1),
+            snapshot.readings_valid
+        );
+    }
 }
@@ -1,19 +1,26 @@
+use avmath::airspeed::MachNumber;
+use avmath::isa::AltimeterSetting;
 use gauge_sys::{
     gauge_unit, indexed_aircraft_variable, named_variable, unindexed_aircraft_variable,
 };
 use num_derive::{FromPrimitive, ToPrimitive};
 use num_traits::{FromPrimitive, ToPrimitive};
+use std::{convert::TryFrom, fmt};
 use uom::si::{
     f64::*,
-    force::poundal,
+    force::{pound_force, poundal},
+    frequency::hertz,
     length::foot,
     mass_density::slug_per_cubic_foot,
-    ratio::ratio,
+    mass_rate::pound_per_hour,
+    pressure::inch_of_mercury,
+    ratio::{percent, ratio},
     velocity::{foot_per_second, knot},
 };
 use wt_cj4::{
-    control_params::{ThrottleMode, ThrottlePercent},
+    control_params::{ThrottleAxis, ThrottleAxisStep, ThrottleMode, ThrottlePercent, ThrustValue},
     engines::EngineNumber,
+    ThrustRate,
 };
 
 gauge_unit!(Percent: "Percent"; "A percentage, expressed as a value between 0 and 100");
@@ -24,10 +31,12 @@ gauge_unit!(Mach: "Mach"; "Velocity measures as a ratio of the speed of sound");
 gauge_unit!(Knots: "Knots"; "Nautical miles per hour");
 gauge_unit!(FeetPerSecond: "Feet per second"; "Feet per second");
 gauge_unit!(SluggerSlugs: "Slug per cubic feet"; "Pressure measured in slugs per cubic foot");
+gauge_unit!(PoundsPerHour: "Pounds per hour"; "Mass flow measured in pounds per hour");
 gauge_unit!(Bool: "Bool"; "A boolean value which is either off (0) or on (1)");
 
-indexed_aircraft_variable!(Throttle(Percent): "GENERAL ENG THROTTLE LEVER POSITION"; "Engine throttle lever position");
+indexed_aircraft_variable!(Throttle(Percent): "GENERAL ENG THROTTLE LEVER POSITION"; "Engine throttle lever position"; epsilon: 0.);
 indexed_aircraft_variable!(Thrust(Pounds): "TURB ENG JET THRUST"; "Turbine engine jet thrust");
+indexed_aircraft_variable!(FuelFlow(PoundsPerHour): "TURB ENG FUEL FLOW PPH"; "Turbine engine fuel flow");
 unindexed_aircraft_variable!(AirspeedMach(Mach): "AIRSPEED MACH"; "Airspeed as Mach number");
 unindexed_aircraft_variable!(AirspeedIndicated(Knots): "AIRSPEED INDICATED"; "Airspeed as indicated by pitot pressure");
 unindexed_aircraft_variable!(AirspeedTrue(Knots): "AIRSPEED TRUE"; "True airspeed");
@@ -39,9 +48,20 @@ unindexed_aircraft_variable!(OnGround(Number): "SIM ON GROUND"; "Whether the use
 
 named_variable!(Throttle1Mode(ThrottleMode): "THROTTLE1_MODE"; "The FADEC mode of engine 1");
 named_variable!(Throttle2Mode(ThrottleMode): "THROTTLE2_MODE"; "The FADEC mode of engine 2");
+named_variable!(Throttle1ThrustTarget(f64): "THROTTLE1_THRUST_TARGET"; "The FADEC thrust target of engine 1 in pounds of force");
+named_variable!(Throttle2ThrustTarget(f64): "THROTTLE2_THRUST_TARGET"; "The FADEC thrust target of engine 2 in pounds of force");
+named_variable!(Throttle1FadecActive(Boolean): "THROTTLE1_FADEC_ACTIVE"; "Whether the FADEC is controlling engine 1");
+named_variable!(Throttle2FadecActive(Boolean): "THROTTLE2_FADEC_ACTIVE"; "Whether the FADEC is controlling engine 2");
 
 named_variable!(Throttle1Position(ThrottlePercent): "Throttle1_Pos"; "The visual position of the engine 1 throttle lever");
 named_variable!(Throttle2Position(ThrottlePercent): "Throttle2_Pos"; "The visual position of the engine 2 throttle lever");
+named_variable!(AltimeterQnh(f64): "FADEC_ALTIMETER_QNH"; "The altimeter setting in inches of mercury, for aircraft that do not publish pressure altitude");
+named_variable!(ThrottleStep(f64): "FADEC_THROTTLE_STEP"; "The percentage of the throttle axis to move for each throttle increment or decrement");
+named_variable!(ControlInterval(f64): "FADEC_CONTROL_INTERVAL"; "The minimum time in milliseconds between steps of the FADEC control loop");
+named_variable!(CenterDetent(f64): "FADEC_CENTER_DETENT"; "The percentage of the throttle axis either side of center within which a spring-centered lever holds the current thrust");
+named_variable!(ThrustTargetRate(f64): "FADEC_THRUST_TARGET_RATE"; "The rate in pounds of force per second at which the displayed thrust target follows the FADEC thrust target");
+named_variable!(FlightDataRecorderRate(f64): "FADEC_RECORDER_RATE"; "The rate in hertz at which snapshots are published to the flight data recorder");
+named_variable!(FlightDataRecorderDropped(f64): "FADEC_RECORDER_DROPPED"; "The number of publishes to the flight data recorder that have failed");
 named_variable!(FlightDataRecorderEnabled(Boolean): "FLIGHT_DATA_RECORDER_ENABLED"; "Whether or not the flight data recorder should be enabled");
 
 fn engine_number_to_sim_index(engine: EngineNumber) -> u32 {
@@ -52,10 +72,11 @@ fn engine_number_to_sim_index(engine: EngineNumber) -> u32 {
 }
 
 impl Throttle {
-    // pub fn read_by_index(engine: EngineNumber) -> Ratio {
-    //     let index = engine_number_to_sim_index(engine);
-    //     Ratio::new::<percent>(Self::read_raw_by_index(index))
-    // }
+    /// Reads the position of the throttle lever as an axis value
+    pub fn read_by_index(engine: EngineNumber) -> ThrottleAxis {
+        let index = engine_number_to_sim_index(engine);
+        ThrottleAxis::from_ratio(Ratio::new::<percent>(Self::read_raw_by_index(index)))
+    }
 
     pub fn set_position(engine: EngineNumber, pct: ThrottlePercent) {
         match engine {
@@ -70,11 +91,31 @@ impl Throttle {
             EngineNumber::Engine2 => Throttle2Mode::set_raw(mode),
         }
     }
+
+    pub fn set_thrust_target(engine: EngineNumber, target: ThrustValue) {
+        let pounds = target.to_pound_force();
+        match engine {
+            EngineNumber::Engine1 => Throttle1ThrustTarget::set_raw(pounds),
+            EngineNumber::Engine2 => Throttle2ThrustTarget::set_raw(pounds),
+        }
+    }
+
+    pub fn set_fadec_active(engine: EngineNumber, active: bool) {
+        let active = if active {
+            Boolean::True
+        } else {
+            Boolean::False
+        };
+        match engine {
+            EngineNumber::Engine1 => Throttle1FadecActive::set_raw(active),
+            EngineNumber::Engine2 => Throttle2FadecActive::set_raw(active),
+        }
+    }
 }
 
 impl AirspeedMach {
-    pub fn read() -> Ratio {
-        Ratio::new::<ratio>(Self::read_raw())
+    pub fn read() -> MachNumber {
+        MachNumber::new::<ratio>(Self::read_raw())
     }
 }
 
@@ -103,10 +144,40 @@ impl Thrust {
     }
 }
 
+impl FuelFlow {
+    pub fn read_by_index(engine: EngineNumber) -> MassRate {
+        let index = engine_number_to_sim_index(engine);
+        MassRate::new::<pound_per_hour>(Self::read_raw_by_index(index))
+    }
+}
+
 impl PressureAltitude {
     pub fn read() -> avmath::isa::PressureAltitude {
         avmath::isa::PressureAltitude::new::<foot>(Self::read_raw())
     }
+
+    /// Reads the pressure altitude, computing it from the plane altitude when
+    /// the aircraft publishes an altimeter setting instead
+    pub fn read_with_fallback() -> avmath::isa::PressureAltitude {
+        let qnh = AltimeterQnh::read_raw();
+        if qnh > 0. {
+            pressure_altitude_from_indicated(
+                GeometricAltitude::read().remove_context(),
+                AltimeterSetting::new::<inch_of_mercury>(qnh),
+            )
+        } else {
+            Self::read()
+        }
+    }
+}
+
+/// Computes the pressure altitude from an indicated altitude and the
+/// altimeter setting used to indicate it
+pub fn pressure_altitude_from_indicated(
+    indicated_altitude: Length,
+    qnh: AltimeterSetting,
+) -> avmath::isa::PressureAltitude {
+    qnh.to_pressure_altitude(indicated_altitude)
 }
 
 impl GeometricAltitude {
@@ -121,6 +192,89 @@ impl AmbientDensity {
     }
 }
 
+impl OnGround {
+    pub fn read() -> bool {
+        Boolean::from(Self::read_raw()) == Boolean::True
+    }
+}
+
+impl ThrottleStep {
+    /// Reads the configured throttle step, using the default step when none
+    /// has been configured
+    pub fn read() -> ThrottleAxisStep {
+        let step = Self::read_raw();
+        if step > 0. {
+            ThrottleAxisStep::from_ratio(Ratio::new::<percent>(step))
+        } else {
+            ThrottleAxisStep::DEFAULT
+        }
+    }
+}
+
+impl ControlInterval {
+    /// Reads the configured control loop interval, if one has been configured
+    ///
+    /// A negative value steps the control loop on every frame, as does the
+    /// default interval used when none has been configured.
+    pub fn read() -> Option<Time> {
+        let interval = Self::read_raw();
+        if interval > 0. {
+            Some(Time::new::<uom::si::time::millisecond>(interval))
+        } else if interval < 0. {
+            Some(Time::new::<uom::si::time::second>(0.))
+        } else {
+            None
+        }
+    }
+}
+
+impl CenterDetent {
+    /// Reads the configured center detent, if holding thrust with a centered
+    /// lever has been enabled
+    pub fn read() -> Option<Ratio> {
+        let detent = Self::read_raw();
+        if detent > 0. {
+            Some(Ratio::new::<percent>(detent))
+        } else {
+            None
+        }
+    }
+}
+
+impl ThrustTargetRate {
+    /// Reads the configured rate of the displayed thrust target, if the
+    /// display is to be smoothed
+    pub fn read() -> Option<ThrustRate> {
+        let rate = Self::read_raw();
+        if rate > 0. {
+            Some(Force::new::<pound_force>(rate) / Time::new::<uom::si::time::second>(1.))
+        } else {
+            None
+        }
+    }
+}
+
+impl FlightDataRecorderDropped {
+    /// Publishes the number of failed publishes to the flight data recorder
+    pub fn set(count: u64) {
+        Self::set_raw(count as f64);
+    }
+}
+
+impl FlightDataRecorderRate {
+    /// Reads the interval between recorded snapshots
+    ///
+    /// When no rate has been configured, every step is recorded.
+    pub fn read_interval() -> Time {
+        let rate = Self::read_raw();
+        if rate > 0. {
+            Frequency::new::<hertz>(rate).recip()
+        } else {
+            Time::new::<uom::si::time::second>(0.)
+        }
+    }
+}
+
 impl FlightDataRecorderEnabled {
     pub fn read() -> bool {
         Self::read_raw() == Boolean::True
@@ -343,6 +497,68 @@ impl simconnect_sys::NotificationGroup for NotificationGroup {
     }
 }
 
+/// Client event IDs for recorder events start here to avoid colliding with the
+/// throttle events
+const RECORDER_EVENT_ID_BASE: u32 = 0x100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+pub enum RecorderEventType {
+    /// Marks the current frame in the flight data recording
+    ///
+    /// The event data is recorded as the marker value.
+    Marker,
+}
+
+impl simconnect_sys::EventType for RecorderEventType {
+    type EventsIter = &'static [simconnect_sys::EventDefinition<Self>];
+
+    fn to_ffi(&self) -> simconnect_sys::ffi::RawEventId {
+        self.to_u32()
+            .map(|id| simconnect_sys::ffi::RawEventId(id + RECORDER_EVENT_ID_BASE))
+            .unwrap()
+    }
+
+    fn from_ffi(raw: simconnect_sys::ffi::RawEventId) -> Option<Self> {
+        raw.0
+            .checked_sub(RECORDER_EVENT_ID_BASE)
+            .and_then(Self::from_u32)
+    }
+
+    fn event_definitions() -> Self::EventsIter {
+        &[simconnect_sys::EventDefinition {
+            name: "FADEC.RECORD_MARKER",
+            event: Self::Marker,
+            is_maskable: false,
+        }]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+pub enum RecorderNotificationGroup {
+    Recorder = 1,
+}
+
+impl simconnect_sys::NotificationGroup for RecorderNotificationGroup {
+    type GroupsIter = &'static [simconnect_sys::NotificationGroupDefinition<Self>];
+    type EventType = RecorderEventType;
+    fn to_ffi(&self) -> simconnect_sys::ffi::RawNotificationGroupId {
+        self.to_u32()
+            .map(simconnect_sys::ffi::RawNotificationGroupId)
+            .unwrap()
+    }
+
+    fn from_ffi(raw: simconnect_sys::ffi::RawNotificationGroupId) -> Option<Self> {
+        Self::from_u32(raw.0)
+    }
+
+    fn group_definitions() -> Self::GroupsIter {
+        &[simconnect_sys::NotificationGroupDefinition {
+            group: Self::Recorder,
+            priority: simconnect_sys::ffi::NotificationGroupPriority::DEFAULT,
+        }]
+    }
+}
+
 #[derive(Debug)]
 #[repr(C)]
 pub struct EngineDataControl {
@@ -350,6 +566,54 @@ pub struct EngineDataControl {
     pub throttle_engine2: ThrottlePercent,
 }
 
+impl EngineDataControl {
+    /// The number of values sent to the simulator
+    pub const LEN: usize = 2;
+
+    /// The values sent to the simulator, in the order of the data definitions
+    #[cfg(test)]
+    pub fn as_slice(&self) -> &[f64] {
+        // The struct is `repr(C)` and each field is a transparent `f64`, so it
+        // is laid out as `[f64; Self::LEN]`
+        unsafe { std::slice::from_raw_parts(self as *const Self as *const f64, Self::LEN) }
+    }
+}
+
+/// The number of values given does not match the number of data definitions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DataLengthMismatch {
+    /// The number of values expected
+    pub expected: usize,
+
+    /// The number of values given
+    pub actual: usize,
+}
+
+impl fmt::Display for DataLengthMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected {} values, got {}", self.expected, self.actual)
+    }
+}
+
+impl std::error::Error for DataLengthMismatch {}
+
+impl TryFrom<&[f64]> for EngineDataControl {
+    type Error = DataLengthMismatch;
+
+    fn try_from(values: &[f64]) -> Result<Self, Self::Error> {
+        match *values {
+            [throttle_engine1, throttle_engine2] => Ok(Self {
+                throttle_engine1: ThrottlePercent::from(throttle_engine1),
+                throttle_engine2: ThrottlePercent::from(throttle_engine2),
+            }),
+            _ => Err(DataLengthMismatch {
+                expected: Self::LEN,
+                actual: values.len(),
+            }),
+        }
+    }
+}
+
 impl simconnect_sys::DataDefinitionGroup for EngineDataControl {
     type DataDefsIter = &'static [simconnect_sys::DataDefinition];
 
@@ -358,17 +622,94 @@ impl simconnect_sys::DataDefinitionGroup for EngineDataControl {
     }
 
     fn data_definitions() -> Self::DataDefsIter {
-        &[
-            simconnect_sys::DataDefinition {
-                name: "GENERAL ENG THROTTLE LEVER POSITION:1",
-                unit: "Percent",
-                datum_type: simconnect_sys::ffi::DataType::Float64,
-            },
-            simconnect_sys::DataDefinition {
-                name: "GENERAL ENG THROTTLE LEVER POSITION:2",
-                unit: "Percent",
-                datum_type: simconnect_sys::ffi::DataType::Float64,
+        const DEFINITIONS: &[simconnect_sys::DataDefinition] =
+            &[Throttle::data_definition(1), Throttle::data_definition(2)];
+        DEFINITIONS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn engine_data_control_round_trips_through_slice() {
+        let control = EngineDataControl::try_from(&[55.0, 60.0][..]).unwrap();
+
+        assert_eq!(ThrottlePercent::from(55.0), control.throttle_engine1);
+        assert_eq!(ThrottlePercent::from(60.0), control.throttle_engine2);
+        assert_eq!(&[55.0, 60.0], control.as_slice());
+        assert_eq!(
+            size_of::<EngineDataControl>(),
+            size_of_val(control.as_slice())
+        );
+        assert_eq!(
+            <EngineDataControl as simconnect_sys::DataDefinitionGroup>::data_definitions().len(),
+            EngineDataControl::LEN
+        );
+    }
+
+    #[test]
+    fn engine_data_control_rejects_wrong_length() {
+        assert_eq!(
+            DataLengthMismatch {
+                expected: 2,
+                actual: 3
             },
-        ]
+            EngineDataControl::try_from(&[55.0, 60.0, 65.0][..]).unwrap_err()
+        );
+        assert!(EngineDataControl::try_from(&[][..]).is_err());
+    }
+
+    #[test]
+    fn computed_pressure_altitude_matches_direct_reading_under_standard_qnh() {
+        for &reading in &[-500., 0., 10_000., 41_000.] {
+            let direct = avmath::isa::PressureAltitude::new::<foot>(reading);
+            let computed = pressure_altitude_from_indicated(
+                Length::new::<foot>(reading),
+                AltimeterSetting::standard(),
+            );
+
+            assert!(
+                (computed - direct).abs() < Length::new::<foot>(1e-6),
+                "computed {:?} did not match direct {:?}",
+                computed,
+                direct
+            );
+        }
+    }
+}
+
+/// Client event IDs for system events start here to avoid colliding with the
+/// throttle and recorder events
+const SYSTEM_EVENT_ID_BASE: u32 = 0x200;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+pub enum SystemEventType {
+    /// The simulation has started or restarted, such as when a flight is loaded
+    SimStart,
+}
+
+impl simconnect_sys::EventType for SystemEventType {
+    type EventsIter = &'static [simconnect_sys::EventDefinition<Self>];
+
+    fn to_ffi(&self) -> simconnect_sys::ffi::RawEventId {
+        self.to_u32()
+            .map(|id| simconnect_sys::ffi::RawEventId(id + SYSTEM_EVENT_ID_BASE))
+            .unwrap()
+    }
+
+    fn from_ffi(raw: simconnect_sys::ffi::RawEventId) -> Option<Self> {
+        raw.0
+            .checked_sub(SYSTEM_EVENT_ID_BASE)
+            .and_then(Self::from_u32)
+    }
+
+    fn event_definitions() -> Self::EventsIter {
+        &[simconnect_sys::EventDefinition {
+            name: "SimStart",
+            event: Self::SimStart,
+            is_maskable: false,
+        }]
     }
 }
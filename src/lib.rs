@@ -28,34 +28,32 @@ static GAUGE: parking_lot::Mutex<Option<gauges::FdGauge>> = parking_lot::const_m
 /// The primary entry point for Microsoft Flight Simulator modules built on
 /// top of the legacy Gauge API. This function will be called externally by
 /// the simulator as certain events occur.
+///
+/// A panic within the gauge is logged and reported to the simulator as a
+/// failure, as unwinding across the FFI boundary is undefined behavior.
 #[no_mangle]
 pub extern "C" fn FdGauge_gauge_callback(
     _ctx: gauge_sys::ffi::FsContext,
     raw_service_id: RawServiceId,
     extra_data: *const std::ffi::c_void,
 ) -> bool {
+    catch_panic(|| gauge_callback(raw_service_id, extra_data))
+}
+
+/// Dispatches a gauge service request to the installed gauge
+fn gauge_callback(raw_service_id: RawServiceId, extra_data: *const std::ffi::c_void) -> bool {
     if let Some(service_id) = ServiceId::from_ffi(raw_service_id) {
         match service_id {
             ServiceId::PreInstall => true,
-            ServiceId::PostInstall => {
-                let mut gauge = GAUGE.lock();
-                if gauge.is_none() {
-                    let new_gauge = gauges::FdGauge::new();
-                    *gauge = new_gauge.ok();
-                    gauge.is_some()
-                } else {
-                    true
-                }
-            }
+            ServiceId::PostInstall => post_install(
+                &mut GAUGE.lock(),
+                gauges::FdGauge::reset_persisted_state,
+                gauges::FdGauge::new,
+            ),
             ServiceId::PreDraw => {
                 let draw_data =
                     unsafe { (extra_data as *const gauge_sys::ffi::GaugeDrawData).as_ref() };
-                let mut gauge = GAUGE.lock();
-                if let (Some(g), Some(data)) = (gauge.as_mut(), draw_data) {
-                    g.on_update(data).is_ok()
-                } else {
-                    false
-                }
+                pre_draw(GAUGE.lock().as_mut(), draw_data, gauges::FdGauge::on_update)
             }
             ServiceId::PreKill => {
                 GAUGE.lock().take();
@@ -68,3 +66,157 @@ pub extern "C" fn FdGauge_gauge_callback(
         false
     }
 }
+
+/// Runs a gauge call, logging a panic and reporting it as a failure rather
+/// than letting it unwind into the simulator
+///
+/// The gauge is left installed, so that later frames are still handled if the
+/// panic was specific to a single frame. On targets that abort on panic, a
+/// panic cannot be caught and this has no effect.
+fn catch_panic(call: impl FnOnce() -> bool) -> bool {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(call)) {
+        Ok(handled) => handled,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("unknown cause");
+            eprintln!("Gauge panicked: {}", message);
+            false
+        }
+    }
+}
+
+/// Installs a gauge if none is installed, returning whether a gauge is
+/// installed
+///
+/// Named variables written by a gauge outlive it when the gauge is killed, so
+/// a reinstalled gauge would otherwise inherit the last values written by its
+/// predecessor. Such state is reset before a new gauge is created so that
+/// every installation starts from a known state.
+fn post_install<G, E>(
+    gauge: &mut Option<G>,
+    reset: impl FnOnce(),
+    create: impl FnOnce() -> Result<G, E>,
+) -> bool {
+    if gauge.is_none() {
+        reset();
+        *gauge = create().ok();
+    }
+    gauge.is_some()
+}
+
+/// Steps an installed gauge with the draw data for a frame, returning whether
+/// the frame was handled successfully
+///
+/// The simulator may provide no draw data for a frame. Such frames are skipped
+/// without stepping the gauge, so that it is never stepped with timing from a
+/// prior frame, and are not reported as failures. Failing to update the gauge
+/// or having no gauge installed are reported as failures.
+fn pre_draw<G, E: std::fmt::Display>(
+    gauge: Option<&mut G>,
+    draw_data: Option<&gauge_sys::ffi::GaugeDrawData>,
+    update: impl FnOnce(&mut G, &gauge_sys::ffi::GaugeDrawData) -> Result<(), E>,
+) -> bool {
+    match (gauge, draw_data) {
+        (Some(g), Some(data)) => match update(g, data) {
+            Ok(()) => true,
+            Err(err) => {
+                eprintln!("Error updating gauge: {}", err);
+                false
+            }
+        },
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draw_data(t: f64) -> gauge_sys::ffi::GaugeDrawData {
+        gauge_sys::ffi::GaugeDrawData {
+            mx: 0.,
+            my: 0.,
+            t,
+            dt: 1. / 30.,
+            window_width: 0,
+            window_height: 0,
+            framebuffer_width: 0,
+            framebuffer_height: 0,
+        }
+    }
+
+    fn record(times: &mut Vec<f64>, data: &gauge_sys::ffi::GaugeDrawData) -> Result<(), String> {
+        times.push(data.t);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_draw_data_skips_frame_without_failing() {
+        let mut times = Vec::new();
+
+        assert!(pre_draw(Some(&mut times), Some(&draw_data(1.)), record));
+        assert!(pre_draw(Some(&mut times), None, record));
+        assert!(pre_draw(Some(&mut times), Some(&draw_data(2.)), record));
+
+        assert_eq!(vec![1., 2.], times);
+    }
+
+    #[test]
+    fn reinstall_resets_persisted_state_before_creating_gauge() {
+        let persisted = std::cell::Cell::new(Some("CLB"));
+        let reset = || persisted.set(None);
+        let create = || Ok::<_, ()>(persisted.get());
+
+        let mut gauge = None;
+        assert!(post_install(&mut gauge, reset, create));
+        assert_eq!(Some(None), gauge);
+
+        // The gauge writes state while installed, which survives a kill
+        persisted.set(Some("TO"));
+        assert!(post_install(&mut gauge, reset, create));
+        assert_eq!(Some("TO"), persisted.get());
+        gauge.take();
+
+        assert!(post_install(&mut gauge, reset, create));
+        assert_eq!(Some(None), gauge);
+        assert_eq!(None, persisted.get());
+    }
+
+    #[test]
+    fn failed_install_leaves_no_gauge() {
+        let mut gauge: Option<()> = None;
+
+        assert!(!post_install(&mut gauge, || {}, || Err("failed")));
+        assert_eq!(None, gauge);
+    }
+
+    #[test]
+    fn update_errors_and_missing_gauge_fail_frame() {
+        let mut times: Vec<f64> = Vec::new();
+
+        assert!(!pre_draw(Some(&mut times), Some(&draw_data(1.)), |_, _| {
+            Err("failed")
+        }));
+        assert!(!pre_draw(None, Some(&draw_data(1.)), record));
+    }
+
+    #[test]
+    fn panicking_update_is_caught_and_fails_frame() {
+        fn panicking(_: &mut Vec<f64>, data: &gauge_sys::ffi::GaugeDrawData) -> Result<(), String> {
+            panic!("update failed at {}", data.t)
+        }
+
+        let mut times = Vec::new();
+        let mut step = |t, update: fn(&mut Vec<f64>, &_) -> _| {
+            catch_panic(|| pre_draw(Some(&mut times), Some(&draw_data(t)), update))
+        };
+
+        assert!(!step(1., panicking));
+        assert!(step(2., record));
+        assert_eq!(vec![2.], times);
+    }
+}
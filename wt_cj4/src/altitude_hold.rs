@@ -0,0 +1,194 @@
+//! An altitude-hold loop for a speed and thrust cascade
+//!
+//! The altitude hold is the outer loop of a cascade. It compares the pressure
+//! altitude against a target altitude and commands a vertical speed, which an
+//! inner speed or thrust loop is then responsible for achieving.
+
+use avmath::isa::PressureAltitude;
+use uom::si::{
+    f64::*,
+    frequency::hertz,
+    length::foot,
+    ratio::ratio,
+    time::{minute, second},
+    velocity::foot_per_minute,
+};
+use wt_systems::pid::{
+    cascade::CascadeConfiguration,
+    integral_zeroing::{PidConfiguration, PidController, ZeroStrategy},
+    DerivativeSource, Pid,
+};
+
+/// The vertical speed, in feet per minute, commanded by a saturated altitude
+/// hold
+pub const MAX_VERTICAL_SPEED: f64 = 3_000.;
+
+/// Translates the output of the altitude hold into a vertical speed target
+///
+/// A positive output commands a climb and a negative output commands a
+/// descent, up to [`MAX_VERTICAL_SPEED`] in either direction.
+pub fn vertical_speed_target(output: Ratio) -> Velocity {
+    output * Velocity::new::<foot_per_minute>(MAX_VERTICAL_SPEED)
+}
+
+/// Constructs a cascade configuration with the altitude hold as its outer loop
+///
+/// The inner loop is stepped against the vertical speed target.
+pub fn cascade_configuration<InnerConfig>(
+    outer: PidConfiguration<Length>,
+    inner: InnerConfig,
+) -> CascadeConfiguration<PidConfiguration<Length>, InnerConfig, Velocity> {
+    CascadeConfiguration {
+        outer,
+        inner,
+        set_point: vertical_speed_target,
+    }
+}
+
+/// An altitude-hold controller producing a vertical speed target
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AltitudeHold {
+    target: PressureAltitude,
+    pid: PidController<Length>,
+    pid_config: PidConfiguration<Length>,
+}
+
+impl AltitudeHold {
+    /// Constructs a new altitude hold for the given target altitude
+    pub fn new(target: PressureAltitude) -> Self {
+        Self {
+            target,
+            pid: PidController::default(),
+            pid_config: AltitudeHoldPidConfiguration::default(),
+        }
+    }
+
+    /// Uses the given PID configuration for the altitude loop
+    pub fn with_pid_config(mut self, pid_config: PidConfiguration<Length>) -> Self {
+        self.pid_config = pid_config;
+        self
+    }
+
+    /// The target altitude
+    pub fn target(&self) -> PressureAltitude {
+        self.target
+    }
+
+    /// The PID configuration for the altitude loop
+    pub fn pid_config(&self) -> &PidConfiguration<Length> {
+        &self.pid_config
+    }
+
+    /// Changes the target altitude
+    ///
+    /// Any error accumulated toward the prior target is discarded.
+    pub fn set_target(&mut self, target: PressureAltitude) {
+        if target != self.target {
+            self.target = target;
+            self.pid.reset();
+        }
+    }
+
+    /// Steps the altitude loop forward in time
+    ///
+    /// Returns the vertical speed target for the inner loop, which is positive
+    /// when below the target altitude and negative when above it.
+    pub fn step(&mut self, pressure_altitude: PressureAltitude, delta_t: Time) -> Velocity {
+        let altitude = pressure_altitude.remove_context();
+        let output = self.pid.step(
+            self.target.remove_context() - altitude,
+            &self.pid_config,
+            altitude,
+            delta_t,
+        );
+        vertical_speed_target(output)
+    }
+}
+
+struct AltitudeHoldPidConfiguration;
+
+impl AltitudeHoldPidConfiguration {
+    #[inline]
+    fn default() -> PidConfiguration<Length> {
+        PidConfiguration {
+            gain_proportion: Ratio::new::<ratio>(1.) / Length::new::<foot>(1_000.),
+            gain_integral: Ratio::new::<ratio>(0.01)
+                / (Length::new::<foot>(1_000.) * Time::new::<minute>(1.)),
+            gain_derivative: Time::new::<second>(0.) / Length::new::<foot>(1.),
+            output_range: (Ratio::new::<ratio>(-1.), Ratio::new::<ratio>(1.)),
+            derivative_range: (Ratio::new::<ratio>(-1.), Ratio::new::<ratio>(1.)),
+            derivative_source: DerivativeSource::PlantValue,
+            integral_range: None,
+            integral_leak: Frequency::new::<hertz>(0.),
+            tracking_gain: Frequency::new::<hertz>(0.),
+            reference_step: None,
+            zero_strategy: ZeroStrategy::OnSignChange,
+            tolerance: Length::new::<foot>(0.),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wt_systems::pid::cascade::Cascade;
+
+    fn hold_at(target: f64) -> AltitudeHold {
+        AltitudeHold::new(PressureAltitude::new::<foot>(target))
+    }
+
+    #[test]
+    fn commands_descent_above_and_climb_below_target() {
+        let delta_t = Time::new::<second>(1. / 30.);
+
+        let above = hold_at(10_000.).step(PressureAltitude::new::<foot>(10_500.), delta_t);
+        assert!(above < Velocity::new::<foot_per_minute>(0.), "{:?}", above);
+
+        let below = hold_at(10_000.).step(PressureAltitude::new::<foot>(9_500.), delta_t);
+        assert!(below > Velocity::new::<foot_per_minute>(0.), "{:?}", below);
+
+        let far_below = hold_at(10_000.).step(PressureAltitude::new::<foot>(0.), delta_t);
+        assert_eq!(
+            Velocity::new::<foot_per_minute>(MAX_VERTICAL_SPEED),
+            far_below
+        );
+    }
+
+    #[test]
+    fn drives_the_set_point_of_an_inner_loop() {
+        let hold = hold_at(10_000.);
+        let inner = PidConfiguration {
+            gain_proportion: Ratio::new::<ratio>(1.) / Velocity::new::<foot_per_minute>(1_000.),
+            gain_integral: Ratio::new::<ratio>(0.)
+                / (Velocity::new::<foot_per_minute>(1.) * Time::new::<second>(1.)),
+            gain_derivative: Time::new::<second>(0.) / Velocity::new::<foot_per_minute>(1.),
+            output_range: (Ratio::new::<ratio>(-1.), Ratio::new::<ratio>(1.)),
+            derivative_range: (Ratio::new::<ratio>(-1.), Ratio::new::<ratio>(1.)),
+            derivative_source: DerivativeSource::PlantValue,
+            integral_range: None,
+            integral_leak: Frequency::new::<hertz>(0.),
+            tracking_gain: Frequency::new::<hertz>(0.),
+            reference_step: None,
+            zero_strategy: ZeroStrategy::OnSignChange,
+            tolerance: Velocity::new::<foot_per_minute>(0.),
+        };
+        let config = cascade_configuration(*hold.pid_config(), inner);
+        let mut cascade = Cascade::new(
+            PidController::<Length>::default(),
+            PidController::<Velocity>::default(),
+        );
+
+        let altitude = Length::new::<foot>(10_500.);
+        let components = cascade.step_with_components(
+            hold.target().remove_context() - altitude,
+            &config,
+            altitude,
+            Velocity::new::<foot_per_minute>(0.),
+            Time::new::<second>(1. / 30.),
+        );
+
+        assert!(components.inner_set_point < Velocity::new::<foot_per_minute>(0.));
+        assert!(components.inner.output() < Ratio::new::<ratio>(0.));
+    }
+}
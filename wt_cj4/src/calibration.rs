@@ -0,0 +1,178 @@
+//! Calibration of the FADEC thrust model against recorded engine data
+//!
+//! The FADEC assumes that the gross thrust produced by an engine is the
+//! throttle ratio multiplied by the effective maximum thrust, which is in turn
+//! the thrust efficiency multiplied by the maximum density thrust:
+//!
+//! ```text
+//! gross = throttle × efficiency × (density × density_factor + 250 pdl)
+//! ```
+//!
+//! Given recorded samples of throttle, ambient density, Mach number, and the
+//! achieved engine thrust, [`calibrate_thrust_model`] estimates the density
+//! factor and the thrust efficiency with an ordinary least-squares fit.
+
+use crate::fadec::{convert_to_gross_thrust, max_density_thrust_with_factor, BASELINE_MAX_THRUST};
+use avmath::airspeed::MachNumber;
+use uom::si::{f64::*, force::poundal, mass_density::slug_per_cubic_foot, ratio::ratio};
+
+/// The factor applied to ambient density to compute the maximum density thrust
+pub type DensityFactor = <Force as std::ops::Div<MassDensity>>::Output;
+
+/// A single recorded observation of the engine used for calibration
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CalibrationSample {
+    /// The throttle ratio commanded to the engine
+    pub throttle: Ratio,
+    /// The ambient air density
+    pub ambient_density: MassDensity,
+    /// The Mach number of the aircraft
+    pub mach_number: MachNumber,
+    /// The engine thrust achieved at the commanded throttle
+    pub thrust: Force,
+}
+
+/// The thrust model constants estimated from recorded samples
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThrustModelCalibration {
+    /// The factor applied to ambient density in the maximum density thrust
+    pub density_factor: DensityFactor,
+    /// The fraction of the maximum density thrust that the engine achieves
+    pub thrust_efficiency: Ratio,
+}
+
+impl ThrustModelCalibration {
+    /// Predicts the gross thrust for the given throttle ratio and density
+    pub fn predict_gross_thrust(&self, throttle: Ratio, ambient_density: MassDensity) -> Force {
+        max_density_thrust_with_factor(ambient_density, self.density_factor)
+            * self.thrust_efficiency
+            * throttle
+    }
+}
+
+/// Fits the thrust model constants to the recorded samples
+///
+/// Each sample is converted to gross thrust and divided by its throttle ratio,
+/// which leaves a quantity that is linear in ambient density. The slope and
+/// intercept of that line are found by least squares, and the thrust
+/// efficiency and density factor are recovered from them.
+///
+/// Samples with a non-positive throttle or non-finite values are ignored.
+/// Returns `None` if fewer than two samples remain, if the remaining samples
+/// do not span more than one density, or if the fit is not physical.
+pub fn calibrate_thrust_model(samples: &[CalibrationSample]) -> Option<ThrustModelCalibration> {
+    let points = samples.iter().filter_map(|sample| {
+        let throttle = sample.throttle.get::<ratio>();
+        let density = sample.ambient_density.get::<slug_per_cubic_foot>();
+        let gross = convert_to_gross_thrust(sample.thrust, sample.mach_number).get::<poundal>();
+        if throttle > 0. && density.is_finite() && gross.is_finite() {
+            Some((density, gross / throttle))
+        } else {
+            None
+        }
+    });
+
+    let (mut count, mut sum_x, mut sum_y, mut sum_xx, mut sum_xy) = (0., 0., 0., 0., 0.);
+    for (x, y) in points {
+        count += 1.;
+        sum_x += x;
+        sum_y += y;
+        sum_xx += x * x;
+        sum_xy += x * y;
+    }
+
+    let determinant = count * sum_xx - sum_x * sum_x;
+    if count < 2. || determinant.abs() <= f64::EPSILON * sum_xx * count {
+        return None;
+    }
+
+    let slope = (count * sum_xy - sum_x * sum_y) / determinant;
+    let intercept = (sum_y - slope * sum_x) / count;
+    let efficiency = intercept / BASELINE_MAX_THRUST;
+    if !(efficiency > 0. && efficiency.is_finite()) {
+        return None;
+    }
+
+    let density_factor: DensityFactor =
+        Force::new::<poundal>(slope / efficiency) / MassDensity::new::<slug_per_cubic_foot>(1.);
+
+    Some(ThrustModelCalibration {
+        density_factor,
+        thrust_efficiency: Ratio::new::<ratio>(efficiency),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wt_systems::testing::NoiseSource;
+
+    fn density_factor_in_ft4_per_s2(factor: DensityFactor) -> f64 {
+        (MassDensity::new::<slug_per_cubic_foot>(1.) * factor).get::<poundal>()
+    }
+
+    fn synthetic_samples(truth: ThrustModelCalibration) -> Vec<CalibrationSample> {
+        let mut noise = NoiseSource::new(969);
+        (0..50)
+            .map(|_| {
+                let throttle = Ratio::new::<ratio>(0.6 + 0.4 * (noise.next_unit() + 1.) / 2.);
+                let ambient_density =
+                    MassDensity::new::<slug_per_cubic_foot>(0.0015 + 0.0008 * noise.next_unit());
+                let mach_number = MachNumber::new::<ratio>(0.4 + 0.3 * noise.next_unit());
+                let gross = truth.predict_gross_thrust(throttle, ambient_density);
+                let ram_factor = convert_to_gross_thrust(Force::new::<poundal>(1.), mach_number)
+                    .get::<poundal>();
+                CalibrationSample {
+                    throttle,
+                    ambient_density,
+                    mach_number,
+                    thrust: gross / ram_factor,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn recovers_known_constants_from_synthetic_samples() {
+        let truth = ThrustModelCalibration {
+            density_factor: Force::new::<poundal>(38_500.)
+                / MassDensity::new::<slug_per_cubic_foot>(1.),
+            thrust_efficiency: Ratio::new::<ratio>(0.88),
+        };
+
+        let fit = calibrate_thrust_model(&synthetic_samples(truth)).unwrap();
+
+        let relative_error = |expected: f64, actual: f64| ((actual - expected) / expected).abs();
+        assert!(
+            relative_error(
+                density_factor_in_ft4_per_s2(truth.density_factor),
+                density_factor_in_ft4_per_s2(fit.density_factor)
+            ) < 1e-9,
+            "{:?}",
+            fit
+        );
+        assert!(
+            relative_error(
+                truth.thrust_efficiency.get::<ratio>(),
+                fit.thrust_efficiency.get::<ratio>()
+            ) < 1e-9,
+            "{:?}",
+            fit
+        );
+    }
+
+    #[test]
+    fn single_density_cannot_be_calibrated() {
+        let sample = CalibrationSample {
+            throttle: Ratio::new::<ratio>(0.8),
+            ambient_density: MassDensity::new::<slug_per_cubic_foot>(0.0015),
+            mach_number: MachNumber::new::<ratio>(0.5),
+            thrust: Force::new::<poundal>(30_000.),
+        };
+
+        assert_eq!(calibrate_thrust_model(&[sample, sample]), None);
+        assert_eq!(calibrate_thrust_model(&[]), None);
+    }
+}
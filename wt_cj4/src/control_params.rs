@@ -2,17 +2,18 @@
 
 use std::{fmt, ops};
 use uom::num_traits::clamp;
+use uom::si::Unit;
 use uom::si::{
     f64::*,
-    force::poundal,
-    ratio::{percent, ratio},
+    force::{pound_force, poundal},
 };
 
 /// The FADEC throttle mode
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ThrottleMode {
     /// An engine at effectively idle state
+    #[default]
     Undefined,
 
     /// Cruise mode
@@ -23,12 +24,11 @@ pub enum ThrottleMode {
 
     /// Takeoff mode
     Takeoff,
-}
 
-impl Default for ThrottleMode {
-    fn default() -> Self {
-        Self::Undefined
-    }
+    /// Reverse thrust, commanded only on the ground
+    ///
+    /// Only selected when the FADEC has a reverse limit configured.
+    Reverse,
 }
 
 impl From<ThrottleMode> for f64 {
@@ -38,6 +38,7 @@ impl From<ThrottleMode> for f64 {
             ThrottleMode::Cruise => 1.,
             ThrottleMode::Climb => 2.,
             ThrottleMode::Takeoff => 3.,
+            ThrottleMode::Reverse => 4.,
         }
     }
 }
@@ -52,6 +53,8 @@ impl From<f64> for ThrottleMode {
             ThrottleMode::Climb
         } else if m < 4. {
             ThrottleMode::Takeoff
+        } else if m < 5. {
+            ThrottleMode::Reverse
         } else {
             ThrottleMode::Undefined
         }
@@ -65,6 +68,7 @@ impl fmt::Display for ThrottleMode {
             Self::Cruise => "CRU",
             Self::Climb => "CLB",
             Self::Takeoff => "TO",
+            Self::Reverse => "REV",
         };
         f.write_str(s)
     }
@@ -82,15 +86,20 @@ impl ThrottleAxis {
     const THRUST_STEP: f64 = 256.;
     const RANGE: f64 = Self::MAX_VALUE - Self::MIN_VALUE;
 
+    const REVERSE_MAX_VALUE: f64 = -16000.;
     const UNDEF_MAX_VALUE: f64 = -15250.;
     const CRUISE_MAX_VALUE: f64 = 9060.; //Visually, 6360. looks better as the boundary here.
-    const CRUISE_RANGE: f64 = Self::CRUISE_MAX_VALUE - Self::MIN_VALUE;
     const CLIMB_MAX_VALUE: f64 = 15000.;
 
     /// Minimum value
     pub const MIN: Self = Self(Self::MIN_VALUE);
     /// Maximum value
     pub const MAX: Self = Self(Self::MAX_VALUE);
+    /// The top limit for the reverse range, at the bottom of the lever travel
+    ///
+    /// Without a reverse limit configured, the reverse range is part of the
+    /// idle detent.
+    pub const REVERSE_MAX: Self = Self(Self::REVERSE_MAX_VALUE);
     /// The top limit for the undefined range
     pub const UNDEF_MAX: Self = Self(Self::UNDEF_MAX_VALUE);
     /// The top limit for the cruise range
@@ -102,6 +111,8 @@ impl ThrottleAxis {
         Self((Self::CLIMB_MAX_VALUE - Self::CRUISE_MAX_VALUE) / 2. + Self::CRUISE_MAX_VALUE);
     /// The throttle level value corresponding to the Takeoff detent
     pub const TAKEOFF: Self = Self::MAX;
+    /// The center of the axis, where a spring-centered lever rests
+    pub const CENTER: Self = Self((Self::MIN_VALUE + Self::MAX_VALUE) / 2.);
 
     /// Interprets a raw value as a throttle axis, saturating to the valid
     /// range
@@ -128,7 +139,17 @@ impl ThrottleAxis {
 
     /// Decreases the thrust axis by 1 / 128 of the full axis range
     pub fn dec(self) -> Self {
-        Self(self.0 + Self::THRUST_STEP).clamp()
+        Self(self.0 - Self::THRUST_STEP).clamp()
+    }
+
+    /// Increases the thrust axis by the step provided
+    pub fn inc_by(self, step: ThrottleAxisStep) -> Self {
+        Self(self.0 + step.0).clamp()
+    }
+
+    /// Decreases the thrust axis by the step provided
+    pub fn dec_by(self, step: ThrottleAxisStep) -> Self {
+        Self(self.0 - step.0).clamp()
     }
 
     /// Clamps the value to the valid range
@@ -138,19 +159,84 @@ impl ThrottleAxis {
 
     /// Reinterprets the axis as a ratio between the minimum and maximum values
     pub fn to_ratio(self) -> Ratio {
-        Ratio::new::<ratio>((self.0 - Self::MIN_VALUE) / Self::RANGE)
+        convert::raw_to_ratio(self.0, Self::MIN_VALUE, Self::MAX_VALUE)
+    }
+
+    /// Whether the axis is within `detent` of the center, as a portion of the
+    /// full axis range, on either side
+    pub fn is_centered(self, detent: Ratio) -> bool {
+        (self.to_ratio() - Self::CENTER.to_ratio()).abs() <= detent
     }
 
     /// Reinterprets the axis as a ratio between the minimum and maximum values
     /// for cruise flight
+    ///
+    /// Unlike [`to_ratio`](Self::to_ratio), the ratio spans only the
+    /// undefined and cruise ranges: the minimum maps to 0 and
+    /// [`CRUISE_MAX`](Self::CRUISE_MAX) maps to 1. Axis values in the climb
+    /// and takeoff ranges continue the same line beyond 1, and are not
+    /// limited to the top of the cruise range.
     pub fn normalize_cruise(self) -> Ratio {
-        Ratio::new::<ratio>((self.0 - Self::MIN_VALUE) / Self::CRUISE_RANGE)
+        convert::raw_to_ratio(self.0, Self::MIN_VALUE, Self::CRUISE_MAX_VALUE)
+    }
+
+    /// Reinterprets the axis as a ratio of full reverse
+    ///
+    /// [`REVERSE_MAX`](Self::REVERSE_MAX) maps to 0 and the minimum maps to
+    /// 1. Axis values above the reverse range map to 0.
+    pub fn normalize_reverse(self) -> Ratio {
+        convert::raw_to_ratio(
+            self.0.min(Self::REVERSE_MAX_VALUE),
+            Self::REVERSE_MAX_VALUE,
+            Self::MIN_VALUE,
+        )
+    }
+
+    /// Creates an axis value from a ratio between the minimum and maximum
+    /// values for cruise flight
+    ///
+    /// The exact inverse of [`normalize_cruise`](Self::normalize_cruise) for
+    /// any ratio that maps within the axis range. Ratios beyond the ends of
+    /// the axis saturate to [`MIN`](Self::MIN) or [`MAX`](Self::MAX).
+    pub fn from_normalized_cruise(value: Ratio) -> Self {
+        Self(convert::ratio_to_raw(
+            value,
+            Self::MIN_VALUE,
+            Self::CRUISE_MAX_VALUE,
+        ))
+        .clamp()
     }
 
     /// Creates an axis value where the throttle is position between minimum and
     /// maximum is provided
     pub fn from_ratio(value: Ratio) -> Self {
-        Self(value.get::<ratio>() * Self::RANGE + Self::MIN_VALUE).clamp()
+        Self(convert::ratio_to_raw(
+            value,
+            Self::MIN_VALUE,
+            Self::MAX_VALUE,
+        ))
+        .clamp()
+    }
+
+    /// Creates an axis value that will be commanded to the throttle
+    /// percentage provided, respecting the throttle detents
+    ///
+    /// The mapping from an axis value to a commanded percentage is not
+    /// one-to-one: within the undefined and cruise ranges, the percentage
+    /// follows the axis linearly, but any axis value in the climb or takeoff
+    /// ranges commands the percentage of that detent. The inverse chosen here
+    /// follows the axis linearly in the undefined and cruise ranges and snaps
+    /// to the [`CLIMB`](Self::CLIMB) or [`TAKEOFF`](Self::TAKEOFF) detent for
+    /// percentages falling within those ranges.
+    pub fn from_percent(value: ThrottlePercent) -> Self {
+        let axis = Self::from_ratio(value.to_ratio());
+        if axis > Self::CLIMB_MAX {
+            Self::TAKEOFF
+        } else if axis > Self::CRUISE_MAX {
+            Self::CLIMB
+        } else {
+            axis
+        }
     }
 }
 
@@ -167,6 +253,42 @@ impl fmt::Display for ThrottleAxis {
     }
 }
 
+/// The distance the throttle axis moves for a single increment or decrement
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(transparent)]
+pub struct ThrottleAxisStep(f64);
+
+impl ThrottleAxisStep {
+    /// The step used by [`ThrottleAxis::inc`], 1 / 128 of the full axis range
+    pub const DEFAULT: Self = Self(ThrottleAxis::THRUST_STEP);
+
+    /// Creates a step covering the provided portion of the full axis range,
+    /// saturating to the valid range
+    ///
+    /// Steps are limited to at least 1 / 4096 and at most the full range
+    /// of the axis.
+    pub fn from_ratio(value: Ratio) -> Self {
+        Self(clamp(
+            convert::ratio_to_normalized(value) * ThrottleAxis::RANGE,
+            ThrottleAxis::RANGE / 4096.,
+            ThrottleAxis::RANGE,
+        ))
+    }
+
+    /// Reinterprets the step as a portion of the full axis range
+    pub fn to_ratio(self) -> Ratio {
+        convert::normalized_to_ratio(self.0 / ThrottleAxis::RANGE)
+    }
+}
+
+impl Default for ThrottleAxisStep {
+    #[inline]
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
 /// A thrust value for the CJ4 in poundals
 #[derive(Clone, Copy, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -176,7 +298,6 @@ pub struct ThrustValue(f64);
 impl ThrustValue {
     const MIN_VALUE: f64 = 0.;
     const MAX_VALUE: f64 = 3600.;
-    const RANGE: f64 = Self::MAX_VALUE - Self::MIN_VALUE;
 
     /// The minimun thrust value
     pub const MIN: Self = Self(Self::MIN_VALUE);
@@ -191,13 +312,33 @@ impl ThrustValue {
     /// Creates an engine thrust value equivalent to the ratio between
     /// the minimum and maximum rated thrust values
     pub fn from_ratio(value: Ratio) -> Self {
-        Self(value.get::<ratio>() * Self::RANGE + Self::MIN_VALUE).clamp()
+        Self(convert::ratio_to_raw(
+            value,
+            Self::MIN_VALUE,
+            Self::MAX_VALUE,
+        ))
+        .clamp()
     }
 
     /// Reinterprets the engine thrust value as a ratio between the
     /// minimum and maxiumum rated thrust values
     pub fn to_ratio(self) -> Ratio {
-        Ratio::new::<ratio>((self.0 - Self::MIN_VALUE) / Self::RANGE)
+        convert::raw_to_ratio(self.0, Self::MIN_VALUE, Self::MAX_VALUE)
+    }
+
+    /// Reinterprets the engine thrust value as a force
+    pub fn to_force(self) -> Force {
+        Force::new::<poundal>(self.0)
+    }
+
+    /// Converts a thrust expressed in pound-force to engine thrust
+    pub fn from_pound_force(value: f64) -> Self {
+        Self::from_force(Force::new::<pound_force>(value))
+    }
+
+    /// Converts the engine thrust value to pound-force
+    pub fn to_pound_force(self) -> f64 {
+        self.to_force().get::<pound_force>()
     }
 
     /// Clamps the value to valid rated values
@@ -206,9 +347,16 @@ impl ThrustValue {
     }
 }
 
+impl Default for ThrustValue {
+    #[inline]
+    fn default() -> Self {
+        Self::MIN
+    }
+}
+
 impl fmt::Display for ThrustValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:.3} pdl", self.0)
+        write!(f, "{:.3} {}", self.0, poundal::abbreviation())
     }
 }
 
@@ -230,19 +378,55 @@ impl ThrottlePercent {
     /// Creates a throttle percent a ratio between the minimum and full
     /// positions
     pub fn from_ratio(value: Ratio) -> Self {
-        Self(value.get::<percent>()).clamp()
+        Self(convert::ratio_to_percent(value)).clamp()
+    }
+
+    /// Creates a throttle percent from a ratio between the minimum and full
+    /// positions, along with whether the ratio fell outside of that range and
+    /// was clamped
+    pub fn from_ratio_clamped(value: Ratio) -> (Self, bool) {
+        let unclamped = Self(convert::ratio_to_percent(value));
+        let clamped = unclamped.clamp();
+        (clamped, clamped != unclamped)
+    }
+
+    /// Creates a reverse throttle from a ratio of full reverse
+    ///
+    /// Reverse is commanded as a negative throttle position, down to the
+    /// negation of the full position.
+    pub fn from_reverse_ratio(value: Ratio) -> Self {
+        Self(-convert::ratio_to_percent(value)).clamp_reverse()
+    }
+
+    /// Whether the throttle commands reverse thrust
+    pub fn is_reverse(self) -> bool {
+        self.0 < Self::MIN_VALUE
     }
 
     /// Reinterprets the throttle percentage as a ratio between the minimum
     /// and full positions
     pub fn to_ratio(self) -> Ratio {
-        Ratio::new::<percent>(self.0)
+        convert::percent_to_ratio(self.0)
     }
 
     /// Clamps the value to valid values
     fn clamp(self) -> Self {
         Self(clamp(self.0, Self::MIN_VALUE, Self::MAX_VALUE))
     }
+
+    /// Clamps the value to valid reverse values
+    fn clamp_reverse(self) -> Self {
+        Self(clamp(self.0, -Self::MAX_VALUE, Self::MIN_VALUE))
+    }
+
+    /// Clamps the value to between `min` and `max`, within the valid range
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug builds if `min` is greater than `max`.
+    pub fn clamp_to(self, min: Self, max: Self) -> Self {
+        Self(clamp(self.0, min.0, max.0)).clamp()
+    }
 }
 
 impl ops::Add for ThrottlePercent {
@@ -281,3 +465,410 @@ impl fmt::Display for ThrottlePercent {
         write!(f, "{:.3} pct", self.0)
     }
 }
+
+/// Conversions among the forms in which control parameters are expressed
+///
+/// A control parameter may be held as a raw value within a fixed range (such
+/// as a throttle axis position or a thrust in poundals), normalized to
+/// `0..=1` across that range, as a percentage (`0..=100`), or as a [`Ratio`].
+/// A normalized value of 1 is a ratio of 1 and a percentage of 100.
+///
+/// None of these conversions clamp; callers saturate to the valid range.
+pub mod convert {
+    use uom::si::{
+        f64::Ratio,
+        ratio::{percent, ratio},
+    };
+
+    /// Normalizes a raw value so that `min` maps to 0 and `max` maps to 1
+    #[inline]
+    pub fn normalize(raw: f64, min: f64, max: f64) -> f64 {
+        (raw - min) / (max - min)
+    }
+
+    /// Expands a normalized value so that 0 maps to `min` and 1 maps to `max`
+    #[inline]
+    pub fn denormalize(normalized: f64, min: f64, max: f64) -> f64 {
+        normalized * (max - min) + min
+    }
+
+    /// Interprets a normalized value as a ratio
+    #[inline]
+    pub fn normalized_to_ratio(normalized: f64) -> Ratio {
+        Ratio::new::<ratio>(normalized)
+    }
+
+    /// Expresses a ratio as a normalized value
+    #[inline]
+    pub fn ratio_to_normalized(value: Ratio) -> f64 {
+        value.get::<ratio>()
+    }
+
+    /// Interprets a percentage as a ratio
+    #[inline]
+    pub fn percent_to_ratio(percentage: f64) -> Ratio {
+        Ratio::new::<percent>(percentage)
+    }
+
+    /// Expresses a ratio as a percentage
+    #[inline]
+    pub fn ratio_to_percent(value: Ratio) -> f64 {
+        value.get::<percent>()
+    }
+
+    /// Expresses a raw value within `min..=max` as a ratio of that range
+    #[inline]
+    pub fn raw_to_ratio(raw: f64, min: f64, max: f64) -> Ratio {
+        normalized_to_ratio(normalize(raw, min, max))
+    }
+
+    /// Expresses a ratio of the range `min..=max` as a raw value
+    #[inline]
+    pub fn ratio_to_raw(value: Ratio, min: f64, max: f64) -> f64 {
+        denormalize(ratio_to_normalized(value), min, max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uom::si::ratio::{percent, ratio};
+    use wt_systems::testing;
+
+    fn detent_percent(axis: ThrottleAxis) -> ThrottlePercent {
+        if axis > ThrottleAxis::CLIMB_MAX {
+            ThrottlePercent::from(ThrottleAxis::TAKEOFF)
+        } else if axis > ThrottleAxis::CRUISE_MAX {
+            ThrottlePercent::from(ThrottleAxis::CLIMB)
+        } else {
+            ThrottlePercent::from(axis)
+        }
+    }
+
+    #[test]
+    fn configured_step_governs_inc_and_dec() {
+        let step = ThrottleAxisStep::from_ratio(Ratio::new::<ratio>(0.25));
+        let axis = ThrottleAxis::from_raw(0.);
+
+        assert_eq!(ThrottleAxis::from_raw(8192.), axis.inc_by(step));
+        assert_eq!(ThrottleAxis::from_raw(-8192.), axis.dec_by(step));
+
+        let fine = ThrottleAxisStep::from_ratio(Ratio::new::<ratio>(1. / 1024.));
+        assert_eq!(ThrottleAxis::from_raw(32.), axis.inc_by(fine));
+        assert_eq!(ThrottleAxis::from_raw(-32.), axis.dec_by(fine));
+    }
+
+    #[test]
+    fn centered_axis_is_detected_within_detent() {
+        let detent = Ratio::new::<percent>(2.);
+
+        assert!(ThrottleAxis::CENTER.is_centered(detent));
+        assert!(ThrottleAxis::from_raw(600.).is_centered(detent));
+        assert!(ThrottleAxis::from_raw(-600.).is_centered(detent));
+        assert!(!ThrottleAxis::from_raw(700.).is_centered(detent));
+        assert!(!ThrottleAxis::MIN.is_centered(detent));
+        assert!(!ThrottleAxis::CLIMB.is_centered(detent));
+    }
+
+    #[test]
+    fn fixed_steps_move_in_opposite_directions() {
+        let axis = ThrottleAxis::from_raw(0.);
+        assert_eq!(
+            ThrottleAxis::from_raw(ThrottleAxis::THRUST_STEP),
+            axis.inc()
+        );
+        assert_eq!(
+            ThrottleAxis::from_raw(-ThrottleAxis::THRUST_STEP),
+            axis.dec()
+        );
+    }
+
+    #[test]
+    fn dec_lowers_the_axis_and_saturates_at_min() {
+        let axis = ThrottleAxis::CLIMB;
+        assert!(axis.dec() < axis);
+
+        let near_min = ThrottleAxis::from_raw(ThrottleAxis::MIN_VALUE + 100.);
+        assert_eq!(ThrottleAxis::MIN, near_min.dec());
+        assert_eq!(ThrottleAxis::MIN, ThrottleAxis::MIN.dec());
+    }
+
+    #[test]
+    fn throttle_percent_clamps_to_limits() {
+        let idle = ThrottlePercent::from_ratio(Ratio::new::<percent>(20.));
+        let derate = ThrottlePercent::from_ratio(Ratio::new::<percent>(85.));
+        let mid = ThrottlePercent::from_ratio(Ratio::new::<percent>(50.));
+
+        assert_eq!(idle, ThrottlePercent::MIN.clamp_to(idle, derate));
+        assert_eq!(derate, ThrottlePercent::MAX.clamp_to(idle, derate));
+        assert_eq!(mid, mid.clamp_to(idle, derate));
+        assert_eq!(
+            ThrottlePercent::MAX,
+            ThrottlePercent(120.).clamp_to(derate, ThrottlePercent(150.))
+        );
+    }
+
+    #[test]
+    fn throttle_percent_reports_clamping_from_ratio() {
+        assert_eq!(
+            (ThrottlePercent::MAX, true),
+            ThrottlePercent::from_ratio_clamped(Ratio::new::<ratio>(1.2))
+        );
+        assert_eq!(
+            (ThrottlePercent::MIN, true),
+            ThrottlePercent::from_ratio_clamped(Ratio::new::<ratio>(-0.1))
+        );
+        assert_eq!(
+            (ThrottlePercent(50.), false),
+            ThrottlePercent::from_ratio_clamped(Ratio::new::<ratio>(0.5))
+        );
+    }
+
+    #[test]
+    fn reverse_range_maps_to_negative_throttle() {
+        assert_eq!(
+            Ratio::new::<ratio>(0.),
+            ThrottleAxis::UNDEF_MAX.normalize_reverse()
+        );
+        assert_eq!(
+            Ratio::new::<ratio>(0.),
+            ThrottleAxis::REVERSE_MAX.normalize_reverse()
+        );
+        assert_eq!(
+            Ratio::new::<ratio>(1.),
+            ThrottleAxis::MIN.normalize_reverse()
+        );
+
+        let reverse = ThrottlePercent::from_reverse_ratio(Ratio::new::<ratio>(0.5));
+        assert_eq!(ThrottlePercent(-50.), reverse);
+        assert!(reverse.is_reverse());
+        assert!(!ThrottlePercent::MIN.is_reverse());
+        assert_eq!(
+            ThrottlePercent(-100.),
+            ThrottlePercent::from_reverse_ratio(Ratio::new::<ratio>(1.5))
+        );
+    }
+
+    #[test]
+    fn thrust_display_matches_stored_unit() {
+        let thrust = ThrustValue::from_force(Force::new::<poundal>(1_234.5));
+
+        assert_eq!(
+            format!("{:.3} {}", thrust.to_force().get::<poundal>(), "pdl"),
+            thrust.to_string()
+        );
+    }
+
+    #[test]
+    fn thrust_converts_between_poundal_and_pound_force() {
+        let thrust = ThrustValue::from_pound_force(100.);
+
+        testing::assert_equal_within_epsilon(3_217.404, thrust.to_force().get::<poundal>(), 1e-3);
+        testing::assert_equal_within_epsilon(100., thrust.to_pound_force(), 1e-9);
+    }
+
+    #[test]
+    fn stepping_saturates_at_axis_limits() {
+        let step = ThrottleAxisStep::from_ratio(Ratio::new::<ratio>(0.5));
+        assert_eq!(ThrottleAxis::MAX, ThrottleAxis::CLIMB.inc_by(step));
+        assert_eq!(
+            ThrottleAxis::MIN,
+            ThrottleAxis::from_raw(0.).dec_by(step).dec_by(step)
+        );
+    }
+
+    #[test]
+    fn step_size_is_limited_to_valid_range() {
+        assert_eq!(
+            ThrottleAxisStep::DEFAULT,
+            ThrottleAxisStep::from_ratio(Ratio::new::<ratio>(1. / 128.))
+        );
+        testing::assert_equal_within_epsilon(
+            1. / 4096.,
+            ThrottleAxisStep::from_ratio(Ratio::new::<ratio>(0.))
+                .to_ratio()
+                .get::<ratio>(),
+            1e-12,
+        );
+        testing::assert_equal_within_epsilon(
+            1.,
+            ThrottleAxisStep::from_ratio(Ratio::new::<ratio>(3.))
+                .to_ratio()
+                .get::<ratio>(),
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn from_percent_round_trips_in_undefined_and_cruise_ranges() {
+        for &raw in &[-16384., -15500., -15250., -8000., 0., 6360., 9060.] {
+            let axis = ThrottleAxis::from_raw(raw);
+            let round_trip = ThrottleAxis::from_percent(detent_percent(axis));
+            testing::assert_equal_within_epsilon(round_trip.0, axis.0, 1e-9);
+        }
+    }
+
+    #[test]
+    fn from_percent_snaps_to_climb_detent_in_climb_range() {
+        for &raw in &[9100., ThrottleAxis::CLIMB.0, 14000., 15000.] {
+            let commanded = detent_percent(ThrottleAxis::from_raw(raw));
+            let axis = ThrottleAxis::from_percent(commanded);
+            assert_eq!(ThrottleAxis::CLIMB, axis);
+            assert_eq!(commanded, ThrottlePercent::from(axis));
+        }
+    }
+
+    #[test]
+    fn from_percent_snaps_to_takeoff_detent_in_takeoff_range() {
+        for &raw in &[15001., 16000., 16384.] {
+            let commanded = detent_percent(ThrottleAxis::from_raw(raw));
+            let axis = ThrottleAxis::from_percent(commanded);
+            assert_eq!(ThrottleAxis::TAKEOFF, axis);
+            assert_eq!(commanded, ThrottlePercent::from(axis));
+        }
+    }
+
+    #[test]
+    fn from_percent_snaps_raw_percentages_to_detent() {
+        let climb_max = ThrottlePercent::from(ThrottleAxis::CLIMB_MAX);
+        let cruise_max = ThrottlePercent::from(ThrottleAxis::CRUISE_MAX);
+        let between = ThrottlePercent::from((f64::from(climb_max) + f64::from(cruise_max)) / 2.);
+
+        assert_eq!(ThrottleAxis::CLIMB, ThrottleAxis::from_percent(between));
+        assert_eq!(
+            ThrottleAxis::TAKEOFF,
+            ThrottleAxis::from_percent(ThrottlePercent::MAX)
+        );
+        assert_eq!(
+            ThrottleAxis::MIN,
+            ThrottleAxis::from_percent(ThrottlePercent::MIN)
+        );
+    }
+
+    #[test]
+    fn cruise_normalization_round_trips_across_cruise_band() {
+        for i in 0..=100 {
+            let axis = ThrottleAxis::from_raw(convert::denormalize(
+                f64::from(i) / 100.,
+                ThrottleAxis::MIN_VALUE,
+                ThrottleAxis::CRUISE_MAX_VALUE,
+            ));
+            let round_trip = ThrottleAxis::from_normalized_cruise(axis.normalize_cruise());
+            testing::assert_equal_within_epsilon(axis.0, round_trip.0, 1e-9);
+
+            let normalized = Ratio::new::<ratio>(f64::from(i) / 100.);
+            let round_trip = ThrottleAxis::from_normalized_cruise(normalized).normalize_cruise();
+            testing::assert_equal_within_epsilon(
+                normalized.get::<ratio>(),
+                round_trip.get::<ratio>(),
+                1e-12,
+            );
+        }
+        assert_eq!(
+            ThrottleAxis::MIN,
+            ThrottleAxis::from_normalized_cruise(Ratio::new::<ratio>(0.))
+        );
+        assert_eq!(
+            ThrottleAxis::CRUISE_MAX,
+            ThrottleAxis::from_normalized_cruise(Ratio::new::<ratio>(1.))
+        );
+    }
+
+    #[test]
+    fn cruise_normalization_saturates_beyond_axis() {
+        assert!(ThrottleAxis::CLIMB.normalize_cruise() > Ratio::new::<ratio>(1.));
+        assert_eq!(
+            ThrottleAxis::MAX,
+            ThrottleAxis::from_normalized_cruise(Ratio::new::<ratio>(2.))
+        );
+        assert_eq!(
+            ThrottleAxis::MIN,
+            ThrottleAxis::from_normalized_cruise(Ratio::new::<ratio>(-1.))
+        );
+    }
+
+    #[test]
+    fn conversions_agree_at_range_limits() {
+        assert_eq!(0., convert::normalize(-16384., -16384., 16384.));
+        assert_eq!(1., convert::normalize(16384., -16384., 16384.));
+        assert_eq!(-16384., convert::denormalize(0., -16384., 16384.));
+        assert_eq!(16384., convert::denormalize(1., -16384., 16384.));
+        assert_eq!(Ratio::new::<ratio>(1.), convert::percent_to_ratio(100.));
+        assert_eq!(100., convert::ratio_to_percent(Ratio::new::<ratio>(1.)));
+        assert_eq!(Ratio::new::<ratio>(0.), ThrottleAxis::MIN.to_ratio());
+        assert_eq!(Ratio::new::<ratio>(1.), ThrottleAxis::MAX.to_ratio());
+        assert_eq!(
+            ThrottlePercent::MAX,
+            ThrottlePercent::from(ThrottleAxis::MAX)
+        );
+        assert_eq!(Ratio::new::<ratio>(1.), ThrustValue::MAX.to_ratio());
+        testing::assert_equal_within_epsilon(
+            1.,
+            ThrottleAxis::CRUISE_MAX.normalize_cruise().get::<ratio>(),
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn every_axis_position_round_trips_through_each_form() {
+        for raw in -16384..=16384 {
+            let axis = ThrottleAxis::from_raw(f64::from(raw));
+            let as_ratio = axis.to_ratio();
+            let as_percent = ThrottlePercent::from(axis);
+
+            testing::assert_equal_within_epsilon(
+                convert::normalize(axis.0, -16384., 16384.),
+                as_ratio.get::<ratio>(),
+                1e-12,
+            );
+            testing::assert_equal_within_epsilon(
+                as_ratio.get::<percent>(),
+                f64::from(as_percent),
+                1e-9,
+            );
+            testing::assert_equal_within_epsilon(
+                axis.0,
+                ThrottleAxis::from_ratio(as_ratio).0,
+                1e-9,
+            );
+            testing::assert_equal_within_epsilon(axis.0, ThrottleAxis::from(as_percent).0, 1e-9);
+        }
+    }
+
+    #[test]
+    fn every_percentage_round_trips_through_ratio() {
+        for hundredths in 0..=10_000 {
+            let percentage = f64::from(hundredths) / 100.;
+            let throttle = ThrottlePercent::from(percentage);
+
+            testing::assert_equal_within_epsilon(
+                percentage / 100.,
+                convert::ratio_to_normalized(throttle.to_ratio()),
+                1e-12,
+            );
+            testing::assert_equal_within_epsilon(
+                percentage,
+                f64::from(ThrottlePercent::from_ratio(throttle.to_ratio())),
+                1e-9,
+            );
+        }
+    }
+
+    #[test]
+    fn every_thrust_value_round_trips_through_ratio() {
+        for poundals in 0..=3_600 {
+            let thrust = ThrustValue::from_force(Force::new::<poundal>(f64::from(poundals)));
+
+            testing::assert_equal_within_epsilon(
+                f64::from(poundals) / 3_600.,
+                thrust.to_ratio().get::<ratio>(),
+                1e-12,
+            );
+            testing::assert_equal_within_epsilon(
+                thrust.0,
+                ThrustValue::from_ratio(thrust.to_ratio()).0,
+                1e-9,
+            );
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Smoothing of FADEC values for display
+//!
+//! The thrust target steps whenever the FADEC changes what limits it, such as
+//! when the lever enters the climb detent. A thrust target bug that follows
+//! those steps directly flickers across the display, so the displayed target
+//! instead ramps toward the control target at a limited rate. The control
+//! loop always uses the unsmoothed target.
+
+use crate::control_params::ThrustValue;
+use uom::si::f64::{Force, Time};
+use wt_systems::filter::SlewLimiter;
+
+/// The rate at which a displayed thrust moves, in units of force per unit of
+/// time
+pub type ThrustRate = <Force as std::ops::Div<Time>>::Output;
+
+/// The thrust target displayed for an engine
+///
+/// Without a rate limit, the displayed target is the control target.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayedThrustTarget {
+    limiter: Option<SlewLimiter<Force, ThrustRate>>,
+    displayed: Option<ThrustValue>,
+}
+
+impl DisplayedThrustTarget {
+    /// Constructs a displayed thrust target that moves no faster than
+    /// `max_rate`
+    pub fn new(max_rate: ThrustRate) -> Self {
+        Self {
+            limiter: Some(SlewLimiter::new(max_rate)),
+            displayed: None,
+        }
+    }
+
+    /// Limits the rate at which the displayed target moves, or displays the
+    /// control target directly if `None`
+    pub fn set_max_rate(&mut self, max_rate: Option<ThrustRate>) {
+        match (&mut self.limiter, max_rate) {
+            (Some(limiter), Some(max_rate)) => limiter.set_max_rate(max_rate),
+            (limiter, max_rate) => *limiter = max_rate.map(SlewLimiter::new),
+        }
+    }
+
+    /// The rate at which the displayed target moves, if limited
+    pub fn max_rate(&self) -> Option<ThrustRate> {
+        self.limiter.map(|l| l.max_rate())
+    }
+
+    /// The thrust target as of the last step, if any
+    pub fn displayed(&self) -> Option<ThrustValue> {
+        self.displayed
+    }
+
+    /// Moves the displayed target toward the control target
+    ///
+    /// The first step displays the control target directly.
+    pub fn step(&mut self, target: ThrustValue, delta_t: Time) -> ThrustValue {
+        let displayed = match &mut self.limiter {
+            Some(limiter) => ThrustValue::from_force(limiter.step(target.to_force(), delta_t)),
+            None => target,
+        };
+        self.displayed = Some(displayed);
+        displayed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uom::si::{force::poundal, time::second};
+
+    #[test]
+    fn step_in_control_target_ramps_displayed_target() {
+        let delta_t = Time::new::<second>(0.1);
+        let max_rate = Force::new::<poundal>(1_500.) / Time::new::<second>(1.);
+        let mut display = DisplayedThrustTarget::new(max_rate);
+        let low = ThrustValue::from_force(Force::new::<poundal>(1_000.));
+        let high = ThrustValue::from_force(Force::new::<poundal>(3_000.));
+
+        assert_eq!(low, display.step(low, delta_t));
+
+        let mut prior = low.to_force();
+        for _ in 0..13 {
+            let displayed = display.step(high, delta_t).to_force();
+            let change = (displayed - prior).get::<poundal>();
+            assert!(change > 149.99 && change < 150.01, "{}", change);
+            prior = displayed;
+        }
+        assert!(prior < high.to_force());
+
+        for _ in 0..2 {
+            display.step(high, delta_t);
+        }
+        assert_eq!(Some(high), display.displayed());
+
+        display.set_max_rate(None);
+        assert_eq!(low, display.step(low, delta_t));
+    }
+}
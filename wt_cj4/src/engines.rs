@@ -36,6 +36,14 @@ impl EngineNumber {
     pub fn iter() -> impl IntoIterator<Item = Self> {
         iter::once(Self::Engine1).chain(iter::once(Self::Engine2))
     }
+
+    /// The engine on the opposite side of the aircraft
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Engine1 => Self::Engine2,
+            Self::Engine2 => Self::Engine1,
+        }
+    }
 }
 
 /// Bilateral engine data structure
@@ -1,23 +1,134 @@
 //! The CJ4 FADEC controller module
 
 use crate::control_params::{ThrottleAxis, ThrottleMode, ThrottlePercent, ThrustValue};
-use avmath::isa::PressureAltitude;
-use uom::num_traits::{clamp, clamp_min};
+use avmath::airspeed::MachNumber;
+use avmath::isa::{GeopotentialAltitude, PressureAltitude};
+use avmath::readings::AtmosphericReadings;
+use avmath::{calculations, constants};
+use uom::num_traits::clamp;
 use uom::si::{
     acceleration::foot_per_second_squared,
     f64::*,
     force::poundal,
     length::foot,
-    mass_rate::pound_per_second,
-    momentum::pound_foot_per_second,
+    mass_density::slug_per_cubic_foot,
     ratio::{percent, ratio},
+    temperature_interval,
+    thermodynamic_temperature::{degree_celsius, kelvin},
     time::second,
+    velocity::knot,
     volume::cubic_foot,
 };
+use wt_systems::filter::NotchFilter;
 use wt_systems::pid::{
     integral_zeroing::{PidConfiguration, PidController},
-    Pid, PidComponents,
+    presets, Configuration, Pid, PidComponents,
 };
+use wt_systems::table::Lut1;
+
+/// The number of consecutive frames with plausible readings required before
+/// the FADEC will take control of the engine
+pub const DEFAULT_STARTUP_LOCKOUT_FRAMES: u32 = 10;
+
+/// The time constant, in seconds, with which the estimated thrust follows the
+/// thrust model while the thrust reading is stale
+///
+/// Approximates the spool time of the engine, so that the climb PID sees a
+/// thrust that lags the throttle as it does with live readings.
+pub const STALE_THRUST_TIME_CONSTANT: f64 = 1.5;
+
+/// The lowest ambient density, in slugs per cubic foot, used when computing
+/// the maximum density thrust
+///
+/// The simulator can briefly report a zero or negative density while loading.
+/// Such readings are raised to this floor, which is below the density at any
+/// altitude the aircraft can reach, so that the thrust target falls back to
+/// the 250 pdl baseline rather than a degenerate value.
+pub const MINIMUM_AMBIENT_DENSITY: f64 = 1e-7;
+
+/// The result of a single step of the FADEC controller
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FadecOutput {
+    /// The throttle commanded to the engine
+    pub throttle: ThrottlePercent,
+
+    /// The thrust target for the current throttle mode
+    pub thrust_target: ThrustValue,
+
+    /// The idle floor applied for the throttle mode and whether the aircraft
+    /// is on the ground
+    pub idle_limit: ThrottlePercent,
+
+    /// The components of the most recent climb PID output
+    pub pid_components: PidComponents,
+
+    /// Whether the most recent climb PID output fell outside of the configured
+    /// output range
+    pub pid_saturated: bool,
+
+    /// Whether the selected throttle fell outside of the valid range and was
+    /// clamped before being commanded
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub throttle_clamped: bool,
+
+    /// The branch of the FADEC that set the thrust target
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub thrust_limit_source: ThrustLimitSource,
+}
+
+/// The branch of the FADEC that set the thrust target during a step
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThrustLimitSource {
+    /// The lever was passed through to the engine, as in cruise or while the
+    /// FADEC is disabled or locked out
+    #[default]
+    Lever,
+
+    /// The engine was held at idle, either in the climb detent on the ground
+    /// or with the lever in the reverse range while airborne or without a
+    /// reverse limit configured
+    Idle,
+
+    /// The last command was held because the readings were not plausible
+    Held,
+
+    /// Takeoff thrust
+    Takeoff,
+
+    /// The low-altitude branch of the climb thrust schedule
+    ClimbLowAltitude,
+
+    /// The high-altitude branch of the climb thrust schedule, limited by the
+    /// maximum density thrust
+    ClimbHighAltitude,
+
+    /// The climb thrust schedule blended with a speed-derived target
+    ClimbSpeedBlend,
+
+    /// Reverse thrust on the ground
+    Reverse,
+}
+
+impl From<FadecOutput> for (ThrustValue, ThrottlePercent) {
+    fn from(output: FadecOutput) -> Self {
+        (output.thrust_target, output.throttle)
+    }
+}
+
+/// Readings the FADEC needs in addition to the atmospheric state
+///
+/// Any structure implementing this trait, such as
+/// [`Instruments`][crate::Instruments], can be passed both to a FADEC step and
+/// to the avmath calculations accepting [`AtmosphericReadings`].
+pub trait FadecReadings: AtmosphericReadings {
+    /// Indicated airspeed
+    fn airspeed_indicated(&self) -> Velocity;
+
+    /// Whether the aircraft is on the ground
+    fn on_ground(&self) -> bool;
+}
 
 /// The CJ4 FADEC controller
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -28,221 +139,2900 @@ pub struct FadecController {
     last_pid_outputs: PidComponents,
     throttle_selected: Ratio,
     enabled: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    startup_lockout_frames: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    valid_frames: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    fixed_step: Option<Time>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    step_accumulator: Time,
+    #[cfg_attr(feature = "serde", serde(default))]
+    throttle_limits: ThrottleLimits,
+    #[cfg_attr(feature = "serde", serde(default))]
+    overspeed_protection: Option<OverspeedProtection>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    overspeed_protection_active: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    turbine_temperature_limit: Option<TurbineTemperatureLimit>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    turbine_temperature_limited: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    climb_speed_blend: Option<ClimbSpeedBlend>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    climb_speed_blend_engaged: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    error_filter: Option<NotchFilter<Force>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    climb_schedule: ClimbThrustSchedule,
+    #[cfg_attr(feature = "serde", serde(default))]
+    last_thrust_target: Option<ThrustValue>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    last_gross_thrust: Option<Force>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    holding: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    reverse_inhibited: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    active: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    throttle_clamped: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    unwind_clamped_throttle: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    passthrough_curve: PassthroughCurve,
+    #[cfg_attr(feature = "serde", serde(default))]
+    climb_output_range: Option<ClimbOutputRange>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pid_steps: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    saturated_pid_steps: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    stale_thrust_frames: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    last_raw_thrust: Option<Force>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    repeated_thrust_frames: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    estimated_thrust: Option<Force>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    thrust_limit_source: ThrustLimitSource,
 }
 
-impl Default for FadecController {
+/// The mapping from lever position to thrust used while the FADEC passes the
+/// throttle through to the engine
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PassthroughCurve {
+    /// Thrust proportional to the lever position
+    Linear,
+
+    /// Thrust proportional to the lever position raised to an exponent
+    Power {
+        /// The exponent applied to the lever position
+        exponent: f64,
+    },
+}
+
+impl PassthroughCurve {
+    /// The exponent of the default power-law curve
+    pub const DEFAULT_EXPONENT: f64 = 3.5;
+
+    /// Maps a lever position to a fraction of the rated thrust
+    pub fn apply(self, lever: Ratio) -> Ratio {
+        match self {
+            Self::Linear => lever,
+            Self::Power { exponent } => Ratio::new::<ratio>(lever.get::<ratio>().powf(exponent)),
+        }
+    }
+}
+
+impl Default for PassthroughCurve {
+    fn default() -> Self {
+        Self::Power {
+            exponent: Self::DEFAULT_EXPONENT,
+        }
+    }
+}
+
+/// The losses between the thrust of an uninstalled engine and the thrust
+/// delivered once installed in the airframe
+///
+/// Each loss is a fraction of the uninstalled thrust, and the losses are
+/// summed to give the installed thrust efficiency. The defaults split the 7%
+/// loss that was originally tuned by hand between the inlet and the bleed air
+/// and power extracted from the engine.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct InstalledThrustLosses {
+    /// Thrust lost to the total pressure lost through the inlet duct
+    pub inlet: Ratio,
+
+    /// Thrust lost to bleed air and shaft power extracted for aircraft
+    /// systems
+    pub bleed: Ratio,
+}
+
+impl InstalledThrustLosses {
+    /// The fraction of the uninstalled thrust delivered by the installed
+    /// engine
+    pub fn efficiency(&self) -> Ratio {
+        Ratio::new::<ratio>(1.) - self.inlet - self.bleed
+    }
+}
+
+impl Default for InstalledThrustLosses {
     fn default() -> Self {
         Self {
-            climb_pid_config: ClimbFadecPidConfiguration::default(),
-            pid_state: PidController::default(),
-            last_pid_outputs: PidComponents::default(),
-            throttle_selected: Ratio::new::<ratio>(0.),
-            enabled: true,
+            inlet: Ratio::new::<percent>(3.),
+            bleed: Ratio::new::<percent>(4.),
         }
     }
 }
 
-impl FadecController {
-    /// Provides read access to view the current PID configuration
-    pub fn pid_config(&self) -> &PidConfiguration<Force> {
-        &self.climb_pid_config
+/// The thrust targets followed by the FADEC in the climb detent
+///
+/// Breakpoints are given in pressure altitude, with thrust interpolated
+/// linearly between them and clamped beyond the edges.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClimbThrustSchedule {
+    /// The fraction of the maximum thrust available at the ambient density
+    /// that may be commanded
+    ///
+    /// Also scales the thrust commanded from the lever in cruise. Defaults to
+    /// the efficiency of the default [`InstalledThrustLosses`].
+    pub thrust_efficiency: Ratio,
+
+    /// The thrust target at low altitudes, used while the maximum effective
+    /// thrust is sufficient to achieve it
+    pub low_altitude_thrust: [(Length, Force); 2],
+
+    /// The thrust withheld from the maximum effective thrust at high
+    /// altitudes
+    pub high_altitude_loss: [(Length, Force); 2],
+}
+
+/// Tuning for a FADEC controller that can be loaded without recompiling
+///
+/// Quantities are given as plain numbers in SI base units. Any field that is
+/// omitted takes its default value.
+#[cfg(feature = "serde")]
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct FadecConfig {
+    /// Whether the FADEC is enabled
+    pub enabled: bool,
+
+    /// The configuration of the climb thrust PID
+    pub climb_pid: PidConfiguration<Force>,
+
+    /// The climb thrust schedule
+    pub climb_schedule: ClimbThrustSchedule,
+
+    /// The lever to thrust mapping used while the FADEC is disabled
+    pub passthrough_curve: PassthroughCurve,
+
+    /// The blend of the climb thrust schedule with a speed-derived target,
+    /// if the climb thrust should respond to the airspeed
+    pub climb_speed_blend: Option<ClimbSpeedBlend>,
+
+    /// The climb PID output range by pressure altitude, if it should replace
+    /// the fixed output range of the climb PID
+    pub climb_output_range: Option<ClimbOutputRange>,
+
+    /// The installation losses from which to derive the thrust efficiency of
+    /// the climb schedule, if they should replace its thrust efficiency
+    pub installation_losses: Option<InstalledThrustLosses>,
+
+    /// The number of consecutive frames with an unchanged thrust reading
+    /// after which the thrust is estimated from the thrust model, if the
+    /// FADEC should detect stale thrust readings
+    pub stale_thrust_frames: Option<u32>,
+
+    /// The ITT model and limit with which to cap the commanded thrust, if the
+    /// FADEC should protect the turbine temperature
+    pub turbine_temperature_limit: Option<TurbineTemperatureLimit>,
+}
+
+#[cfg(feature = "serde")]
+impl Default for FadecConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            climb_pid: ClimbFadecPidConfiguration::default(),
+            climb_schedule: ClimbThrustSchedule::default(),
+            passthrough_curve: PassthroughCurve::default(),
+            climb_speed_blend: None,
+            climb_output_range: None,
+            installation_losses: None,
+            stale_thrust_frames: None,
+            turbine_temperature_limit: None,
+        }
     }
+}
 
-    /// Provides read access to the current PID state
-    pub fn pid_state(&self) -> &PidController<Force> {
-        &self.pid_state
+impl Default for ClimbThrustSchedule {
+    fn default() -> Self {
+        Self {
+            thrust_efficiency: InstalledThrustLosses::default().efficiency(),
+            low_altitude_thrust: [
+                (
+                    Length::new::<foot>(0.),
+                    Force::new::<poundal>(2050. + 7000. / 24.),
+                ),
+                (Length::new::<foot>(7000.), Force::new::<poundal>(2050.)),
+            ],
+            high_altitude_loss: [
+                (Length::new::<foot>(35000.), Force::new::<poundal>(0.)),
+                (
+                    Length::new::<foot>(35000. + 110. * 64.),
+                    Force::new::<poundal>(110.),
+                ),
+            ],
+        }
     }
+}
 
-    /// Provides read access to the current PID state
-    pub fn last_pid_outputs(&self) -> PidComponents {
-        self.last_pid_outputs
+impl ClimbThrustSchedule {
+    /// Derives the thrust efficiency from the given installation losses
+    pub fn with_installation_losses(mut self, losses: InstalledThrustLosses) -> Self {
+        self.thrust_efficiency = losses.efficiency();
+        self
     }
 
-    /// The currently configured throttle value
-    pub fn throttle_selected(&self) -> Ratio {
-        self.throttle_selected
+    /// The thrust target at low altitudes
+    pub fn low_altitude_thrust(&self, pressure_altitude: PressureAltitude) -> Force {
+        Lut1::new(self.low_altitude_thrust).lookup(pressure_altitude.remove_context())
     }
 
-    /// Whether or not the FADEC module is enabled
-    pub fn is_enabled(&self) -> bool {
-        self.enabled
+    /// The thrust withheld from the maximum effective thrust at high
+    /// altitudes
+    pub fn high_altitude_loss(&self, pressure_altitude: PressureAltitude) -> Force {
+        Lut1::new(self.high_altitude_loss).lookup(pressure_altitude.remove_context())
     }
+}
 
-    /// Steps the FADEC controller to command the virtual throttle lever
-    /// position changes required to obtain the desired thrust based on the
-    /// current throttle mode
-    #[allow(clippy::too_many_arguments)] // TODO reduce this out some
-    pub fn get_desired_throttle(
-        &mut self,
-        current_throttle: Ratio,
-        throttle_mode: ThrottleMode,
-        engine_thrust: Force,
-        mach_number: Ratio,
-        ambient_density: MassDensity,
-        pressure_altitude: PressureAltitude,
-        delta_t: Time,
-    ) -> (ThrustValue, ThrottlePercent) {
-        if !self.enabled {
-            self.throttle_selected = current_throttle;
-            let throttle_exp = Ratio::new::<ratio>(self.throttle_selected.get::<ratio>().powf(3.5));
-            return (
-                ThrustValue::from_ratio(throttle_exp),
-                ThrottlePercent::from_ratio(self.throttle_selected),
-            );
-        }
+/// Limits on the throttle commanded to an engine
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThrottleLimits {
+    /// The lowest throttle commanded, such as to hold a minimum idle
+    pub idle: ThrottlePercent,
 
-        let thrust_efficiency = Ratio::new::<percent>(93.0);
+    /// The highest throttle commanded, such as to derate the engine
+    pub max: ThrottlePercent,
 
-        match throttle_mode {
-            ThrottleMode::Takeoff => {
-                //self.pid_state.reset();
-                (ThrustValue::MAX, ThrottlePercent::MAX)
-            }
-            ThrottleMode::Climb => {
-                let gross_thrust = convert_to_gross_thrust(engine_thrust, mach_number);
-                let max_density_thrust = get_max_density_thrust(ambient_density);
-                let max_effective_thrust = max_density_thrust * thrust_efficiency;
+    /// The lowest throttle commanded while on the ground, if distinct from
+    /// the flight idle
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ground_idle: Option<ThrottlePercent>,
 
-                // println!("Raw thrust: {:.3}, Airspeed: {:.3} M, Gross thrust: {:.3}, Ambient density: {:.4}, Max density thrust: {:.3}, altitude: {:.0}", engine_thrust.into_format_args(poundal, Abbreviation), mach_number.into_format_args(ratio, Abbreviation), gross_thrust.into_format_args(poundal, Abbreviation), ambient_density.into_format_args(slug_per_cubic_foot, Abbreviation), max_density_thrust.into_format_args(poundal, Abbreviation), pressure_altitude.remove_context().into_format_args(foot, Abbreviation));
+    /// The reverse throttle commanded on the ground with the lever at the
+    /// bottom of the reverse range, or `None` if the engine does not reverse
+    ///
+    /// The reverse throttle follows the lever linearly from idle at the top
+    /// of the reverse range.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_reverse: Option<ThrottlePercent>,
+}
+
+impl ThrottleLimits {
+    /// Selects the idle floor for the throttle mode and whether the aircraft
+    /// is on the ground
+    pub fn idle_for(&self, on_ground: bool) -> ThrottlePercent {
+        if on_ground {
+            self.ground_idle.unwrap_or(self.idle)
+        } else {
+            self.idle
+        }
+    }
+}
 
-                let base_thrust = Force::new::<poundal>(2050.);
-                let low_altitude_thrust_gain =
-                    calculate_low_altitude_thrust_gain(pressure_altitude);
-                let low_altitude_thrust_target: Force = base_thrust + low_altitude_thrust_gain;
+impl Default for ThrottleLimits {
+    fn default() -> Self {
+        Self {
+            idle: ThrottlePercent::MIN,
+            max: ThrottlePercent::MAX,
+            ground_idle: None,
+            max_reverse: None,
+        }
+    }
+}
 
-                let thrust_target: Force = if max_effective_thrust < low_altitude_thrust_target {
-                    let high_altitude_thrust_loss =
-                        calculate_high_altitude_thrust_loss(pressure_altitude);
-                    max_effective_thrust - high_altitude_thrust_loss
+/// Airspeed limits near which the FADEC reduces the climb thrust target to
+/// avoid an overspeed
+///
+/// Within the margin below either limit, the thrust target is scaled down
+/// linearly, reaching zero at the limit. The more restrictive of the airspeed
+/// and Mach reductions applies.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OverspeedProtection {
+    /// The maximum operating indicated airspeed (Vmo)
+    pub vmo: Velocity,
 
-                // println!(
-                //     "High altitude thrust target: {:.3}",
-                //     high_altitude_thrust_target.into_format_args(poundal, Abbreviation)
-                // );
-                } else {
-                    // println!(
-                    //     "Low altitude thrust target: {:.3}",
-                    //     low_altitude_thrust_target.into_format_args(poundal, Abbreviation)
-                    // );
+    /// The maximum operating Mach number (Mmo)
+    pub mmo: MachNumber,
 
-                    low_altitude_thrust_target
-                };
+    /// The indicated airspeed below Vmo at which thrust reduction begins
+    pub airspeed_margin: Velocity,
 
-                let error = thrust_target - gross_thrust;
+    /// The Mach number below Mmo at which thrust reduction begins
+    pub mach_margin: Ratio,
+}
 
-                self.last_pid_outputs = self.pid_state.step_with_components(
-                    error,
-                    &self.climb_pid_config,
-                    gross_thrust,
-                    delta_t,
-                );
+impl Default for OverspeedProtection {
+    /// The CJ4 limits of 305 KIAS and Mach 0.77
+    fn default() -> Self {
+        Self {
+            vmo: Velocity::new::<knot>(305.),
+            mmo: MachNumber::new::<ratio>(0.77),
+            airspeed_margin: Velocity::new::<knot>(10.),
+            mach_margin: Ratio::new::<ratio>(0.02),
+        }
+    }
+}
 
-                self.throttle_selected += self.last_pid_outputs.output();
-                // println!("Thrust target: {:.4} (error: {:+.4}); commanding change of {:+.4} to {:.4} of maximum", thrust_target.into_format_args(poundal, Abbreviation), self.pid_state.prior_error().into_format_args(poundal, Abbreviation), output.into_format_args(ratio, Abbreviation), self.throttle_selected.into_format_args(ratio, Abbreviation));
+impl OverspeedProtection {
+    /// The fraction of the thrust target to retain at the given airspeed
+    pub fn thrust_scale(&self, airspeed_indicated: Velocity, mach_number: MachNumber) -> Ratio {
+        let airspeed_scale: Ratio = (self.vmo - airspeed_indicated) / self.airspeed_margin;
+        let mach_scale: Ratio =
+            (self.mmo.remove_context() - mach_number.remove_context()) / self.mach_margin;
+        let scale = if airspeed_scale < mach_scale {
+            airspeed_scale
+        } else {
+            mach_scale
+        };
 
-                (
-                    ThrustValue::from_force(thrust_target),
-                    ThrottlePercent::from_ratio(self.throttle_selected),
-                )
-            }
-            ThrottleMode::Cruise | ThrottleMode::Undefined => {
-                self.throttle_selected = current_throttle;
-                let cruise_normalized_throttle =
-                    ThrottleAxis::from_ratio(current_throttle).normalize_cruise();
-                let effective_thrust = cruise_normalized_throttle * thrust_efficiency;
+        clamp(scale, Ratio::new::<ratio>(0.), Ratio::new::<ratio>(1.))
+    }
+}
 
-                //self.pid_state.reset();
-                // println!("Current throttle: {:.4} ({:.4} of cruise; {:.4} effective); Commanding engine to {:.4} of maximum", current_throttle.into_format_args(ratio, Abbreviation), cruise_normalized_throttle.into_format_args(ratio, Abbreviation), effective_thrust.into_format_args(ratio, Abbreviation), effective_thrust.into_format_args(ratio, Abbreviation));
+/// A model of the interstage turbine temperature (ITT) and the limit to which
+/// the FADEC holds it
+///
+/// The ITT is modeled as the ambient temperature plus a rise proportional to
+/// the commanded thrust. Where the modeled ITT at the commanded thrust would
+/// exceed the limit, the thrust is capped at the highest thrust that holds the
+/// ITT at the limit, so that rated thrust is only available up to a flat
+/// rating temperature.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TurbineTemperatureLimit {
+    /// The highest ITT that may be commanded
+    pub limit: ThermodynamicTemperature,
 
-                (
-                    ThrustValue::from_ratio(effective_thrust),
-                    ThrottlePercent::from_ratio(effective_thrust),
-                )
-            }
+    /// The rise of the ITT above the ambient temperature at rated thrust
+    pub rise_at_rated_thrust: TemperatureInterval,
+}
+
+impl Default for TurbineTemperatureLimit {
+    /// A limit of 800 °C with a rise of 765 °C at rated thrust, flat rating
+    /// the engine to an ambient temperature of 35 °C
+    fn default() -> Self {
+        Self {
+            limit: ThermodynamicTemperature::new::<degree_celsius>(800.),
+            rise_at_rated_thrust: TemperatureInterval::new::<temperature_interval::kelvin>(765.),
         }
     }
 }
 
-fn calculate_low_altitude_thrust_gain(pressure_altitude: PressureAltitude) -> Force {
-    let minimum_thrust_gain = Force::new::<poundal>(0.);
-    let thrust_gain_rate = MassRate::new::<pound_per_second>(1.) / Time::new::<second>(24.);
-    let low_altitude_ceiling = PressureAltitude::new::<foot>(7000.);
+impl TurbineTemperatureLimit {
+    /// The modeled ITT at a fraction of rated thrust
+    pub fn modeled_temperature(
+        &self,
+        thrust: Ratio,
+        ambient_temperature: ThermodynamicTemperature,
+    ) -> ThermodynamicTemperature {
+        ambient_temperature + self.rise_at_rated_thrust * thrust
+    }
 
-    if pressure_altitude > low_altitude_ceiling {
-        return minimum_thrust_gain;
+    /// The highest fraction of rated thrust that holds the modeled ITT at or
+    /// below the limit, which may exceed rated thrust in cool air
+    pub fn thrust_limit(&self, ambient_temperature: ThermodynamicTemperature) -> Ratio {
+        let margin = self.limit.get::<kelvin>() - ambient_temperature.get::<kelvin>();
+        let rise = self
+            .rise_at_rated_thrust
+            .get::<temperature_interval::kelvin>();
+        Ratio::new::<ratio>((margin / rise).max(0.))
     }
+}
+
+/// Blends the climb thrust schedule with a target that holds a climb speed
+///
+/// Below the target airspeed, the speed-derived target rises from the
+/// scheduled thrust toward the maximum effective thrust, reaching it at
+/// `speed_margin` below the target. Above the target airspeed, it falls toward
+/// zero in the same way. The thrust target is then blended between the
+/// scheduled and speed-derived targets by `weight`.
+///
+/// The blend engages whenever climb thrust is scheduled. Like an autothrottle
+/// override, pulling the lever into the undefined range, or below
+/// `disconnect_below` if set, disconnects the blend: the climb PID is reset and
+/// the lever is mapped as usual for its mode. While the lever remains below
+/// `disconnect_below`, climb thrust is scheduled without the blend. The blend
+/// engages again the next time the lever is placed in the climb detent at or
+/// above `disconnect_below`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClimbSpeedBlend {
+    /// The indicated airspeed to hold in the climb
+    pub target_airspeed: Velocity,
+
+    /// The deviation from the target airspeed at which the speed-derived
+    /// target reaches its limit
+    pub speed_margin: Velocity,
+
+    /// The portion of the thrust target taken from the speed-derived target
+    pub weight: Ratio,
 
-    let altitude_reduction: Length = low_altitude_ceiling - pressure_altitude;
-    let low_altitude_thrust_gain: Force = altitude_reduction * thrust_gain_rate;
+    /// The lever position, as a ratio of the full lever travel, below which
+    /// the blend disconnects
+    ///
+    /// Must not be above the climb detent, or the blend could never engage.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub disconnect_below: Option<Ratio>,
+}
 
-    clamp_min(low_altitude_thrust_gain, minimum_thrust_gain)
+impl Default for ClimbSpeedBlend {
+    /// An even blend holding the CJ4 climb speed of 240 KIAS
+    fn default() -> Self {
+        Self {
+            target_airspeed: Velocity::new::<knot>(240.),
+            speed_margin: Velocity::new::<knot>(20.),
+            weight: Ratio::new::<ratio>(0.5),
+            disconnect_below: None,
+        }
+    }
 }
 
-fn calculate_high_altitude_thrust_loss(pressure_altitude: PressureAltitude) -> Force {
-    let minimum_thrust_loss = Force::new::<poundal>(0.);
-    let maximum_thrust_loss = Force::new::<poundal>(110.);
-    let thrust_loss_rate = MassRate::new::<pound_per_second>(1.) / Time::new::<second>(64.);
-    let high_altitude_floor = PressureAltitude::new::<foot>(35000.);
+impl ClimbSpeedBlend {
+    /// Blends the scheduled thrust target with the target needed to hold the
+    /// climb speed
+    pub fn blend(
+        &self,
+        scheduled_thrust: Force,
+        max_effective_thrust: Force,
+        airspeed_indicated: Velocity,
+    ) -> Force {
+        let one = Ratio::new::<ratio>(1.);
+        let deviation: Ratio = clamp(
+            (self.target_airspeed - airspeed_indicated) / self.speed_margin,
+            -one,
+            one,
+        );
+        let speed_thrust = if deviation > Ratio::new::<ratio>(0.) {
+            scheduled_thrust + (max_effective_thrust - scheduled_thrust) * deviation
+        } else {
+            scheduled_thrust * (one + deviation)
+        };
 
-    if pressure_altitude < high_altitude_floor {
-        return minimum_thrust_loss;
+        scheduled_thrust * (one - self.weight) + speed_thrust * self.weight
     }
 
-    let altitude_reduction: Length = pressure_altitude - high_altitude_floor;
-    let high_altitude_thrust_loss: Force = altitude_reduction * thrust_loss_rate;
+    /// Whether `disconnect_below`, if set, is at or below the climb detent
+    pub fn is_valid(&self) -> bool {
+        self.disconnect_below
+            .is_none_or(|threshold| threshold <= ThrottleAxis::CLIMB.to_ratio())
+    }
 
-    clamp(
-        high_altitude_thrust_loss,
-        minimum_thrust_loss,
-        maximum_thrust_loss,
-    )
+    /// Whether the lever has been pulled back far enough to disconnect the
+    /// blend
+    pub fn is_disconnect(&self, lever: Ratio, throttle_mode: ThrottleMode) -> bool {
+        matches!(
+            throttle_mode,
+            ThrottleMode::Undefined | ThrottleMode::Reverse
+        ) || self
+            .disconnect_below
+            .is_some_and(|threshold| lever < threshold)
+    }
 }
 
-fn convert_to_gross_thrust(thrust_in: Force, mach_in: Ratio) -> Force {
-    thrust_in * (1. + (mach_in.get::<ratio>().powi(2) / 5.)).powf(3.5)
-}
+/// Limits on the climb PID output that vary with pressure altitude
+///
+/// Breakpoints are given in pressure altitude, with each limit interpolated
+/// linearly between them and clamped beyond the edges. The lower and upper
+/// limits are independent, so the PID may be given more authority in one
+/// direction than the other.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClimbOutputRange {
+    /// The lowest output the PID may apply to the selected throttle in a
+    /// single step
+    pub lower: [(Length, Ratio); 2],
 
-fn get_max_density_thrust(ambient_density: MassDensity) -> Force {
-    let density_factor = Volume::new::<cubic_foot>(42_009.0345696695)
-        * Acceleration::new::<foot_per_second_squared>(1.);
-    let f: Force = ambient_density * density_factor;
-    f + Force::new::<poundal>(250.)
+    /// The highest output the PID may apply to the selected throttle in a
+    /// single step
+    pub upper: [(Length, Ratio); 2],
 }
 
-struct ClimbFadecPidConfiguration;
+impl ClimbOutputRange {
+    /// An output range that does not vary with altitude
+    pub fn constant(lower: Ratio, upper: Ratio) -> Self {
+        let zero = Length::new::<foot>(0.);
+        let ceiling = Length::new::<foot>(45_000.);
+        Self {
+            lower: [(zero, lower), (ceiling, lower)],
+            upper: [(zero, upper), (ceiling, upper)],
+        }
+    }
 
-impl ClimbFadecPidConfiguration {
-    #[inline]
-    fn default() -> PidConfiguration<Force> {
-        PidConfiguration {
-            gain_proportion: Ratio::new::<percent>(1.2) / Force::new::<poundal>(1_000.),
-            gain_integral: Ratio::new::<percent>(0.0001)
-                / Momentum::new::<pound_foot_per_second>(1.),
-            gain_derivative: Time::new::<second>(0.018) / Force::new::<poundal>(1_000.),
-            output_range: (Ratio::new::<percent>(-2.), Ratio::new::<percent>(2.)),
-            derivative_range: (Ratio::new::<percent>(-20.), Ratio::new::<percent>(20.)),
-            tolerance: Force::new::<poundal>(0.),
+    /// The output range at the given pressure altitude
+    pub fn at(&self, pressure_altitude: PressureAltitude) -> (Ratio, Ratio) {
+        let altitude = pressure_altitude.remove_context();
+        (
+            Lut1::new(self.lower).lookup(altitude),
+            Lut1::new(self.upper).lookup(altitude),
+        )
+    }
+}
+
+impl Default for FadecController {
+    fn default() -> Self {
+        Self {
+            climb_pid_config: ClimbFadecPidConfiguration::default(),
+            pid_state: PidController::default(),
+            last_pid_outputs: PidComponents::default(),
+            throttle_selected: Ratio::new::<ratio>(0.),
+            enabled: true,
+            startup_lockout_frames: DEFAULT_STARTUP_LOCKOUT_FRAMES,
+            valid_frames: 0,
+            fixed_step: None,
+            step_accumulator: Time::new::<second>(0.),
+            throttle_limits: ThrottleLimits::default(),
+            overspeed_protection: None,
+            overspeed_protection_active: false,
+            turbine_temperature_limit: None,
+            turbine_temperature_limited: false,
+            climb_speed_blend: None,
+            climb_speed_blend_engaged: false,
+            error_filter: None,
+            climb_schedule: ClimbThrustSchedule::default(),
+            last_thrust_target: None,
+            last_gross_thrust: None,
+            holding: false,
+            reverse_inhibited: false,
+            active: false,
+            throttle_clamped: false,
+            unwind_clamped_throttle: false,
+            passthrough_curve: PassthroughCurve::default(),
+            climb_output_range: None,
+            pid_steps: 0,
+            saturated_pid_steps: 0,
+            stale_thrust_frames: None,
+            last_raw_thrust: None,
+            repeated_thrust_frames: 0,
+            estimated_thrust: None,
+            thrust_limit_source: ThrustLimitSource::Lever,
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use uom::num_traits::zero;
-    use uom::si::mass_density::slug_per_cubic_foot;
-    use wt_systems::testing;
+impl FadecController {
+    /// Constructs a FADEC controller from a JSON [`FadecConfig`]
+    #[cfg(feature = "serde")]
+    pub fn from_config(config: &str) -> Result<Self, serde_json::Error> {
+        let config: FadecConfig = serde_json::from_str(config)?;
+        if !config
+            .climb_speed_blend
+            .is_none_or(|blend| blend.is_valid())
+        {
+            return Err(serde::de::Error::custom(
+                "climb speed blend disconnects above the climb detent",
+            ));
+        }
+        Ok(Self {
+            climb_pid_config: config.climb_pid,
+            enabled: config.enabled,
+            climb_schedule: match config.installation_losses {
+                Some(losses) => config.climb_schedule.with_installation_losses(losses),
+                None => config.climb_schedule,
+            },
+            passthrough_curve: config.passthrough_curve,
+            climb_speed_blend: config.climb_speed_blend,
+            climb_output_range: config.climb_output_range,
+            stale_thrust_frames: config.stale_thrust_frames,
+            turbine_temperature_limit: config.turbine_temperature_limit,
+            ..Self::default()
+        })
+    }
 
-    #[test]
-    fn t_get_max_density_thrust() {
-        let input = MassDensity::new::<slug_per_cubic_foot>(0.00241899350658059);
+    /// Sets the number of consecutive frames with plausible readings required
+    /// before the FADEC will take control of the engine
+    ///
+    /// Until then, the FADEC passes the throttle position through to the
+    /// engine unmodified.
+    pub fn with_startup_lockout(mut self, frames: u32) -> Self {
+        self.startup_lockout_frames = frames;
+        self
+    }
 
-        //0.03108096668
+    /// Runs the PID at a constant internal rate, independent of the frame rate
+    ///
+    /// Elapsed time is accumulated across calls, and the PID is stepped once
+    /// for each whole internal step available, with any leftover time carried
+    /// into the next call. This makes control behavior reproducible regardless
+    /// of how the simulator divides time into frames.
+    pub fn with_fixed_step(mut self, rate: Frequency) -> Self {
+        self.fixed_step = Some(rate.recip());
+        self.step_accumulator = Time::new::<second>(0.);
+        self
+    }
 
-        let expected = 0.00241899350658059 * 1000. * 1351.6 + 250.;
-        let actual = get_max_density_thrust(input).get::<poundal>();
+    /// Limits the throttle commanded to the engine to between `idle` and `max`
+    pub fn with_throttle_limits(mut self, idle: ThrottlePercent, max: ThrottlePercent) -> Self {
+        self.throttle_limits = ThrottleLimits {
+            idle,
+            max,
+            ..self.throttle_limits
+        };
+        self
+    }
 
-        testing::assert_equal_in_significant_figures(expected, actual, 12)
+    /// Sets the lever to thrust mapping used while the FADEC passes the
+    /// throttle through to the engine
+    pub fn with_passthrough_curve(mut self, curve: PassthroughCurve) -> Self {
+        self.passthrough_curve = curve;
+        self
+    }
+
+    /// Holds a distinct, higher idle while on the ground
+    ///
+    /// The flight idle set by
+    /// [`with_throttle_limits`][Self::with_throttle_limits] applies while
+    /// airborne.
+    pub fn with_ground_idle(mut self, ground_idle: ThrottlePercent) -> Self {
+        self.throttle_limits.ground_idle = Some(ground_idle);
+        self
+    }
+
+    /// Commands reverse thrust, up to `max_reverse`, while on the ground with
+    /// the lever in the reverse range
+    pub fn with_max_reverse(mut self, max_reverse: ThrottlePercent) -> Self {
+        self.throttle_limits.max_reverse = Some(max_reverse);
+        self
+    }
+
+    /// Reduces the climb thrust target as the airspeed nears the limits of
+    /// `protection`
+    pub fn with_overspeed_protection(mut self, protection: OverspeedProtection) -> Self {
+        self.overspeed_protection = Some(protection);
+        self
+    }
+
+    /// Caps the takeoff and climb thrust so that the ITT modeled by `limit`
+    /// does not exceed its limit
+    ///
+    /// The ambient temperature is derived from the ambient density at the
+    /// standard pressure for the pressure altitude. No cap is applied while
+    /// the readings do not give a usable temperature.
+    pub fn with_turbine_temperature_limit(mut self, limit: TurbineTemperatureLimit) -> Self {
+        self.turbine_temperature_limit = Some(limit);
+        self
+    }
+
+    /// Trades climb thrust to hold a climb speed, blended with the climb
+    /// thrust schedule as described by `blend`
+    ///
+    /// A `disconnect_below` above the climb detent is lowered to the detent.
+    pub fn with_climb_speed_blend(mut self, blend: ClimbSpeedBlend) -> Self {
+        let detent = ThrottleAxis::CLIMB.to_ratio();
+        self.climb_speed_blend = Some(ClimbSpeedBlend {
+            disconnect_below: blend.disconnect_below.map(|threshold| {
+                if threshold > detent {
+                    detent
+                } else {
+                    threshold
+                }
+            }),
+            ..blend
+        });
+        self
+    }
+
+    /// Filters the climb thrust error with a notch at `center` before it is
+    /// passed to the PID
+    ///
+    /// Suppresses a throttle hunt at a known frequency without slowing the
+    /// response to other changes in thrust. Higher `quality` factors produce a
+    /// narrower notch.
+    pub fn with_error_notch(mut self, center: Frequency, quality: f64) -> Self {
+        self.error_filter = Some(NotchFilter::new(center, quality));
+        self
+    }
+
+    /// Pulls the selected throttle back into the valid range whenever it is
+    /// clamped
+    ///
+    /// Without this, the climb PID can integrate the selected throttle well
+    /// past full, and the throttle stays pinned at full until the PID has
+    /// unwound the excess.
+    pub fn with_clamped_throttle_unwinding(mut self) -> Self {
+        self.unwind_clamped_throttle = true;
+        self
+    }
+
+    /// Replaces the output range of the climb PID with one that varies with
+    /// pressure altitude
+    ///
+    /// Each step, the PID output is clamped to the range at the current
+    /// pressure altitude before it is applied to the selected throttle.
+    pub fn with_climb_output_range(mut self, range: ClimbOutputRange) -> Self {
+        self.climb_output_range = Some(range);
+        self
+    }
+
+    /// Estimates the thrust from the thrust model once the thrust reading
+    /// has been unchanged for `frames` consecutive frames
+    ///
+    /// The simulator occasionally stops updating the engine thrust, which
+    /// leaves the climb PID integrating a constant error. While the reading
+    /// is stale, the climb PID is instead closed around an estimate that
+    /// follows the thrust the model predicts for the selected throttle,
+    /// starting from the last reading. The reading is used again as soon as
+    /// it changes.
+    pub fn with_stale_thrust_fallback(mut self, frames: u32) -> Self {
+        self.stale_thrust_frames = Some(frames);
+        self
+    }
+
+    /// Whether the thrust reading has been unchanged for long enough that
+    /// the thrust is being estimated from the thrust model
+    pub fn is_thrust_stale(&self) -> bool {
+        match self.stale_thrust_frames {
+            Some(frames) => self.repeated_thrust_frames >= frames,
+            None => false,
+        }
+    }
+
+    /// Bounds the momentum retained by the climb PID to `limit` in either
+    /// direction
+    ///
+    /// The climb PID output is applied as an increment to the selected
+    /// throttle, so the integral is not needed to hold a steady thrust and any
+    /// limit is stable. With the default climb gains, a limit of up to
+    /// 20,000 pdl·s keeps the integral contribution within the output range of
+    /// the PID. Larger limits allow the integral alone to saturate the output,
+    /// and with it the rate at which the throttle moves.
+    pub fn with_climb_integral_limit(mut self, limit: Momentum) -> Self {
+        self.climb_pid_config.integral_range = Some((-limit.abs(), limit.abs()));
+        self
+    }
+
+    /// Whether overspeed protection reduced the thrust target during the last
+    /// step
+    pub fn is_overspeed_protection_active(&self) -> bool {
+        self.overspeed_protection_active
+    }
+
+    /// Whether the turbine temperature limit capped the thrust during the
+    /// last step
+    pub fn is_turbine_temperature_limited(&self) -> bool {
+        self.turbine_temperature_limited
+    }
+
+    /// Whether the climb speed blend is engaged, having scheduled climb thrust
+    /// since it was last disconnected
+    pub fn is_climb_speed_blend_engaged(&self) -> bool {
+        self.climb_speed_blend_engaged
+    }
+
+    /// Whether the FADEC held its last command during the last step because
+    /// the readings were not plausible
+    pub fn is_holding(&self) -> bool {
+        self.holding
+    }
+
+    /// Whether a reverse thrust limit is configured, so that the bottom of
+    /// the lever travel selects reverse rather than idle
+    pub fn has_reverse(&self) -> bool {
+        self.throttle_limits.max_reverse.is_some()
+    }
+
+    /// Whether the FADEC refused to command reverse thrust during the last
+    /// step because the aircraft was airborne
+    pub fn is_reverse_inhibited(&self) -> bool {
+        self.reverse_inhibited
+    }
+
+    /// Whether the selected throttle fell outside of the valid range and was
+    /// clamped during the last step
+    pub fn is_throttle_clamped(&self) -> bool {
+        self.throttle_clamped
+    }
+
+    /// The branch of the FADEC that set the thrust target during the last
+    /// step
+    pub fn thrust_limit_source(&self) -> ThrustLimitSource {
+        self.thrust_limit_source
+    }
+
+    /// Whether the FADEC scheduled the engine during the last step
+    ///
+    /// Only takeoff thrust, the climb schedule and reverse thrust on the
+    /// ground count as active. Following the lever, holding at idle and
+    /// holding the last command on implausible readings do not.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// The difference between the thrust target and the gross thrust
+    /// achieved, as of the last step
+    ///
+    /// Only available when the last step scheduled climb thrust from
+    /// plausible readings.
+    pub fn thrust_error(&self) -> Option<Force> {
+        Some(self.last_thrust_target?.to_force() - self.last_gross_thrust?)
+    }
+
+    /// Whether the FADEC is still waiting for plausible readings before
+    /// taking control of the engine
+    pub fn is_locked_out(&self) -> bool {
+        self.valid_frames < self.startup_lockout_frames
+    }
+
+    /// Provides read access to view the current PID configuration
+    pub fn pid_config(&self) -> &PidConfiguration<Force> {
+        &self.climb_pid_config
+    }
+
+    /// Provides read access to the current PID state
+    pub fn pid_state(&self) -> &PidController<Force> {
+        &self.pid_state
+    }
+
+    /// Provides read access to the current PID state
+    pub fn last_pid_outputs(&self) -> PidComponents {
+        self.last_pid_outputs
+    }
+
+    /// The fraction of climb PID steps with an output outside of the
+    /// configured output range
+    ///
+    /// A duty cycle near one suggests that the output range is too narrow or
+    /// the gains are poorly tuned. Zero before the first PID step.
+    pub fn saturation_duty_cycle(&self) -> Ratio {
+        if self.pid_steps == 0 {
+            return Ratio::new::<ratio>(0.);
+        }
+        Ratio::new::<ratio>(f64::from(self.saturated_pid_steps) / f64::from(self.pid_steps))
+    }
+
+    /// The currently configured throttle value
+    pub fn throttle_selected(&self) -> Ratio {
+        self.throttle_selected
+    }
+
+    /// Whether or not the FADEC module is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Estimates the throttle needed for the engine to develop `target` thrust
+    ///
+    /// Inverts the approximate thrust model used by the climb schedule, in
+    /// which the gross thrust scales linearly with the throttle up to the
+    /// maximum density thrust. The estimate is suitable as an initial guess
+    /// for [`warm_start`][Self::warm_start] or when tuning, but does not
+    /// account for engine lag or installation losses.
+    pub fn estimate_throttle_for_thrust(
+        target: Force,
+        ambient_density: MassDensity,
+        mach_number: MachNumber,
+    ) -> ThrottlePercent {
+        let gross_thrust = convert_to_gross_thrust(target, mach_number);
+        let max_density_thrust = get_max_density_thrust(floor_ambient_density(ambient_density));
+        ThrottlePercent::from_ratio(gross_thrust / max_density_thrust)
+    }
+
+    /// Estimates the thrust that the engine develops at `throttle`
+    ///
+    /// The counterpart of
+    /// [`estimate_throttle_for_thrust`][Self::estimate_throttle_for_thrust],
+    /// subject to the same approximations.
+    pub fn estimate_thrust_for_throttle(
+        throttle: ThrottlePercent,
+        ambient_density: MassDensity,
+        mach_number: MachNumber,
+    ) -> Force {
+        let max_density_thrust = get_max_density_thrust(floor_ambient_density(ambient_density));
+        let gross_thrust = max_density_thrust * throttle.to_ratio();
+        gross_thrust
+            / convert_to_gross_thrust(Force::new::<poundal>(1.), mach_number).get::<poundal>()
+    }
+
+    /// Seeds the selected throttle from which the climb PID adjusts
+    ///
+    /// Starting from an estimate, such as from
+    /// [`estimate_throttle_for_thrust`][Self::estimate_throttle_for_thrust],
+    /// avoids hunting up from the current lever position.
+    pub fn warm_start(&mut self, throttle: ThrottlePercent) {
+        self.throttle_selected = throttle.to_ratio();
+    }
+
+    /// Steps the FADEC controller, returning the thrust target and commanded
+    /// throttle
+    ///
+    /// Equivalent to [`get_desired_output`][Self::get_desired_output], keeping
+    /// only the thrust target and commanded throttle.
+    pub fn get_desired_throttle<R: FadecReadings + ?Sized>(
+        &mut self,
+        current_throttle: Ratio,
+        throttle_mode: ThrottleMode,
+        engine_thrust: Force,
+        readings: &R,
+        delta_t: Time,
+    ) -> (ThrustValue, ThrottlePercent) {
+        self.get_desired_output(
+            current_throttle,
+            throttle_mode,
+            engine_thrust,
+            readings,
+            delta_t,
+        )
+        .into()
+    }
+
+    /// Steps the FADEC controller to command the virtual throttle lever
+    /// position changes required to obtain the desired thrust based on the
+    /// current throttle mode
+    ///
+    /// While on the ground, the climb thrust schedule is inhibited and the
+    /// engine is held at idle with the throttle in the climb detent.
+    ///
+    /// Reverse thrust is only commanded on the ground. While airborne, a lever
+    /// in the reverse range commands the flight idle instead.
+    pub fn get_desired_output<R: FadecReadings + ?Sized>(
+        &mut self,
+        current_throttle: Ratio,
+        throttle_mode: ThrottleMode,
+        engine_thrust: Force,
+        readings: &R,
+        delta_t: Time,
+    ) -> FadecOutput {
+        let (thrust, throttle) = self.schedule_throttle(
+            current_throttle,
+            throttle_mode,
+            engine_thrust,
+            readings,
+            delta_t,
+        );
+
+        let idle = self.throttle_limits.idle_for(readings.on_ground());
+        let throttle = if throttle.is_reverse() {
+            throttle
+        } else {
+            throttle.clamp_to(idle, self.throttle_limits.max)
+        };
+
+        // Only the climb schedule runs the PID, so the other branches report
+        // no PID output rather than that of the last climb step
+        let pid_components = match self.thrust_limit_source {
+            ThrustLimitSource::ClimbLowAltitude
+            | ThrustLimitSource::ClimbHighAltitude
+            | ThrustLimitSource::ClimbSpeedBlend => self.last_pid_outputs,
+            _ => PidComponents::default(),
+        };
+        let pid_output = pid_components.output();
+        FadecOutput {
+            throttle,
+            thrust_target: thrust,
+            idle_limit: idle,
+            pid_components,
+            pid_saturated: self
+                .climb_pid_config_at(readings.pressure_altitude())
+                .clamp_output(pid_output)
+                != pid_output,
+            throttle_clamped: self.throttle_clamped,
+            thrust_limit_source: self.thrust_limit_source,
+        }
+    }
+
+    /// Schedules the thrust and throttle for the current throttle mode,
+    /// before applying the throttle limits
+    fn schedule_throttle<R: FadecReadings + ?Sized>(
+        &mut self,
+        current_throttle: Ratio,
+        throttle_mode: ThrottleMode,
+        engine_thrust: Force,
+        readings: &R,
+        delta_t: Time,
+    ) -> (ThrustValue, ThrottlePercent) {
+        let mach_number = readings.mach_number();
+        let ambient_density = readings.ambient_density();
+        let pressure_altitude = readings.pressure_altitude();
+        let airspeed_indicated = readings.airspeed_indicated();
+        let on_ground = readings.on_ground();
+        let plausible = are_readings_plausible(engine_thrust, ambient_density);
+        if plausible {
+            self.valid_frames = self.valid_frames.saturating_add(1);
+        } else if self.is_locked_out() {
+            self.valid_frames = 0;
+        }
+        self.track_thrust_reading(engine_thrust);
+
+        self.overspeed_protection_active = false;
+        self.turbine_temperature_limited = false;
+        self.holding = false;
+        self.throttle_clamped = false;
+        self.reverse_inhibited = false;
+        self.active = false;
+        self.last_gross_thrust = None;
+
+        if !self.enabled || self.is_locked_out() {
+            return self.passthrough(current_throttle);
+        }
+
+        // Disconnecting hands the engine back to the mapping for the lever's
+        // mode, starting the PID afresh
+        let blend = self
+            .climb_speed_blend
+            .filter(|blend| !blend.is_disconnect(current_throttle, throttle_mode));
+        if self.climb_speed_blend_engaged && blend.is_none() {
+            self.climb_speed_blend_engaged = false;
+            self.reset_climb_pid();
+        }
+
+        let thrust_efficiency = self.climb_schedule.thrust_efficiency;
+
+        match throttle_mode {
+            ThrottleMode::Takeoff => {
+                //self.pid_state.reset();
+                self.active = true;
+                self.thrust_limit_source = ThrustLimitSource::Takeoff;
+                match self.limit_turbine_temperature(
+                    ThrustValue::MAX.to_ratio(),
+                    ambient_density,
+                    pressure_altitude,
+                ) {
+                    Some(thrust) => (
+                        ThrustValue::from_ratio(thrust),
+                        ThrottlePercent::from_ratio(thrust),
+                    ),
+                    None => (ThrustValue::MAX, ThrottlePercent::MAX),
+                }
+            }
+            ThrottleMode::Reverse if !on_ground => {
+                // Reverse thrust is never commanded in flight
+                self.reverse_inhibited = true;
+                self.throttle_selected = Ratio::new::<ratio>(0.);
+                self.thrust_limit_source = ThrustLimitSource::Idle;
+                (ThrustValue::MIN, ThrottlePercent::MIN)
+            }
+            ThrottleMode::Reverse => {
+                self.reset_climb_pid();
+                self.throttle_selected = Ratio::new::<ratio>(0.);
+                let throttle = match self.throttle_limits.max_reverse {
+                    Some(max_reverse) => {
+                        self.active = true;
+                        self.thrust_limit_source = ThrustLimitSource::Reverse;
+                        ThrottlePercent::from_reverse_ratio(
+                            ThrottleAxis::from_ratio(current_throttle).normalize_reverse()
+                                * max_reverse.to_ratio(),
+                        )
+                    }
+                    None => {
+                        self.thrust_limit_source = ThrustLimitSource::Idle;
+                        ThrottlePercent::MIN
+                    }
+                };
+                (ThrustValue::MIN, throttle)
+            }
+            ThrottleMode::Climb if on_ground => {
+                self.reset_climb_pid();
+                self.throttle_selected = Ratio::new::<ratio>(0.);
+                self.thrust_limit_source = ThrustLimitSource::Idle;
+                (ThrustValue::MIN, ThrottlePercent::MIN)
+            }
+            ThrottleMode::Climb if !plausible => {
+                // Hold the last command rather than integrating an error
+                // computed from unusable readings
+                self.holding = true;
+                self.thrust_limit_source = ThrustLimitSource::Held;
+                (
+                    self.last_thrust_target.unwrap_or(ThrustValue::MIN),
+                    ThrottlePercent::from_ratio(self.throttle_selected),
+                )
+            }
+            ThrottleMode::Climb => {
+                self.active = true;
+                let engine_thrust = if self.is_thrust_stale() {
+                    self.dead_reckon_thrust(engine_thrust, ambient_density, mach_number, delta_t)
+                } else {
+                    engine_thrust
+                };
+                let gross_thrust = convert_to_gross_thrust(engine_thrust, mach_number);
+                let max_density_thrust =
+                    get_max_density_thrust(floor_ambient_density(ambient_density));
+                let max_effective_thrust = max_density_thrust * thrust_efficiency;
+
+                // println!("Raw thrust: {:.3}, Airspeed: {:.3} M, Gross thrust: {:.3}, Ambient density: {:.4}, Max density thrust: {:.3}, altitude: {:.0}", engine_thrust.into_format_args(poundal, Abbreviation), mach_number.into_format_args(ratio, Abbreviation), gross_thrust.into_format_args(poundal, Abbreviation), ambient_density.into_format_args(slug_per_cubic_foot, Abbreviation), max_density_thrust.into_format_args(poundal, Abbreviation), pressure_altitude.remove_context().into_format_args(foot, Abbreviation));
+
+                let low_altitude_thrust_target =
+                    self.climb_schedule.low_altitude_thrust(pressure_altitude);
+
+                let thrust_target: Force = if max_effective_thrust < low_altitude_thrust_target {
+                    let high_altitude_thrust_loss =
+                        self.climb_schedule.high_altitude_loss(pressure_altitude);
+                    self.thrust_limit_source = ThrustLimitSource::ClimbHighAltitude;
+                    max_effective_thrust - high_altitude_thrust_loss
+
+                // println!(
+                //     "High altitude thrust target: {:.3}",
+                //     high_altitude_thrust_target.into_format_args(poundal, Abbreviation)
+                // );
+                } else {
+                    // println!(
+                    //     "Low altitude thrust target: {:.3}",
+                    //     low_altitude_thrust_target.into_format_args(poundal, Abbreviation)
+                    // );
+
+                    self.thrust_limit_source = ThrustLimitSource::ClimbLowAltitude;
+                    low_altitude_thrust_target
+                };
+                let thrust_target = match blend {
+                    Some(blend) => {
+                        self.climb_speed_blend_engaged = true;
+                        self.thrust_limit_source = ThrustLimitSource::ClimbSpeedBlend;
+                        blend.blend(thrust_target, max_effective_thrust, airspeed_indicated)
+                    }
+                    None => thrust_target,
+                };
+                let thrust_target =
+                    self.protect_overspeed(thrust_target, airspeed_indicated, mach_number);
+                let thrust_target = match self.limit_turbine_temperature(
+                    thrust_target / ThrustValue::MAX.to_force(),
+                    ambient_density,
+                    pressure_altitude,
+                ) {
+                    Some(thrust) => ThrustValue::MAX.to_force() * thrust,
+                    None => thrust_target,
+                };
+
+                let error = thrust_target - gross_thrust;
+                let error = match self.error_filter.as_mut() {
+                    Some(filter) => filter.step(error, delta_t),
+                    None => error,
+                };
+
+                match self.fixed_step {
+                    Some(step) => {
+                        self.step_accumulator += delta_t;
+                        while self.step_accumulator >= step {
+                            self.step_pid(error, gross_thrust, pressure_altitude, step);
+                            self.step_accumulator -= step;
+                        }
+                    }
+                    None => self.step_pid(error, gross_thrust, pressure_altitude, delta_t),
+                }
+                self.last_thrust_target = Some(ThrustValue::from_force(thrust_target));
+                self.last_gross_thrust = Some(gross_thrust);
+                // println!("Thrust target: {:.4} (error: {:+.4}); commanding change of {:+.4} to {:.4} of maximum", thrust_target.into_format_args(poundal, Abbreviation), self.pid_state.prior_error().into_format_args(poundal, Abbreviation), output.into_format_args(ratio, Abbreviation), self.throttle_selected.into_format_args(ratio, Abbreviation));
+
+                (
+                    ThrustValue::from_force(thrust_target),
+                    self.clamp_selected_throttle(),
+                )
+            }
+            ThrottleMode::Cruise | ThrottleMode::Undefined => {
+                self.thrust_limit_source = ThrustLimitSource::Lever;
+                self.throttle_selected = current_throttle;
+                let cruise_normalized_throttle =
+                    ThrottleAxis::from_ratio(current_throttle).normalize_cruise();
+                let effective_thrust = cruise_normalized_throttle * thrust_efficiency;
+
+                //self.pid_state.reset();
+                // println!("Current throttle: {:.4} ({:.4} of cruise; {:.4} effective); Commanding engine to {:.4} of maximum", current_throttle.into_format_args(ratio, Abbreviation), cruise_normalized_throttle.into_format_args(ratio, Abbreviation), effective_thrust.into_format_args(ratio, Abbreviation), effective_thrust.into_format_args(ratio, Abbreviation));
+
+                (
+                    ThrustValue::from_ratio(effective_thrust),
+                    ThrottlePercent::from_ratio(effective_thrust),
+                )
+            }
+        }
+    }
+}
+
+impl FadecController {
+    /// Scales the thrust target down as the airspeed nears the overspeed
+    /// protection limits, if configured
+    fn protect_overspeed(
+        &mut self,
+        thrust_target: Force,
+        airspeed_indicated: Velocity,
+        mach_number: MachNumber,
+    ) -> Force {
+        let scale = match self.overspeed_protection {
+            Some(protection) => protection.thrust_scale(airspeed_indicated, mach_number),
+            None => return thrust_target,
+        };
+
+        self.overspeed_protection_active = scale < Ratio::new::<ratio>(1.);
+        thrust_target * scale
+    }
+
+    /// The fraction of rated thrust to which the turbine temperature limit
+    /// caps `thrust`, if configured and below `thrust`
+    fn limit_turbine_temperature(
+        &mut self,
+        thrust: Ratio,
+        ambient_density: MassDensity,
+        pressure_altitude: PressureAltitude,
+    ) -> Option<Ratio> {
+        let limit = self
+            .turbine_temperature_limit?
+            .thrust_limit(ambient_temperature(ambient_density, pressure_altitude)?);
+
+        self.turbine_temperature_limited = thrust > limit;
+        if self.turbine_temperature_limited {
+            Some(limit)
+        } else {
+            None
+        }
+    }
+
+    /// Counts the consecutive frames for which the raw thrust reading has
+    /// not changed
+    fn track_thrust_reading(&mut self, engine_thrust: Force) {
+        if self.last_raw_thrust == Some(engine_thrust) {
+            self.repeated_thrust_frames = self.repeated_thrust_frames.saturating_add(1);
+        } else {
+            self.last_raw_thrust = Some(engine_thrust);
+            self.repeated_thrust_frames = 0;
+            self.estimated_thrust = None;
+        }
+    }
+
+    /// Advances the estimated thrust toward the thrust the model predicts
+    /// for the selected throttle
+    fn dead_reckon_thrust(
+        &mut self,
+        last_reading: Force,
+        ambient_density: MassDensity,
+        mach_number: MachNumber,
+        delta_t: Time,
+    ) -> Force {
+        let modeled = Self::estimate_thrust_for_throttle(
+            ThrottlePercent::from_ratio(self.throttle_selected),
+            ambient_density,
+            mach_number,
+        );
+        let estimate = self.estimated_thrust.unwrap_or(last_reading);
+        let lag = 1. - (-delta_t.get::<second>() / STALE_THRUST_TIME_CONSTANT).exp();
+        let estimate = estimate + (modeled - estimate) * lag;
+        self.estimated_thrust = Some(estimate);
+        estimate
+    }
+
+    /// Clears the accumulated state of the climb PID and its error filter
+    fn reset_climb_pid(&mut self) {
+        self.pid_state.reset();
+        if let Some(filter) = self.error_filter.as_mut() {
+            filter.reset();
+        }
+        self.last_pid_outputs = PidComponents::default();
+    }
+
+    /// The climb PID configuration in effect at the given pressure altitude
+    fn climb_pid_config_at(&self, pressure_altitude: PressureAltitude) -> PidConfiguration<Force> {
+        match self.climb_output_range {
+            Some(range) => PidConfiguration {
+                output_range: range.at(pressure_altitude),
+                ..self.climb_pid_config
+            },
+            None => self.climb_pid_config,
+        }
+    }
+
+    /// Steps the climb PID and applies its output to the selected throttle
+    fn step_pid(
+        &mut self,
+        error: Force,
+        gross_thrust: Force,
+        pressure_altitude: PressureAltitude,
+        delta_t: Time,
+    ) {
+        let config = self.climb_pid_config_at(pressure_altitude);
+        self.last_pid_outputs =
+            self.pid_state
+                .step_with_components(error, &config, gross_thrust, delta_t);
+
+        let output = self.last_pid_outputs.output();
+        let clamped_output = config.clamp_output(output);
+        self.pid_steps = self.pid_steps.saturating_add(1);
+        if clamped_output != output {
+            self.saturated_pid_steps = self.saturated_pid_steps.saturating_add(1);
+        }
+
+        self.throttle_selected += if self.climb_output_range.is_some() {
+            clamped_output
+        } else {
+            output
+        };
+    }
+
+    /// Converts the selected throttle to the commanded throttle, recording
+    /// whether it was clamped
+    fn clamp_selected_throttle(&mut self) -> ThrottlePercent {
+        let (throttle, clamped) = ThrottlePercent::from_ratio_clamped(self.throttle_selected);
+        self.throttle_clamped = clamped;
+        if clamped && self.unwind_clamped_throttle {
+            self.throttle_selected = throttle.to_ratio();
+        }
+        throttle
+    }
+
+    /// Passes the throttle position through to the engine unmodified
+    fn passthrough(&mut self, current_throttle: Ratio) -> (ThrustValue, ThrottlePercent) {
+        self.throttle_selected = current_throttle;
+        self.thrust_limit_source = ThrustLimitSource::Lever;
+        (
+            ThrustValue::from_ratio(self.passthrough_curve.apply(self.throttle_selected)),
+            ThrottlePercent::from_ratio(self.throttle_selected),
+        )
+    }
+}
+
+/// Whether the readings from the simulator are usable by the FADEC
+///
+/// Shortly after the simulator starts, readings may be zero or non-finite
+/// until the simulation settles.
+fn are_readings_plausible(engine_thrust: Force, ambient_density: MassDensity) -> bool {
+    engine_thrust.is_finite()
+        && ambient_density.is_finite()
+        && ambient_density.get::<slug_per_cubic_foot>() > 0.
+}
+
+/// The ambient temperature of dry air with the ambient density at the
+/// standard pressure for the pressure altitude
+///
+/// Not available for implausible densities or outside the altitudes covered
+/// by the standard atmosphere.
+fn ambient_temperature(
+    ambient_density: MassDensity,
+    pressure_altitude: PressureAltitude,
+) -> Option<ThermodynamicTemperature> {
+    if !(ambient_density.is_finite() && ambient_density.get::<slug_per_cubic_foot>() > 0.) {
+        return None;
+    }
+    let pressure = calculations::standard_pressure(GeopotentialAltitude::interpret(
+        pressure_altitude.remove_context(),
+    ))?;
+    let temperature: TemperatureInterval = pressure / (ambient_density * constants::Rd());
+    Some(ThermodynamicTemperature::new::<kelvin>(
+        temperature.get::<temperature_interval::kelvin>(),
+    ))
+}
+
+pub(crate) fn convert_to_gross_thrust(thrust_in: Force, mach_in: MachNumber) -> Force {
+    thrust_in * (1. + (mach_in.get::<ratio>().powi(2) / 5.)).powf(3.5)
+}
+
+/// Raises the ambient density to at least [`MINIMUM_AMBIENT_DENSITY`]
+fn floor_ambient_density(ambient_density: MassDensity) -> MassDensity {
+    let minimum = MassDensity::new::<slug_per_cubic_foot>(MINIMUM_AMBIENT_DENSITY);
+    if ambient_density > minimum {
+        ambient_density
+    } else {
+        minimum
+    }
+}
+
+fn get_max_density_thrust(ambient_density: MassDensity) -> Force {
+    let density_factor = Volume::new::<cubic_foot>(42_009.0345696695)
+        * Acceleration::new::<foot_per_second_squared>(1.);
+    max_density_thrust_with_factor(ambient_density, density_factor)
+}
+
+/// The thrust available at zero ambient density, in poundals
+pub(crate) const BASELINE_MAX_THRUST: f64 = 250.;
+
+/// Computes the maximum density thrust for an arbitrary density factor
+pub(crate) fn max_density_thrust_with_factor(
+    ambient_density: MassDensity,
+    density_factor: <Force as std::ops::Div<MassDensity>>::Output,
+) -> Force {
+    let f: Force = ambient_density * density_factor;
+    f + Force::new::<poundal>(BASELINE_MAX_THRUST)
+}
+
+struct ClimbFadecPidConfiguration;
+
+impl ClimbFadecPidConfiguration {
+    #[inline]
+    fn default() -> PidConfiguration<Force> {
+        presets::climb_thrust()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::select_throttle_mode;
+    use crate::Instruments;
+    use uom::num_traits::zero;
+    use uom::si::{frequency::hertz, momentum::pound_foot_per_second};
+    use wt_systems::testing;
+
+    fn instruments(
+        mach_number: MachNumber,
+        airspeed_indicated: Velocity,
+        ambient_density: MassDensity,
+        pressure_altitude: PressureAltitude,
+        on_ground: bool,
+    ) -> Instruments {
+        Instruments {
+            mach_number,
+            airspeed_indicated,
+            ambient_density,
+            pressure_altitude,
+            on_ground,
+            ..Instruments::default()
+        }
+    }
+
+    fn climb_step_with_ground(
+        fadec: &mut FadecController,
+        ambient_density: MassDensity,
+        on_ground: bool,
+    ) -> (ThrustValue, ThrottlePercent) {
+        fadec
+            .get_desired_output(
+                ThrottleAxis::CLIMB.to_ratio(),
+                ThrottleMode::Climb,
+                Force::new::<poundal>(1_000.),
+                &instruments(
+                    MachNumber::new::<ratio>(0.3),
+                    Velocity::new::<knot>(220.),
+                    ambient_density,
+                    PressureAltitude::new::<foot>(10_000.),
+                    on_ground,
+                ),
+                Time::new::<second>(1. / 60.),
+            )
+            .into()
+    }
+
+    /// Readings from an analysis tool with its own instrument model
+    struct AnalysisReadings {
+        density: MassDensity,
+    }
+
+    impl AtmosphericReadings for AnalysisReadings {
+        fn mach_number(&self) -> MachNumber {
+            MachNumber::new::<ratio>(0.3)
+        }
+
+        fn ambient_density(&self) -> MassDensity {
+            self.density
+        }
+
+        fn pressure_altitude(&self) -> PressureAltitude {
+            PressureAltitude::new::<foot>(10_000.)
+        }
+    }
+
+    impl FadecReadings for AnalysisReadings {
+        fn airspeed_indicated(&self) -> Velocity {
+            Velocity::new::<knot>(220.)
+        }
+
+        fn on_ground(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn shared_readings_feed_avmath_and_fadec() {
+        let readings = AnalysisReadings {
+            density: MassDensity::new::<slug_per_cubic_foot>(0.0017),
+        };
+
+        // 0.0017 slug/ft³ against 0.0023769 slug/ft³ at sea level
+        testing::assert_equal_within_epsilon(
+            0.7152,
+            avmath::readings::density_ratio(&readings).get::<ratio>(),
+            1e-4,
+        );
+
+        let mut fadec = FadecController::default().with_startup_lockout(0);
+        let actual = fadec.get_desired_output(
+            ThrottleAxis::CLIMB.to_ratio(),
+            ThrottleMode::Climb,
+            Force::new::<poundal>(1_000.),
+            &readings,
+            Time::new::<second>(1. / 60.),
+        );
+        let expected = climb_step_with_ground(
+            &mut FadecController::default().with_startup_lockout(0),
+            readings.density,
+            false,
+        );
+
+        assert_eq!(
+            ThrustLimitSource::ClimbLowAltitude,
+            actual.thrust_limit_source
+        );
+        assert_eq!(expected, actual.into());
+    }
+
+    fn climb_step(fadec: &mut FadecController, ambient_density: MassDensity) -> ThrottlePercent {
+        climb_step_with_ground(fadec, ambient_density, false).1
+    }
+
+    /// Steps a FADEC in the climb detent against an engine that follows the
+    /// approximate thrust model with a one second lag, returning the number
+    /// of steps taken for the gross thrust to settle within 10 pdl of the
+    /// target
+    fn steps_to_converge(fadec: &mut FadecController) -> usize {
+        let density = MassDensity::new::<slug_per_cubic_foot>(0.0017);
+        let mach = MachNumber::new::<ratio>(0.3);
+        let delta_t = Time::new::<second>(1. / 60.);
+        let lag = Time::new::<second>(1.);
+        let target = Force::new::<poundal>(2050.);
+        let tolerance = Force::new::<poundal>(10.);
+
+        let mut gross_thrust = Force::new::<poundal>(0.);
+        let mut settled_since = None;
+        for step in 0..10_000 {
+            if (gross_thrust - target).abs() < tolerance {
+                let since = *settled_since.get_or_insert(step);
+                if step - since >= 60 {
+                    return since;
+                }
+            } else {
+                settled_since = None;
+            }
+
+            let ram_factor = convert_to_gross_thrust(Force::new::<poundal>(1.), mach);
+            let throttle = fadec
+                .get_desired_output(
+                    ThrottleAxis::CLIMB.to_ratio(),
+                    ThrottleMode::Climb,
+                    gross_thrust / ram_factor.get::<poundal>(),
+                    &instruments(
+                        mach,
+                        Velocity::new::<knot>(220.),
+                        density,
+                        PressureAltitude::new::<foot>(10_000.),
+                        false,
+                    ),
+                    delta_t,
+                )
+                .throttle;
+
+            let commanded = throttle.to_ratio() * get_max_density_thrust(density);
+            gross_thrust += (commanded - gross_thrust) * (delta_t / lag).get::<ratio>();
+        }
+
+        panic!("FADEC did not settle on the climb thrust target");
+    }
+
+    #[test]
+    fn thrust_error_is_target_less_gross_thrust() {
+        let mut fadec = FadecController::default().with_startup_lockout(0);
+        assert_eq!(None, fadec.thrust_error());
+
+        let density = MassDensity::new::<slug_per_cubic_foot>(0.0017);
+        let (target, _) = climb_step_with_ground(&mut fadec, density, false);
+        let gross_thrust =
+            convert_to_gross_thrust(Force::new::<poundal>(1_000.), MachNumber::new::<ratio>(0.3));
+
+        testing::assert_equal_within_epsilon(
+            (target.to_force() - gross_thrust).get::<poundal>(),
+            fadec.thrust_error().unwrap().get::<poundal>(),
+            1e-9,
+        );
+
+        climb_step_with_ground(&mut fadec, density, true);
+        assert_eq!(None, fadec.thrust_error());
+    }
+
+    #[test]
+    fn persistent_saturation_reports_full_duty_cycle() {
+        let mut fadec = FadecController::default().with_startup_lockout(0);
+        assert_eq!(Ratio::new::<ratio>(0.), fadec.saturation_duty_cycle());
+
+        // An engine that never spools up leaves a persistent shortfall
+        let density = MassDensity::new::<slug_per_cubic_foot>(0.0017);
+        for _ in 0..120 {
+            fadec.get_desired_output(
+                ThrottleAxis::CLIMB.to_ratio(),
+                ThrottleMode::Climb,
+                Force::new::<poundal>(0.),
+                &instruments(
+                    MachNumber::new::<ratio>(0.3),
+                    Velocity::new::<knot>(220.),
+                    density,
+                    PressureAltitude::new::<foot>(10_000.),
+                    false,
+                ),
+                Time::new::<second>(1. / 60.),
+            );
+        }
+
+        assert!(
+            fadec.saturation_duty_cycle() > Ratio::new::<ratio>(0.95),
+            "{:?}",
+            fadec.saturation_duty_cycle()
+        );
+    }
+
+    #[test]
+    fn throttle_estimate_inverts_thrust_model() {
+        let density = MassDensity::new::<slug_per_cubic_foot>(0.0017);
+        let mach = MachNumber::new::<ratio>(0.3);
+        let gross_thrust = get_max_density_thrust(density) * 0.8;
+        let engine_thrust = gross_thrust
+            / convert_to_gross_thrust(Force::new::<poundal>(1.), mach).get::<poundal>();
+
+        let estimate = FadecController::estimate_throttle_for_thrust(engine_thrust, density, mach);
+
+        testing::assert_equal_within_epsilon(80., estimate.to_ratio().get::<percent>(), 1e-9);
+    }
+
+    #[test]
+    fn warm_start_from_estimate_converges_sooner() {
+        let mut cold = FadecController::default().with_startup_lockout(0);
+        let mut warm = FadecController::default().with_startup_lockout(0);
+
+        let mach = MachNumber::new::<ratio>(0.3);
+        let engine_thrust = Force::new::<poundal>(2050.)
+            / convert_to_gross_thrust(Force::new::<poundal>(1.), mach).get::<poundal>();
+        warm.warm_start(FadecController::estimate_throttle_for_thrust(
+            engine_thrust * 0.95,
+            MassDensity::new::<slug_per_cubic_foot>(0.0017),
+            mach,
+        ));
+
+        let cold_steps = steps_to_converge(&mut cold);
+        let warm_steps = steps_to_converge(&mut warm);
+        assert!(
+            warm_steps < cold_steps,
+            "warm start took {} steps; cold start took {}",
+            warm_steps,
+            cold_steps
+        );
+    }
+
+    /// Steps a FADEC in the climb detent with a constant thrust reading
+    /// through frames of the given durations
+    fn run_frames(fadec: &mut FadecController, frames: &[f64]) -> ThrottlePercent {
+        let mut throttle = ThrottlePercent::MIN;
+        for &frame in frames {
+            throttle = fadec
+                .get_desired_output(
+                    ThrottleAxis::CLIMB.to_ratio(),
+                    ThrottleMode::Climb,
+                    Force::new::<poundal>(1_500.),
+                    &instruments(
+                        MachNumber::new::<ratio>(0.3),
+                        Velocity::new::<knot>(220.),
+                        MassDensity::new::<slug_per_cubic_foot>(0.0017),
+                        PressureAltitude::new::<foot>(10_000.),
+                        false,
+                    ),
+                    Time::new::<second>(frame),
+                )
+                .throttle;
+        }
+        throttle
+    }
+
+    #[test]
+    fn fixed_step_is_independent_of_frame_timing() {
+        let fixed = || {
+            FadecController::default()
+                .with_startup_lockout(0)
+                .with_fixed_step(Frequency::new::<hertz>(64.))
+        };
+
+        let mut even = fixed();
+        let mut uneven = fixed();
+
+        let even_throttle = run_frames(&mut even, &[1. / 64.; 16]);
+        let uneven_throttle = run_frames(
+            &mut uneven,
+            &[
+                3. / 64.,
+                1. / 128.,
+                5. / 128.,
+                1. / 32.,
+                5. / 64.,
+                1. / 64.,
+                1. / 32.,
+            ],
+        );
+
+        assert_eq!(even_throttle, uneven_throttle);
+        assert_eq!(even.pid_state(), uneven.pid_state());
+    }
+
+    #[test]
+    fn variable_step_depends_on_frame_timing() {
+        let mut even = FadecController::default().with_startup_lockout(0);
+        let mut uneven = FadecController::default().with_startup_lockout(0);
+
+        let even_throttle = run_frames(&mut even, &[1. / 64.; 16]);
+        let uneven_throttle = run_frames(&mut uneven, &[1. / 8., 1. / 8.]);
+
+        assert_ne!(even_throttle, uneven_throttle);
+    }
+
+    #[test]
+    fn on_ground_inhibits_climb_thrust_target() {
+        let density = MassDensity::new::<slug_per_cubic_foot>(0.0023);
+        let mut airborne = FadecController::default().with_startup_lockout(0);
+        let mut grounded = FadecController::default().with_startup_lockout(0);
+
+        let (airborne_thrust, _) = climb_step_with_ground(&mut airborne, density, false);
+        assert!(airborne_thrust.to_force() > Force::new::<poundal>(2_000.));
+
+        for _ in 0..10 {
+            let (thrust, throttle) = climb_step_with_ground(&mut grounded, density, true);
+            assert_eq!(ThrustValue::MIN, thrust);
+            assert_eq!(ThrottlePercent::MIN, throttle);
+        }
+        assert_eq!(PidController::default(), *grounded.pid_state());
+    }
+
+    #[test]
+    fn commanded_throttle_respects_idle_floor() {
+        let idle = ThrottlePercent::from_ratio(Ratio::new::<percent>(20.));
+        let mut fadec = FadecController::default()
+            .with_startup_lockout(0)
+            .with_throttle_limits(idle, ThrottlePercent::MAX);
+
+        let density = MassDensity::new::<slug_per_cubic_foot>(0.0023);
+        let (_, grounded) = climb_step_with_ground(&mut fadec, density, true);
+        assert_eq!(idle, grounded);
+
+        let cruise = fadec
+            .get_desired_output(
+                ThrottleAxis::MIN.to_ratio(),
+                ThrottleMode::Cruise,
+                Force::new::<poundal>(1_000.),
+                &instruments(
+                    MachNumber::new::<ratio>(0.3),
+                    Velocity::new::<knot>(220.),
+                    density,
+                    PressureAltitude::new::<foot>(10_000.),
+                    false,
+                ),
+                Time::new::<second>(1. / 60.),
+            )
+            .throttle;
+        assert_eq!(idle, cruise);
+    }
+
+    #[test]
+    fn ground_idle_applies_only_on_the_ground() {
+        let flight_idle = ThrottlePercent::from_ratio(Ratio::new::<percent>(20.));
+        let ground_idle = ThrottlePercent::from_ratio(Ratio::new::<percent>(30.));
+        let mut fadec = FadecController::default()
+            .with_startup_lockout(0)
+            .with_throttle_limits(flight_idle, ThrottlePercent::MAX)
+            .with_ground_idle(ground_idle);
+
+        let mut idle_step = |throttle_mode, on_ground| {
+            fadec
+                .get_desired_output(
+                    ThrottleAxis::MIN.to_ratio(),
+                    throttle_mode,
+                    Force::new::<poundal>(1_000.),
+                    &instruments(
+                        MachNumber::new::<ratio>(0.3),
+                        Velocity::new::<knot>(220.),
+                        MassDensity::new::<slug_per_cubic_foot>(0.0023),
+                        PressureAltitude::new::<foot>(0.),
+                        on_ground,
+                    ),
+                    Time::new::<second>(1. / 60.),
+                )
+                .throttle
+        };
+
+        assert_eq!(ground_idle, idle_step(ThrottleMode::Cruise, true));
+        assert_eq!(flight_idle, idle_step(ThrottleMode::Cruise, false));
+        assert_eq!(ground_idle, idle_step(ThrottleMode::Undefined, true));
+        assert_eq!(flight_idle, idle_step(ThrottleMode::Undefined, false));
+        assert_eq!(ground_idle, idle_step(ThrottleMode::Climb, true));
+    }
+
+    #[test]
+    fn climb_step_output_reports_target_limits_and_pid() {
+        let mut fadec = FadecController::default().with_startup_lockout(0);
+        let density = MassDensity::new::<slug_per_cubic_foot>(0.0023);
+
+        let output = fadec.get_desired_output(
+            ThrottleAxis::CLIMB.to_ratio(),
+            ThrottleMode::Climb,
+            Force::new::<poundal>(1_000.),
+            &instruments(
+                MachNumber::new::<ratio>(0.3),
+                Velocity::new::<knot>(220.),
+                density,
+                PressureAltitude::new::<foot>(0.),
+                false,
+            ),
+            Time::new::<second>(1. / 60.),
+        );
+
+        let gross_thrust =
+            convert_to_gross_thrust(Force::new::<poundal>(1_000.), MachNumber::new::<ratio>(0.3));
+        assert_eq!(
+            fadec.thrust_error(),
+            Some(output.thrust_target.to_force() - gross_thrust)
+        );
+        assert_eq!(
+            ThrottlePercent::from_ratio(fadec.throttle_selected()),
+            output.throttle
+        );
+        assert_eq!(ThrottlePercent::MIN, output.idle_limit);
+        assert_eq!(fadec.last_pid_outputs(), output.pid_components);
+        assert!(output.pid_components.proportional > Ratio::new::<ratio>(0.));
+        // Far below the thrust target, the first step saturates the PID
+        assert!(output.pid_saturated);
+        assert_eq!(Ratio::new::<ratio>(1.), fadec.saturation_duty_cycle());
+        assert_eq!(
+            (output.thrust_target, output.throttle),
+            <(ThrustValue, ThrottlePercent)>::from(output)
+        );
+    }
+
+    #[test]
+    fn throttle_shim_agrees_with_structured_output() {
+        let instruments = instruments(
+            MachNumber::new::<ratio>(0.3),
+            Velocity::new::<knot>(220.),
+            MassDensity::new::<slug_per_cubic_foot>(0.0017),
+            PressureAltitude::new::<foot>(10_000.),
+            false,
+        );
+        let mut shim = FadecController::default().with_startup_lockout(0);
+        let mut structured = shim;
+
+        for _ in 0..10 {
+            let output = structured.get_desired_output(
+                ThrottleAxis::CLIMB.to_ratio(),
+                ThrottleMode::Climb,
+                Force::new::<poundal>(1_000.),
+                &instruments,
+                Time::new::<second>(1. / 60.),
+            );
+            let (thrust_target, throttle) = shim.get_desired_throttle(
+                ThrottleAxis::CLIMB.to_ratio(),
+                ThrottleMode::Climb,
+                Force::new::<poundal>(1_000.),
+                &instruments,
+                Time::new::<second>(1. / 60.),
+            );
+
+            assert_eq!(output.thrust_target, thrust_target);
+            assert_eq!(output.throttle, throttle);
+        }
+        assert_eq!(structured, shim);
+    }
+
+    #[test]
+    fn pid_output_is_only_reported_from_the_climb_schedule() {
+        let mut fadec = FadecController::default().with_startup_lockout(0);
+        let mut step = |throttle: ThrottleAxis, on_ground: bool| {
+            fadec.get_desired_output(
+                throttle.to_ratio(),
+                select_throttle_mode(throttle, fadec.has_reverse()),
+                Force::new::<poundal>(1_000.),
+                &instruments(
+                    MachNumber::new::<ratio>(0.3),
+                    Velocity::new::<knot>(220.),
+                    MassDensity::new::<slug_per_cubic_foot>(0.0023),
+                    PressureAltitude::new::<foot>(0.),
+                    on_ground,
+                ),
+                Time::new::<second>(1. / 60.),
+            )
+        };
+
+        let climb = step(ThrottleAxis::CLIMB, false);
+        assert!(climb.pid_saturated);
+        assert_ne!(PidComponents::default(), climb.pid_components);
+
+        for (throttle, on_ground) in [
+            (ThrottleAxis::TAKEOFF, false),
+            (ThrottleAxis::CRUISE_MAX, false),
+            (ThrottleAxis::CLIMB, true),
+        ] {
+            let output = step(throttle, on_ground);
+            assert_eq!(PidComponents::default(), output.pid_components);
+            assert!(!output.pid_saturated);
+        }
+    }
+
+    #[test]
+    fn tighter_upper_output_bound_clamps_only_positive_output() {
+        let range =
+            ClimbOutputRange::constant(Ratio::new::<percent>(-4.), Ratio::new::<percent>(1.));
+        let mut fadec = FadecController::default()
+            .with_startup_lockout(0)
+            .with_climb_output_range(range);
+        fadec.warm_start(ThrottlePercent::from(50.));
+
+        let mut climb_step = |engine_thrust: f64| {
+            let before = fadec.throttle_selected();
+            fadec.get_desired_output(
+                ThrottleAxis::CLIMB.to_ratio(),
+                ThrottleMode::Climb,
+                Force::new::<poundal>(engine_thrust),
+                &instruments(
+                    MachNumber::new::<ratio>(0.3),
+                    Velocity::new::<knot>(220.),
+                    MassDensity::new::<slug_per_cubic_foot>(0.0023),
+                    PressureAltitude::new::<foot>(0.),
+                    false,
+                ),
+                Time::new::<second>(1. / 60.),
+            );
+            fadec.throttle_selected() - before
+        };
+
+        let raised = climb_step(1_000.);
+        let lowered = climb_step(6_000.);
+
+        testing::assert_equal_within_epsilon(1., raised.get::<percent>(), 1e-9);
+        assert!(lowered < Ratio::new::<percent>(-1.), "{:?}", lowered);
+        assert!(lowered >= Ratio::new::<percent>(-4.), "{:?}", lowered);
+    }
+
+    #[test]
+    fn output_range_follows_pressure_altitude() {
+        let range = ClimbOutputRange {
+            lower: [
+                (Length::new::<foot>(10_000.), Ratio::new::<percent>(-2.)),
+                (Length::new::<foot>(40_000.), Ratio::new::<percent>(-4.)),
+            ],
+            upper: [
+                (Length::new::<foot>(10_000.), Ratio::new::<percent>(2.)),
+                (Length::new::<foot>(40_000.), Ratio::new::<percent>(1.)),
+            ],
+        };
+
+        let (lower, upper) = range.at(PressureAltitude::new::<foot>(25_000.));
+        testing::assert_equal_within_epsilon(-3., lower.get::<percent>(), 1e-9);
+        testing::assert_equal_within_epsilon(1.5, upper.get::<percent>(), 1e-9);
+        assert_eq!(
+            (Ratio::new::<percent>(-2.), Ratio::new::<percent>(2.)),
+            range.at(PressureAltitude::new::<foot>(0.))
+        );
+    }
+
+    #[test]
+    fn climbing_through_crossover_flips_thrust_limit_source() {
+        let mut fadec = FadecController::default().with_startup_lockout(0);
+        let sources: Vec<ThrustLimitSource> = avmath::isa::standard_table()
+            .map(|(altitude, _, _, density)| (altitude.remove_context(), density))
+            .filter(|(altitude, _)| (0_f64..=45_000.).contains(&altitude.get::<foot>()))
+            .map(|(altitude, density)| {
+                fadec
+                    .get_desired_output(
+                        ThrottleAxis::CLIMB.to_ratio(),
+                        ThrottleMode::Climb,
+                        Force::new::<poundal>(1_000.),
+                        &instruments(
+                            MachNumber::new::<ratio>(0.5),
+                            Velocity::new::<knot>(240.),
+                            density,
+                            PressureAltitude::new::<foot>(altitude.get::<foot>()),
+                            false,
+                        ),
+                        Time::new::<second>(1. / 60.),
+                    )
+                    .thrust_limit_source
+            })
+            .collect();
+
+        let crossover = sources
+            .iter()
+            .position(|&source| source == ThrustLimitSource::ClimbHighAltitude)
+            .expect("never crossed over to the high-altitude branch");
+        assert!(crossover > 0);
+        assert!(sources[..crossover]
+            .iter()
+            .all(|&source| source == ThrustLimitSource::ClimbLowAltitude));
+        assert!(sources[crossover..]
+            .iter()
+            .all(|&source| source == ThrustLimitSource::ClimbHighAltitude));
+        assert_eq!(
+            ThrustLimitSource::ClimbHighAltitude,
+            fadec.thrust_limit_source()
+        );
+    }
+
+    #[test]
+    fn thrust_estimate_inverts_throttle_estimate() {
+        let density = MassDensity::new::<slug_per_cubic_foot>(0.0017);
+        let mach = MachNumber::new::<ratio>(0.3);
+        let throttle = ThrottlePercent::from(65.);
+
+        let thrust = FadecController::estimate_thrust_for_throttle(throttle, density, mach);
+        let estimate = FadecController::estimate_throttle_for_thrust(thrust, density, mach);
+
+        testing::assert_equal_within_epsilon(65., estimate.to_ratio().get::<percent>(), 1e-9);
+    }
+
+    #[test]
+    fn frozen_thrust_readings_do_not_wind_up_the_pid() {
+        let frozen_step = |fadec: &mut FadecController, thrust: f64| {
+            fadec.get_desired_output(
+                ThrottleAxis::CLIMB.to_ratio(),
+                ThrottleMode::Climb,
+                Force::new::<poundal>(thrust),
+                &instruments(
+                    MachNumber::new::<ratio>(0.3),
+                    Velocity::new::<knot>(220.),
+                    MassDensity::new::<slug_per_cubic_foot>(0.0023),
+                    PressureAltitude::new::<foot>(0.),
+                    false,
+                ),
+                Time::new::<second>(1. / 30.),
+            )
+        };
+
+        let mut fadec = FadecController::default().with_startup_lockout(0);
+        for _ in 0..30 * 120 {
+            frozen_step(&mut fadec, 1_000.);
+        }
+        assert!(!fadec.is_thrust_stale());
+        assert!(fadec.throttle_selected() > Ratio::new::<ratio>(1.));
+
+        let mut fadec = FadecController::default()
+            .with_startup_lockout(0)
+            .with_stale_thrust_fallback(30);
+        for _ in 0..30 * 120 {
+            frozen_step(&mut fadec, 1_000.);
+        }
+        assert!(fadec.is_thrust_stale());
+        assert!(fadec.throttle_selected() < Ratio::new::<ratio>(1.));
+        let target = fadec.last_thrust_target.unwrap().to_force();
+        let error = fadec.thrust_error().unwrap();
+        assert!(
+            (error / target).abs() < Ratio::new::<percent>(2.),
+            "{:?}",
+            error
+        );
+
+        frozen_step(&mut fadec, 1_001.);
+        assert!(!fadec.is_thrust_stale());
+    }
+
+    #[test]
+    fn climb_integral_limit_bounds_momentum_during_sustained_error() {
+        let limit = Momentum::new::<pound_foot_per_second>(5_000.);
+        let mut fadec = FadecController::default()
+            .with_startup_lockout(0)
+            .with_climb_integral_limit(limit);
+
+        let mut peak = Momentum::new::<pound_foot_per_second>(0.);
+        for _ in 0..60 * 60 {
+            climb_step_with_ground(
+                &mut fadec,
+                MassDensity::new::<slug_per_cubic_foot>(0.0017),
+                false,
+            );
+            let retained = fadec.pid_state().retained_error().abs();
+            assert!(retained <= limit, "{:?}", retained);
+            if retained > peak {
+                peak = retained;
+            }
+        }
+        assert_eq!(limit, peak);
+    }
+
+    #[test]
+    fn selected_throttle_above_full_is_clamped_and_flagged() {
+        let climb_step = |fadec: &mut FadecController| {
+            fadec.warm_start(ThrottlePercent::from(120.));
+            fadec.get_desired_output(
+                ThrottleAxis::CLIMB.to_ratio(),
+                ThrottleMode::Climb,
+                Force::new::<poundal>(1_000.),
+                &instruments(
+                    MachNumber::new::<ratio>(0.3),
+                    Velocity::new::<knot>(220.),
+                    MassDensity::new::<slug_per_cubic_foot>(0.0023),
+                    PressureAltitude::new::<foot>(0.),
+                    false,
+                ),
+                Time::new::<second>(1. / 60.),
+            )
+        };
+
+        let mut fadec = FadecController::default().with_startup_lockout(0);
+        let output = climb_step(&mut fadec);
+        assert_eq!(ThrottlePercent::MAX, output.throttle);
+        assert!(output.throttle_clamped);
+        assert!(fadec.is_throttle_clamped());
+        assert!(fadec.throttle_selected() > Ratio::new::<ratio>(1.));
+
+        let mut fadec = FadecController::default()
+            .with_startup_lockout(0)
+            .with_clamped_throttle_unwinding();
+        let output = climb_step(&mut fadec);
+        assert_eq!(ThrottlePercent::MAX, output.throttle);
+        assert!(output.throttle_clamped);
+        assert_eq!(Ratio::new::<ratio>(1.), fadec.throttle_selected());
+
+        let output = fadec.get_desired_output(
+            ThrottleAxis::CRUISE_MAX.to_ratio(),
+            ThrottleMode::Cruise,
+            Force::new::<poundal>(1_000.),
+            &instruments(
+                MachNumber::new::<ratio>(0.3),
+                Velocity::new::<knot>(220.),
+                MassDensity::new::<slug_per_cubic_foot>(0.0023),
+                PressureAltitude::new::<foot>(0.),
+                false,
+            ),
+            Time::new::<second>(1. / 60.),
+        );
+        assert!(!output.throttle_clamped);
+    }
+
+    #[test]
+    fn reverse_lever_commands_idle_in_flight_and_reverse_on_the_ground() {
+        let max_reverse = ThrottlePercent::from_ratio(Ratio::new::<percent>(40.));
+        let mut fadec = FadecController::default()
+            .with_startup_lockout(0)
+            .with_max_reverse(max_reverse);
+
+        let mut lever_step = |lever: ThrottleAxis, on_ground| {
+            let throttle = fadec
+                .get_desired_output(
+                    lever.to_ratio(),
+                    select_throttle_mode(lever, fadec.has_reverse()),
+                    Force::new::<poundal>(1_000.),
+                    &instruments(
+                        MachNumber::new::<ratio>(0.3),
+                        Velocity::new::<knot>(220.),
+                        MassDensity::new::<slug_per_cubic_foot>(0.0023),
+                        PressureAltitude::new::<foot>(0.),
+                        on_ground,
+                    ),
+                    Time::new::<second>(1. / 60.),
+                )
+                .throttle;
+            (
+                throttle,
+                fadec.is_reverse_inhibited(),
+                fadec.thrust_limit_source(),
+            )
+        };
+
+        assert_eq!(
+            (ThrottlePercent::MIN, true, ThrustLimitSource::Idle),
+            lever_step(ThrottleAxis::MIN, false)
+        );
+        assert_eq!(
+            (
+                ThrottlePercent::from(-40.),
+                false,
+                ThrustLimitSource::Reverse
+            ),
+            lever_step(ThrottleAxis::MIN, true)
+        );
+
+        let (partial, inhibited, _) = lever_step(ThrottleAxis::from_raw(-16192.), true);
+        assert!(partial.is_reverse() && !inhibited);
+        assert!(f64::from(partial) > -40.);
+
+        let (idle, inhibited, source) = lever_step(ThrottleAxis::UNDEF_MAX, false);
+        assert!(!idle.is_reverse() && !inhibited);
+        assert_eq!(ThrustLimitSource::Lever, source);
+    }
+
+    #[test]
+    fn cruise_follows_the_lever_and_selects_it() {
+        let mut fadec = FadecController::default().with_startup_lockout(0);
+        let lever = ThrottleAxis::from_raw(0.).to_ratio();
+
+        let output = fadec.get_desired_output(
+            lever,
+            ThrottleMode::Cruise,
+            Force::new::<poundal>(1_000.),
+            &instruments(
+                MachNumber::new::<ratio>(0.5),
+                Velocity::new::<knot>(250.),
+                MassDensity::new::<slug_per_cubic_foot>(0.0017),
+                PressureAltitude::new::<foot>(20_000.),
+                false,
+            ),
+            Time::new::<second>(1. / 60.),
+        );
+
+        // 16384 of the 25444 axis units up to the top of the cruise range
+        let expected = 16_384. / 25_444. * InstalledThrustLosses::default().efficiency().value;
+        testing::assert_equal_within_epsilon(expected, output.throttle.to_ratio().value, 1e-12);
+        testing::assert_equal_within_epsilon(
+            expected,
+            output.thrust_target.to_ratio().value,
+            1e-12,
+        );
+        assert_eq!(lever, fadec.throttle_selected());
+    }
+
+    #[test]
+    fn active_only_when_scheduling_after_lockout() {
+        let mut fadec = FadecController::default().with_startup_lockout(2);
+
+        let mut active_step = |throttle_mode, on_ground| {
+            fadec.get_desired_output(
+                ThrottleAxis::CLIMB.to_ratio(),
+                throttle_mode,
+                Force::new::<poundal>(1_000.),
+                &instruments(
+                    MachNumber::new::<ratio>(0.3),
+                    Velocity::new::<knot>(220.),
+                    MassDensity::new::<slug_per_cubic_foot>(0.0023),
+                    PressureAltitude::new::<foot>(0.),
+                    on_ground,
+                ),
+                Time::new::<second>(1. / 60.),
+            );
+            fadec.is_active()
+        };
+
+        assert!(!active_step(ThrottleMode::Climb, false));
+        assert!(active_step(ThrottleMode::Climb, false));
+        // Forced to idle on the ground
+        assert!(!active_step(ThrottleMode::Climb, true));
+        assert!(!active_step(ThrottleMode::Cruise, false));
+        assert!(active_step(ThrottleMode::Takeoff, false));
+        // Reverse inhibited in flight, and unavailable without a limit
+        assert!(!active_step(ThrottleMode::Reverse, false));
+        assert!(!active_step(ThrottleMode::Reverse, true));
+    }
+
+    #[test]
+    fn held_and_inhibited_steps_are_inactive() {
+        let mut fadec = FadecController::default()
+            .with_startup_lockout(0)
+            .with_max_reverse(ThrottlePercent::from_ratio(Ratio::new::<percent>(40.)));
+        let mut active_step = |throttle_mode, engine_thrust: f64, on_ground| {
+            fadec.get_desired_output(
+                ThrottleAxis::MIN.to_ratio(),
+                throttle_mode,
+                Force::new::<poundal>(engine_thrust),
+                &instruments(
+                    MachNumber::new::<ratio>(0.3),
+                    Velocity::new::<knot>(220.),
+                    MassDensity::new::<slug_per_cubic_foot>(0.0023),
+                    PressureAltitude::new::<foot>(0.),
+                    on_ground,
+                ),
+                Time::new::<second>(1. / 60.),
+            );
+            fadec.is_active()
+        };
+
+        assert!(active_step(ThrottleMode::Climb, 1_000., false));
+        assert!(!active_step(ThrottleMode::Climb, f64::NAN, false));
+        assert!(active_step(ThrottleMode::Reverse, 1_000., true));
+        assert!(!active_step(ThrottleMode::Reverse, 1_000., false));
+    }
+
+    #[test]
+    fn commanded_throttle_respects_derate_ceiling() {
+        let derate = ThrottlePercent::from_ratio(Ratio::new::<percent>(85.));
+        let mut fadec = FadecController::default()
+            .with_startup_lockout(0)
+            .with_throttle_limits(ThrottlePercent::MIN, derate);
+
+        let FadecOutput {
+            thrust_target: thrust,
+            throttle,
+            ..
+        } = fadec.get_desired_output(
+            ThrottleAxis::TAKEOFF.to_ratio(),
+            ThrottleMode::Takeoff,
+            Force::new::<poundal>(3_000.),
+            &instruments(
+                MachNumber::new::<ratio>(0.2),
+                Velocity::new::<knot>(220.),
+                MassDensity::new::<slug_per_cubic_foot>(0.0023),
+                PressureAltitude::new::<foot>(0.),
+                true,
+            ),
+            Time::new::<second>(1. / 60.),
+        );
+        assert_eq!(ThrustValue::MAX, thrust);
+        assert_eq!(derate, throttle);
+    }
+
+    fn climb_thrust_at_speed(
+        fadec: &mut FadecController,
+        mach_number: f64,
+        airspeed_indicated: f64,
+    ) -> Force {
+        fadec
+            .get_desired_output(
+                ThrottleAxis::CLIMB.to_ratio(),
+                ThrottleMode::Climb,
+                Force::new::<poundal>(1_000.),
+                &instruments(
+                    MachNumber::new::<ratio>(mach_number),
+                    Velocity::new::<knot>(airspeed_indicated),
+                    MassDensity::new::<slug_per_cubic_foot>(0.0007),
+                    PressureAltitude::new::<foot>(35_000.),
+                    false,
+                ),
+                Time::new::<second>(1. / 60.),
+            )
+            .thrust_target
+            .to_force()
+    }
+
+    fn climb_thrust_at_airspeed(fadec: &mut FadecController, airspeed_indicated: f64) -> Force {
+        fadec
+            .get_desired_output(
+                ThrottleAxis::CLIMB.to_ratio(),
+                ThrottleMode::Climb,
+                Force::new::<poundal>(1_000.),
+                &instruments(
+                    MachNumber::new::<ratio>(0.4),
+                    Velocity::new::<knot>(airspeed_indicated),
+                    MassDensity::new::<slug_per_cubic_foot>(0.0017),
+                    PressureAltitude::new::<foot>(10_000.),
+                    false,
+                ),
+                Time::new::<second>(1. / 60.),
+            )
+            .thrust_target
+            .to_force()
+    }
+
+    #[test]
+    fn climb_speed_blend_adds_thrust_below_target_speed() {
+        let mut scheduled = FadecController::default().with_startup_lockout(0);
+        let mut blended = FadecController::default()
+            .with_startup_lockout(0)
+            .with_climb_speed_blend(ClimbSpeedBlend::default());
+
+        let scheduled_thrust = climb_thrust_at_airspeed(&mut scheduled, 220.);
+        assert!(climb_thrust_at_airspeed(&mut blended, 220.) > scheduled_thrust);
+        assert!(climb_thrust_at_airspeed(&mut blended, 260.) < scheduled_thrust);
+        testing::assert_equal_within_epsilon(
+            scheduled_thrust.get::<poundal>(),
+            climb_thrust_at_airspeed(&mut blended, 240.).get::<poundal>(),
+            1e-9,
+        );
+    }
+
+    #[test]
+    fn lever_pullback_disconnects_climb_speed_blend() {
+        let blend = ClimbSpeedBlend {
+            disconnect_below: Some(ThrottleAxis::from_raw(0.).to_ratio()),
+            ..ClimbSpeedBlend::default()
+        };
+        let step =
+            |fadec: &mut FadecController, axis: ThrottleAxis| -> (ThrustValue, ThrottlePercent) {
+                fadec
+                    .get_desired_output(
+                        axis.to_ratio(),
+                        select_throttle_mode(axis, fadec.has_reverse()),
+                        Force::new::<poundal>(1_000.),
+                        &instruments(
+                            MachNumber::new::<ratio>(0.4),
+                            Velocity::new::<knot>(220.),
+                            MassDensity::new::<slug_per_cubic_foot>(0.0017),
+                            PressureAltitude::new::<foot>(10_000.),
+                            false,
+                        ),
+                        Time::new::<second>(1. / 60.),
+                    )
+                    .into()
+            };
+        for &pullback in &[ThrottleAxis::from_raw(-1_000.), ThrottleAxis::MIN] {
+            let mut fadec = FadecController::default()
+                .with_startup_lockout(0)
+                .with_climb_speed_blend(blend);
+            assert!(!fadec.is_climb_speed_blend_engaged());
+
+            for _ in 0..30 {
+                step(&mut fadec, ThrottleAxis::CLIMB);
+            }
+            assert!(fadec.is_climb_speed_blend_engaged());
+            assert_ne!(PidController::default(), *fadec.pid_state());
+
+            // Still above the disconnect threshold
+            step(&mut fadec, ThrottleAxis::from_raw(1_000.));
+            assert!(fadec.is_climb_speed_blend_engaged());
+
+            // The disconnect maps the lever as its mode does on later frames
+            let unblended = step(
+                &mut FadecController::default().with_startup_lockout(0),
+                pullback,
+            );
+            assert_eq!(unblended, step(&mut fadec, pullback));
+            assert!(!fadec.is_climb_speed_blend_engaged());
+            assert_eq!(PidController::default(), *fadec.pid_state());
+            assert_eq!(unblended, step(&mut fadec, pullback));
+
+            step(&mut fadec, ThrottleAxis::CLIMB);
+            assert!(fadec.is_climb_speed_blend_engaged());
+        }
+    }
+
+    #[test]
+    fn disconnect_within_the_climb_range_stays_disconnected() {
+        // Between the top of the cruise range and the climb detent
+        let blend = ClimbSpeedBlend {
+            disconnect_below: Some(ThrottleAxis::from_raw(10_500.).to_ratio()),
+            ..ClimbSpeedBlend::default()
+        };
+        let mut fadec = FadecController::default()
+            .with_startup_lockout(0)
+            .with_climb_speed_blend(blend);
+        let mut step = |axis: ThrottleAxis| {
+            fadec.get_desired_output(
+                axis.to_ratio(),
+                select_throttle_mode(axis, false),
+                Force::new::<poundal>(1_000.),
+                &instruments(
+                    MachNumber::new::<ratio>(0.4),
+                    Velocity::new::<knot>(220.),
+                    MassDensity::new::<slug_per_cubic_foot>(0.0017),
+                    PressureAltitude::new::<foot>(10_000.),
+                    false,
+                ),
+                Time::new::<second>(1. / 60.),
+            );
+            (
+                fadec.is_climb_speed_blend_engaged(),
+                fadec.thrust_limit_source(),
+            )
+        };
+
+        assert!(step(ThrottleAxis::CLIMB).0);
+
+        // Still in the climb detent, but below the threshold
+        let pulled_back = ThrottleAxis::from_raw(10_000.);
+        for _ in 0..5 {
+            let (engaged, source) = step(pulled_back);
+            assert!(!engaged);
+            assert_ne!(ThrustLimitSource::ClimbSpeedBlend, source);
+        }
+
+        assert_eq!(
+            (true, ThrustLimitSource::ClimbSpeedBlend),
+            step(ThrottleAxis::CLIMB)
+        );
+    }
+
+    #[test]
+    fn climb_speed_blend_disconnect_is_limited_to_the_climb_detent() {
+        let blend = ClimbSpeedBlend {
+            disconnect_below: Some(ThrottleAxis::MAX.to_ratio()),
+            ..ClimbSpeedBlend::default()
+        };
+        assert!(!blend.is_valid());
+
+        let fadec = FadecController::default().with_climb_speed_blend(blend);
+        assert_eq!(
+            Some(ThrottleAxis::CLIMB.to_ratio()),
+            fadec.climb_speed_blend.and_then(|b| b.disconnect_below)
+        );
+        assert!(fadec.climb_speed_blend.is_some_and(|b| b.is_valid()));
+    }
+
+    #[test]
+    fn overspeed_protection_reduces_thrust_near_mmo() {
+        let mut normal = FadecController::default().with_startup_lockout(0);
+        let mut protected = FadecController::default()
+            .with_startup_lockout(0)
+            .with_overspeed_protection(OverspeedProtection::default());
+
+        let normal_thrust = climb_thrust_at_speed(&mut normal, 0.765, 250.);
+        let protected_thrust = climb_thrust_at_speed(&mut protected, 0.765, 250.);
+
+        assert!(!normal.is_overspeed_protection_active());
+        assert!(protected.is_overspeed_protection_active());
+        testing::assert_equal_within_epsilon(
+            normal_thrust.get::<poundal>() * 0.25,
+            protected_thrust.get::<poundal>(),
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn overspeed_protection_is_inactive_well_below_limits() {
+        let mut normal = FadecController::default().with_startup_lockout(0);
+        let mut protected = FadecController::default()
+            .with_startup_lockout(0)
+            .with_overspeed_protection(OverspeedProtection::default());
+
+        assert_eq!(
+            climb_thrust_at_speed(&mut normal, 0.6, 250.),
+            climb_thrust_at_speed(&mut protected, 0.6, 250.)
+        );
+        assert!(!protected.is_overspeed_protection_active());
+    }
+
+    /// Steps a FADEC in the takeoff detent at sea level with the given
+    /// ambient temperature
+    fn takeoff_at_temperature(fadec: &mut FadecController, celsius: f64) -> FadecOutput {
+        let sea_level = PressureAltitude::new::<foot>(0.);
+        let pressure = calculations::standard_pressure(GeopotentialAltitude::interpret(
+            sea_level.remove_context(),
+        ))
+        .unwrap();
+        let ambient_density = calculations::standard_density_dry_air(
+            pressure,
+            ThermodynamicTemperature::new::<degree_celsius>(celsius),
+        );
+        fadec.get_desired_output(
+            ThrottleAxis::TAKEOFF.to_ratio(),
+            ThrottleMode::Takeoff,
+            Force::new::<poundal>(1_000.),
+            &instruments(
+                MachNumber::new::<ratio>(0.1),
+                Velocity::new::<knot>(60.),
+                ambient_density,
+                sea_level,
+                true,
+            ),
+            Time::new::<second>(1. / 60.),
+        )
+    }
+
+    #[test]
+    fn high_ambient_temperature_reduces_takeoff_thrust() {
+        let mut fadec = FadecController::default()
+            .with_startup_lockout(0)
+            .with_turbine_temperature_limit(TurbineTemperatureLimit::default());
+
+        let standard_day = takeoff_at_temperature(&mut fadec, 15.);
+        assert_eq!(ThrustValue::MAX, standard_day.thrust_target);
+        assert_eq!(ThrottlePercent::MAX, standard_day.throttle);
+        assert!(!fadec.is_turbine_temperature_limited());
+
+        let hot_day = takeoff_at_temperature(&mut fadec, 45.);
+        assert!(fadec.is_turbine_temperature_limited());
+        assert!(hot_day.throttle.to_ratio() < ThrottlePercent::MAX.to_ratio());
+        // (800 °C - 45 °C) / 765 °C of rated thrust holds the ITT at the limit
+        testing::assert_equal_within_epsilon(
+            755. / 765.,
+            hot_day.thrust_target.to_ratio().get::<ratio>(),
+            1e-6,
+        );
+
+        let mut unlimited = FadecController::default().with_startup_lockout(0);
+        assert_eq!(
+            ThrustValue::MAX,
+            takeoff_at_temperature(&mut unlimited, 45.).thrust_target
+        );
+    }
+
+    #[test]
+    fn overspeed_protection_scale_uses_the_more_restrictive_limit() {
+        let protection = OverspeedProtection::default();
+
+        let near_vmo =
+            protection.thrust_scale(Velocity::new::<knot>(300.), MachNumber::new::<ratio>(0.5));
+        testing::assert_equal_within_epsilon(0.5, near_vmo.get::<ratio>(), 1e-9);
+
+        let beyond_mmo =
+            protection.thrust_scale(Velocity::new::<knot>(200.), MachNumber::new::<ratio>(0.8));
+        assert_eq!(Ratio::new::<ratio>(0.), beyond_mmo);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn config_sets_climb_gains_and_schedule() {
+        let mut fadec = FadecController::from_config(
+            r#"{
+                "enabled": true,
+                "climb_pid": {
+                    "gain_proportion": 1e-4,
+                    "gain_integral": 1e-6,
+                    "gain_derivative": 1e-5,
+                    "output_range": [-0.02, 0.02],
+                    "derivative_range": [-0.2, 0.2],
+                    "tolerance": 0.0
+                },
+                "climb_schedule": {
+                    "thrust_efficiency": 0.9,
+                    "low_altitude_thrust": [[0.0, 300.0], [3000.0, 250.0]],
+                    "high_altitude_loss": [[10000.0, 0.0], [12000.0, 500.0]]
+                }
+            }"#,
+        )
+        .unwrap()
+        .with_startup_lockout(0);
+
+        assert!(fadec.is_enabled());
+        assert_eq!(1e-4, fadec.pid_config().gain_proportion.value);
+
+        let (thrust, _) = climb_step_with_ground(
+            &mut fadec,
+            MassDensity::new::<slug_per_cubic_foot>(0.0017),
+            false,
+        );
+        testing::assert_equal_within_epsilon(
+            250.,
+            thrust.to_force().get::<uom::si::force::newton>(),
+            1e-9,
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn partial_config_keeps_defaults() {
+        let fadec = FadecController::from_config(r#"{ "enabled": false }"#).unwrap();
+
+        assert!(!fadec.is_enabled());
+        assert_eq!(&ClimbFadecPidConfiguration::default(), fadec.pid_config());
+        assert!(FadecController::from_config("{ enabled }").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn config_rejects_climb_speed_blend_disconnect_above_climb_detent() {
+        let config = |disconnect_below: ThrottleAxis| {
+            let blend = ClimbSpeedBlend {
+                disconnect_below: Some(disconnect_below.to_ratio()),
+                ..ClimbSpeedBlend::default()
+            };
+            format!(
+                r#"{{ "climb_speed_blend": {} }}"#,
+                serde_json::to_string(&blend).unwrap()
+            )
+        };
+
+        assert!(FadecController::from_config(&config(ThrottleAxis::CLIMB)).is_ok());
+        assert!(FadecController::from_config(&config(ThrottleAxis::CLIMB_MAX)).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn linear_passthrough_maps_lever_straight_to_thrust() {
+        let mut fadec =
+            FadecController::from_config(r#"{ "enabled": false, "passthrough_curve": "Linear" }"#)
+                .unwrap();
+
+        for lever in [0., 0.25, 0.5, 0.75, 1.] {
+            let FadecOutput {
+                thrust_target: thrust,
+                throttle,
+                ..
+            } = fadec.get_desired_output(
+                Ratio::new::<ratio>(lever),
+                ThrottleMode::Climb,
+                Force::new::<poundal>(1_000.),
+                &instruments(
+                    MachNumber::new::<ratio>(0.3),
+                    Velocity::new::<knot>(220.),
+                    MassDensity::new::<slug_per_cubic_foot>(0.0017),
+                    PressureAltitude::new::<foot>(10_000.),
+                    false,
+                ),
+                Time::new::<second>(1. / 60.),
+            );
+            testing::assert_equal_within_epsilon(lever, thrust.to_ratio().get::<ratio>(), 1e-12);
+            testing::assert_equal_within_epsilon(lever, throttle.to_ratio().get::<ratio>(), 1e-12);
+        }
+    }
+
+    #[test]
+    fn default_passthrough_follows_power_law() {
+        let curve = PassthroughCurve::default();
+        testing::assert_equal_within_epsilon(
+            0.5_f64.powf(3.5),
+            curve.apply(Ratio::new::<ratio>(0.5)).get::<ratio>(),
+            1e-12,
+        );
+        let square = PassthroughCurve::Power { exponent: 2. };
+        testing::assert_equal_within_epsilon(
+            0.25,
+            square.apply(Ratio::new::<ratio>(0.5)).get::<ratio>(),
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn default_installation_losses_reproduce_climb_targets() {
+        testing::assert_equal_within_epsilon(
+            0.93,
+            InstalledThrustLosses::default().efficiency().get::<ratio>(),
+            1e-12,
+        );
+
+        let tuned = ClimbThrustSchedule {
+            thrust_efficiency: Ratio::new::<percent>(93.0),
+            ..ClimbThrustSchedule::default()
+        };
+        let mut derived = FadecController::default().with_startup_lockout(0);
+        let mut tuned = FadecController {
+            climb_schedule: tuned,
+            ..FadecController::default()
+        }
+        .with_startup_lockout(0);
+
+        for density in [0.0023, 0.0017, 0.0009, 0.0004] {
+            let density = MassDensity::new::<slug_per_cubic_foot>(density);
+            let (derived_target, _) = climb_step_with_ground(&mut derived, density, false);
+            let (tuned_target, _) = climb_step_with_ground(&mut tuned, density, false);
+            testing::assert_equal_within_epsilon(
+                tuned_target.to_force().get::<poundal>(),
+                derived_target.to_force().get::<poundal>(),
+                1e-9,
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn config_derives_efficiency_from_installation_losses() {
+        let fadec = FadecController::from_config(
+            r#"{ "installation_losses": { "inlet": 0.04, "bleed": 0.06 } }"#,
+        )
+        .unwrap();
+
+        testing::assert_equal_within_epsilon(
+            0.9,
+            fadec.climb_schedule.thrust_efficiency.get::<ratio>(),
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn default_schedule_matches_altitude_breakpoints() {
+        let schedule = ClimbThrustSchedule::default();
+        let thrust_at = |feet| {
+            schedule
+                .low_altitude_thrust(PressureAltitude::new::<foot>(feet))
+                .get::<poundal>()
+        };
+        let loss_at = |feet| {
+            schedule
+                .high_altitude_loss(PressureAltitude::new::<foot>(feet))
+                .get::<poundal>()
+        };
+
+        testing::assert_equal_within_epsilon(2050. + 3500. / 24., thrust_at(3500.), 1e-9);
+        testing::assert_equal_within_epsilon(2050., thrust_at(10_000.), 1e-9);
+        testing::assert_equal_within_epsilon(0., loss_at(30_000.), 1e-9);
+        testing::assert_equal_within_epsilon(1., loss_at(35_064.), 1e-9);
+        testing::assert_equal_within_epsilon(110., loss_at(45_000.), 1e-9);
+    }
+
+    #[test]
+    fn implausible_readings_keep_fadec_in_passthrough() {
+        let mut fadec = FadecController::default().with_startup_lockout(3);
+        let passthrough = ThrottlePercent::from(ThrottleAxis::CLIMB);
+
+        for _ in 0..10 {
+            let throttle = climb_step(&mut fadec, MassDensity::new::<slug_per_cubic_foot>(0.));
+            assert!(fadec.is_locked_out());
+            assert_eq!(passthrough, throttle);
+        }
+
+        let throttle = climb_step(
+            &mut fadec,
+            MassDensity::new::<slug_per_cubic_foot>(f64::NAN),
+        );
+        assert!(fadec.is_locked_out());
+        assert_eq!(passthrough, throttle);
+        assert_eq!(PidController::default(), *fadec.pid_state());
+    }
+
+    #[test]
+    fn invalid_thrust_reading_holds_last_command() {
+        let mut fadec = FadecController::default().with_startup_lockout(3);
+        let density = MassDensity::new::<slug_per_cubic_foot>(0.0017);
+
+        let mut held = ThrottlePercent::MIN;
+        for _ in 0..5 {
+            held = climb_step(&mut fadec, density);
+        }
+        let pid_state = *fadec.pid_state();
+
+        let throttle = fadec
+            .get_desired_output(
+                ThrottleAxis::CLIMB.to_ratio(),
+                ThrottleMode::Climb,
+                Force::new::<poundal>(f64::NAN),
+                &instruments(
+                    MachNumber::new::<ratio>(0.3),
+                    Velocity::new::<knot>(220.),
+                    density,
+                    PressureAltitude::new::<foot>(10_000.),
+                    false,
+                ),
+                Time::new::<second>(1. / 60.),
+            )
+            .throttle;
+
+        assert!(fadec.is_holding());
+        assert!(!fadec.is_locked_out());
+        assert_eq!(held, throttle);
+        assert_ne!(ThrottlePercent::from(ThrottleAxis::CLIMB), throttle);
+        assert_eq!(pid_state, *fadec.pid_state());
+
+        climb_step(&mut fadec, density);
+        assert!(!fadec.is_holding());
+        assert_ne!(pid_state, *fadec.pid_state());
+    }
+
+    #[test]
+    fn plausible_readings_release_lockout() {
+        let mut fadec = FadecController::default().with_startup_lockout(3);
+        let density = MassDensity::new::<slug_per_cubic_foot>(0.0017);
+
+        climb_step(&mut fadec, density);
+        climb_step(&mut fadec, density);
+        assert!(fadec.is_locked_out());
+
+        climb_step(&mut fadec, MassDensity::new::<slug_per_cubic_foot>(0.));
+        climb_step(&mut fadec, density);
+        climb_step(&mut fadec, density);
+        assert!(fadec.is_locked_out());
+
+        climb_step(&mut fadec, density);
+        assert!(!fadec.is_locked_out());
+        assert_ne!(PidController::default(), *fadec.pid_state());
+    }
+
+    #[test]
+    fn climb_pid_config_displays_gains_in_human_terms() {
+        assert_eq!(
+            "Kp 1.2 %/1000 pdl, Ki 0.1 %/(1000 pdl·s), Kd 1.8 %/(1000 pdl/s), output [-2, 2] %, derivative [-20, 20] %",
+            ClimbFadecPidConfiguration::default().to_string()
+        );
+    }
+
+    #[test]
+    fn gross_thrust_accounts_for_ram_pressure_at_mach() {
+        let thrust = Force::new::<poundal>(1_000.);
+
+        assert_eq!(
+            thrust,
+            convert_to_gross_thrust(thrust, MachNumber::new::<ratio>(0.))
+        );
+        testing::assert_equal_within_epsilon(
+            1_000. * 1.072_f64.powf(3.5),
+            convert_to_gross_thrust(thrust, MachNumber::new::<ratio>(0.6)).get::<poundal>(),
+            1e-9,
+        );
+    }
+
+    #[test]
+    fn t_get_max_density_thrust() {
+        let input = MassDensity::new::<slug_per_cubic_foot>(0.00241899350658059);
+
+        //0.03108096668
+
+        let expected = 0.00241899350658059 * 1000. * 1351.6 + 250.;
+        let actual = get_max_density_thrust(input).get::<poundal>();
+
+        testing::assert_equal_in_significant_figures(expected, actual, 12)
+    }
+
+    #[test]
+    fn degenerate_density_yields_baseline_max_thrust() {
+        for &density in &[0., -0.002, f64::NAN] {
+            let input = MassDensity::new::<slug_per_cubic_foot>(density);
+            let actual = get_max_density_thrust(floor_ambient_density(input)).get::<poundal>();
+
+            testing::assert_equal_within_epsilon(250., actual, 1.);
+        }
+    }
+
+    #[test]
+    fn plausible_density_is_not_floored() {
+        let input = MassDensity::new::<slug_per_cubic_foot>(0.0017);
+        assert_eq!(input, floor_ambient_density(input));
     }
 
     #[test]
@@ -9,10 +9,29 @@
 #![deny(missing_debug_implementations, unused_must_use)]
 #![forbid(unsafe_code)]
 
+mod altitude_hold;
+pub mod calibration;
 pub mod control_params;
+mod display;
 pub mod engines;
 mod fadec;
+pub mod migrate;
 mod state;
+mod systems;
 
-pub use fadec::FadecController;
-pub use state::{Aircraft, Engine, EngineReadings, Environment, Instruments, Snapshot};
+pub use altitude_hold::{
+    cascade_configuration, vertical_speed_target, AltitudeHold, MAX_VERTICAL_SPEED,
+};
+pub use display::{DisplayedThrustTarget, ThrustRate};
+#[cfg(feature = "serde")]
+pub use fadec::FadecConfig;
+pub use fadec::{
+    ClimbOutputRange, ClimbSpeedBlend, ClimbThrustSchedule, FadecController, FadecOutput,
+    FadecReadings, InstalledThrustLosses, OverspeedProtection, PassthroughCurve, ThrustLimitSource,
+    TurbineTemperatureLimit,
+};
+pub use state::{
+    Aircraft, CommandedOutputs, Engine, EngineReadings, Environment, Instruments, RawAxisEvent,
+    Snapshot, ThrustAsymmetry, TimingReport, SNAPSHOT_SCHEMA_VERSION,
+};
+pub use systems::{AircraftSystems, ModeTransition, SystemOutputs};
@@ -0,0 +1,118 @@
+//! Migration of recorded snapshots to the current schema
+//!
+//! Every field added to [`Snapshot`] since version 1 takes a default when
+//! missing, so a recording of any version can be read into the current
+//! [`Snapshot`]. Those defaults are only placeholders, though. Where a field
+//! was introduced that the recorder derives from state that older recordings
+//! already held, the conversion to that version reconstructs it from the rest
+//! of the snapshot, as the recorder would have written it. Fields with no
+//! such source keep their defaults.
+//!
+//! Conversions are applied in order, one schema version at a time, so a
+//! version 1 snapshot passes through every conversion up to
+//! [`SNAPSHOT_SCHEMA_VERSION`].
+
+use crate::{CommandedOutputs, Snapshot, SNAPSHOT_SCHEMA_VERSION};
+
+/// The conversion from each schema version to the next, starting from
+/// version 1
+const CONVERSIONS: [fn(Snapshot) -> Snapshot; SNAPSHOT_SCHEMA_VERSION as usize - 1] = [
+    v1_to_v2, v2_to_v3, v3_to_v4, v4_to_v5, v5_to_v6, v6_to_v7, v7_to_v8, v8_to_v9, v9_to_v10,
+];
+
+/// Migrates a snapshot of any prior schema version to the current schema
+///
+/// Snapshots already at the current schema version, or recorded by a newer
+/// build, are returned unchanged.
+pub fn migrate(mut snapshot: Snapshot) -> Snapshot {
+    let from = snapshot.schema_version.max(1) as usize;
+    for (to, convert) in CONVERSIONS.iter().enumerate().skip(from - 1) {
+        snapshot = convert(snapshot);
+        snapshot.schema_version = to as u32 + 2;
+    }
+    snapshot
+}
+
+/// Version 2 added the schema version, the thrust asymmetry, whether the
+/// aircraft is on the ground, and the commanded thrust of each engine
+///
+/// None of these were recorded before, so they keep their defaults.
+fn v1_to_v2(snapshot: Snapshot) -> Snapshot {
+    snapshot
+}
+
+/// Version 3 added the marker, which is absent from unmarked frames
+fn v2_to_v3(snapshot: Snapshot) -> Snapshot {
+    snapshot
+}
+
+/// Version 4 added whether overspeed protection was active
+///
+/// Older recordings hold no trace of the protection, so it is reported
+/// inactive.
+fn v3_to_v4(snapshot: Snapshot) -> Snapshot {
+    snapshot
+}
+
+/// Version 5 added whether the readings from each engine were valid
+///
+/// Derived from the recorded engine readings.
+fn v4_to_v5(snapshot: Snapshot) -> Snapshot {
+    Snapshot {
+        readings_valid: snapshot.environment.engines.map(|_, r| r.is_valid()),
+        ..snapshot
+    }
+}
+
+/// Version 6 added the values written to the simulator
+///
+/// Derived from the recorded aircraft state, which holds the displayed
+/// throttle position and mode and the throttle sent to each engine.
+fn v5_to_v6(snapshot: Snapshot) -> Snapshot {
+    let engines = &snapshot.aircraft.engines;
+    Snapshot {
+        commanded: Some(CommandedOutputs {
+            throttle_position: engines.map(|_, e| e.visual_throttle),
+            throttle_mode: engines.map(|_, e| e.mode),
+            engine_throttle: engines.map(|_, e| e.engine_throttle),
+            ..CommandedOutputs::default()
+        }),
+        ..snapshot
+    }
+}
+
+/// Version 7 added the fuel flow of each engine, which was not recorded before
+fn v6_to_v7(snapshot: Snapshot) -> Snapshot {
+    snapshot
+}
+
+/// Version 8 added the source of the thrust target of each engine
+///
+/// Older recordings do not say what set the thrust target, so the source is
+/// reported as the lever.
+fn v7_to_v8(snapshot: Snapshot) -> Snapshot {
+    snapshot
+}
+
+/// Version 9 added whether the turbine temperature limit capped the thrust,
+/// which was not recorded before and is reported as not limiting
+fn v8_to_v9(snapshot: Snapshot) -> Snapshot {
+    snapshot
+}
+
+/// Version 10 added the FADEC thrust target and whether the FADEC was active
+/// to the values written to the simulator
+///
+/// The thrust target is taken from the commanded thrust of each engine,
+/// recorded since version 2. Whether the FADEC was active was not recorded,
+/// so it is reported inactive.
+fn v9_to_v10(snapshot: Snapshot) -> Snapshot {
+    let engines = &snapshot.aircraft.engines;
+    Snapshot {
+        commanded: snapshot.commanded.map(|commanded| CommandedOutputs {
+            thrust_target: engines.map(|_, e| e.commanded_thrust),
+            ..commanded
+        }),
+        ..snapshot
+    }
+}
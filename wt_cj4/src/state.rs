@@ -1,16 +1,23 @@
 //! Aircraft state information
 
-use crate::control_params::{ThrottleAxis, ThrottleMode, ThrottlePercent};
+use crate::control_params::{ThrottleAxis, ThrottleMode, ThrottlePercent, ThrustValue};
 use crate::engines::EngineData;
-use crate::FadecController;
+use crate::systems::{calculate_throttle_position, select_throttle_mode};
+use crate::{FadecController, FadecReadings, ThrustLimitSource};
+use avmath::airspeed::MachNumber;
 use avmath::isa::{GeometricAltitude, PressureAltitude};
 
 /// Environmental readings from general instrumentation
-#[derive(Clone, Copy, Debug, PartialEq)]
+///
+/// Instruments are embedded in recorded snapshots. Every field takes its
+/// default when missing from a record, so fields may be added without
+/// breaking older recordings.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Instruments {
     /// Aircraft speed represented as a percentage of the speed of sound
-    pub mach_number: uom::si::f64::Ratio,
+    pub mach_number: MachNumber,
 
     /// Ambient density
     pub ambient_density: uom::si::f64::MassDensity,
@@ -29,19 +36,77 @@ pub struct Instruments {
 
     /// Vertical speed
     pub vertical_speed: uom::si::f64::Velocity,
+
+    /// Whether the aircraft is on the ground
+    pub on_ground: bool,
+}
+
+impl avmath::readings::AtmosphericReadings for Instruments {
+    #[inline]
+    fn mach_number(&self) -> MachNumber {
+        self.mach_number
+    }
+
+    #[inline]
+    fn ambient_density(&self) -> uom::si::f64::MassDensity {
+        self.ambient_density
+    }
+
+    #[inline]
+    fn pressure_altitude(&self) -> PressureAltitude {
+        self.pressure_altitude
+    }
+}
+
+impl FadecReadings for Instruments {
+    #[inline]
+    fn airspeed_indicated(&self) -> uom::si::f64::Velocity {
+        self.airspeed_indicated
+    }
+
+    #[inline]
+    fn on_ground(&self) -> bool {
+        self.on_ground
+    }
 }
 
 /// Engine-specific readings
-#[derive(Clone, Copy, Debug, PartialEq)]
+///
+/// Every field takes its default when missing from a recorded snapshot.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct EngineReadings {
     /// Thrust developed by the engines
     pub thrust: uom::si::f64::Force,
+
+    /// Rate at which the engine is burning fuel
+    ///
+    /// Absent from recordings made before fuel flow was recorded.
+    pub fuel_flow: uom::si::f64::MassRate,
+}
+
+impl EngineReadings {
+    /// Whether the thrust reading is usable
+    ///
+    /// The simulator may report a non-finite or negative thrust for a few
+    /// frames after the aircraft spawns.
+    pub fn is_thrust_valid(&self) -> bool {
+        self.thrust.is_finite() && self.thrust.value >= 0.
+    }
+
+    /// Whether all of the engine readings are usable
+    pub fn is_valid(&self) -> bool {
+        self.is_thrust_valid()
+    }
 }
 
 /// Overall inputs for the aircraft simulation
-#[derive(Clone, Copy, Debug, PartialEq)]
+///
+/// Every field takes its default when missing from a recorded snapshot.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Environment {
     /// Readings from instruments
     pub instruments: Instruments,
@@ -60,6 +125,10 @@ pub struct Engine {
     /// The throttle command sent to the engines
     pub engine_throttle: ThrottlePercent,
 
+    /// The thrust the FADEC is attempting to obtain from the engine
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub commanded_thrust: ThrustValue,
+
     /// The throttle position on the console
     pub visual_throttle: ThrottlePercent,
 
@@ -70,6 +139,16 @@ pub struct Engine {
     pub fadec: FadecController,
 }
 
+impl Engine {
+    /// How far the engine is from the thrust the FADEC is commanding
+    ///
+    /// Positive values indicate that the engine is short of the target. Only
+    /// available while the FADEC is scheduling climb thrust.
+    pub fn thrust_error(&self) -> Option<uom::si::f64::Force> {
+        self.fadec.thrust_error()
+    }
+}
+
 /// The state of the entire aircraft
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -78,10 +157,94 @@ pub struct Aircraft {
     pub engines: EngineData<Engine>,
 }
 
+impl Aircraft {
+    /// Constructs an aircraft with the throttle levers in the position
+    /// provided
+    ///
+    /// Each engine starts in the mode corresponding to the lever position,
+    /// with the throttle commands matching the lever, so that loading with
+    /// the levers forward does not momentarily command idle.
+    pub fn new_with_lever(axis: ThrottleAxis) -> Self {
+        let engine = Engine::default();
+        let mode = select_throttle_mode(axis, engine.fadec.has_reverse());
+        let throttle = calculate_throttle_position(mode, axis);
+
+        Self {
+            engines: EngineData::new(Engine {
+                mode,
+                engine_throttle: throttle,
+                visual_throttle: throttle,
+                physical_throttle: axis,
+                ..engine
+            }),
+        }
+    }
+
+    /// Compares the commanded and achieved thrust of each engine to identify
+    /// an engine that is lagging behind the other
+    ///
+    /// An engine is flagged as lagging when its thrust shortfall exceeds the
+    /// shortfall of the opposite engine by more than `threshold`.
+    pub fn thrust_asymmetry(
+        &self,
+        readings: &EngineData<EngineReadings>,
+        threshold: uom::si::f64::Force,
+    ) -> ThrustAsymmetry {
+        let mut shortfall = self.engines.map(|_, e| e.commanded_thrust.to_force());
+        shortfall.zip(readings, |_, s, r| *s -= r.thrust);
+
+        let lagging = EngineData::new_from(|e| shortfall[e] - shortfall[e.opposite()] > threshold);
+
+        ThrustAsymmetry { shortfall, lagging }
+    }
+}
+
+/// Differences between commanded and achieved thrust across the engines
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThrustAsymmetry {
+    /// The amount by which achieved thrust falls short of commanded thrust
+    pub shortfall: EngineData<uom::si::f64::Force>,
+
+    /// Whether the engine is lagging behind the opposite engine
+    pub lagging: EngineData<bool>,
+}
+
+impl ThrustAsymmetry {
+    /// Whether any engine is lagging behind the opposite engine
+    pub fn is_asymmetric(&self) -> bool {
+        self.lagging.engine1 || self.lagging.engine2
+    }
+}
+
+/// The version of the snapshot schema written by this build
+///
+/// Recordings made before the schema version was recorded are treated as
+/// version 1. Fields added since then take their default values when reading
+/// older recordings. Each version added must be given a conversion in
+/// [`migrate`](crate::migrate).
+pub const SNAPSHOT_SCHEMA_VERSION: u32 = 10;
+
+#[cfg(feature = "serde")]
+fn legacy_schema_version() -> u32 {
+    1
+}
+
+#[cfg(feature = "serde")]
+fn legacy_readings_valid() -> EngineData<bool> {
+    EngineData::new(true)
+}
+
 /// A snapshot of the aircraft simulation data
 #[derive(Clone, Copy, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Snapshot {
+    /// The schema version of the snapshot
+    ///
+    /// Should be set to [`SNAPSHOT_SCHEMA_VERSION`] when recording.
+    #[cfg_attr(feature = "serde", serde(default = "legacy_schema_version"))]
+    pub schema_version: u32,
+
     /// The time of the snapshot
     ///
     /// Listed as time since the start of the program
@@ -95,4 +258,381 @@ pub struct Snapshot {
 
     /// Aircraft state after applying all systems
     pub aircraft: Aircraft,
+
+    /// Asymmetry between commanded and achieved thrust across the engines
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub thrust_asymmetry: ThrustAsymmetry,
+
+    /// A marker annotating the frame, if one was requested during the step
+    ///
+    /// Markers are present only on the frame during which they were set.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub marker: Option<u32>,
+
+    /// Whether overspeed protection reduced the thrust target of each engine
+    /// during the step
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub overspeed_protection: EngineData<bool>,
+
+    /// Whether the turbine temperature limit capped the thrust of each engine
+    /// during the step
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub turbine_temperature_limit: EngineData<bool>,
+
+    /// Whether the readings from each engine were usable during the step
+    ///
+    /// Recordings made before validity was recorded are assumed to be valid.
+    #[cfg_attr(feature = "serde", serde(default = "legacy_readings_valid"))]
+    pub readings_valid: EngineData<bool>,
+
+    /// The branch of the FADEC that set the thrust target of each engine
+    /// during the step
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub thrust_limit_source: EngineData<ThrustLimitSource>,
+
+    /// The values written to the simulator after the step
+    ///
+    /// Absent from recordings made before the written values were recorded.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub commanded: Option<CommandedOutputs>,
+}
+
+/// The values written to the simulator for each engine
+///
+/// Recorded separately from the readings so that what was commanded can be
+/// compared against what the engines achieved.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommandedOutputs {
+    /// The throttle position displayed on the console (`Throttle{n}_Pos`)
+    pub throttle_position: EngineData<ThrottlePercent>,
+
+    /// The FADEC throttle mode displayed (`THROTTLE{n}_MODE`)
+    pub throttle_mode: EngineData<ThrottleMode>,
+
+    /// The throttle sent to the engine through SimConnect
+    /// (`GENERAL ENG THROTTLE LEVER POSITION:{n}`)
+    pub engine_throttle: EngineData<ThrottlePercent>,
+
+    /// The FADEC thrust target displayed (`THROTTLE{n}_THRUST_TARGET`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub thrust_target: EngineData<ThrustValue>,
+
+    /// Whether the FADEC is displayed as controlling the engine
+    /// (`THROTTLE{n}_FADEC_ACTIVE`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fadec_active: EngineData<bool>,
+}
+
+/// An input event as received from the simulator, before any processing
+///
+/// Raw events are recorded to a side channel alongside the snapshots to help
+/// diagnose hardware that sends spurious or unexpected input.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawAxisEvent {
+    /// The simulation time of the first control step after the event was
+    /// received
+    pub sim_time: uom::si::f64::Time,
+
+    /// The notification group through which the event was received
+    pub group_id: u32,
+
+    /// The event identifier
+    pub event_id: u32,
+
+    /// The data accompanying the event, such as an axis position
+    pub data: u32,
+}
+
+impl Snapshot {
+    /// Whether the step leading to this snapshot advanced the simulation
+    ///
+    /// Paused or time-accelerated sessions can record steps with a zero or
+    /// negative change in time.
+    pub fn has_positive_delta_t(&self) -> bool {
+        self.delta_t > uom::si::f64::Time::default()
+    }
+
+    /// Whether this snapshot was taken strictly after `prior`
+    pub fn is_after(&self, prior: &Snapshot) -> bool {
+        self.sim_time > prior.sim_time
+    }
+}
+
+/// Counts of snapshots in a recording whose simulation time is not monotonic
+///
+/// Snapshots are observed in recording order. Problems are tallied rather than
+/// treated as errors so that a recording can still be processed in full.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TimingReport {
+    /// The number of snapshots observed
+    pub records: usize,
+
+    /// The number of snapshots with a zero or negative change in time
+    pub non_positive_delta_t: usize,
+
+    /// The number of snapshots whose simulation time did not increase from
+    /// the prior snapshot
+    pub non_increasing_sim_time: usize,
+
+    prior: Option<Snapshot>,
+}
+
+impl TimingReport {
+    /// Checks the timing of the next snapshot in the recording
+    pub fn observe(&mut self, snapshot: &Snapshot) {
+        self.records += 1;
+        if !snapshot.has_positive_delta_t() {
+            self.non_positive_delta_t += 1;
+        }
+        if let Some(prior) = &self.prior {
+            if !snapshot.is_after(prior) {
+                self.non_increasing_sim_time += 1;
+            }
+        }
+        self.prior = Some(*snapshot);
+    }
+
+    /// Whether every observed snapshot advanced the simulation time
+    pub fn is_monotonic(&self) -> bool {
+        self.non_positive_delta_t == 0 && self.non_increasing_sim_time == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engines::EngineNumber;
+    use uom::si::{
+        f64::{Force, MassDensity, Time, Velocity},
+        force::poundal,
+        length::foot,
+        mass_density::slug_per_cubic_foot,
+        ratio::ratio,
+        time::second,
+        velocity::knot,
+    };
+    use wt_systems::testing;
+
+    fn snapshot_at(sim_time: f64, delta_t: f64) -> Snapshot {
+        Snapshot {
+            schema_version: SNAPSHOT_SCHEMA_VERSION,
+            sim_time: Time::new::<second>(sim_time),
+            delta_t: Time::new::<second>(delta_t),
+            environment: Environment {
+                instruments: Instruments {
+                    mach_number: MachNumber::new::<ratio>(0.4),
+                    ambient_density: MassDensity::new::<slug_per_cubic_foot>(0.0017),
+                    geometric_altitude: GeometricAltitude::new::<foot>(10_000.),
+                    pressure_altitude: PressureAltitude::new::<foot>(10_000.),
+                    airspeed_indicated: Velocity::new::<knot>(220.),
+                    airspeed_true: Velocity::new::<knot>(255.),
+                    vertical_speed: Velocity::new::<knot>(0.),
+                    on_ground: false,
+                },
+                engines: EngineData::new(EngineReadings {
+                    thrust: Force::new::<poundal>(1_500.),
+                    fuel_flow: Default::default(),
+                }),
+            },
+            aircraft: Aircraft::default(),
+            thrust_asymmetry: ThrustAsymmetry::default(),
+            marker: None,
+            overspeed_protection: EngineData::default(),
+            turbine_temperature_limit: EngineData::default(),
+            readings_valid: EngineData::new(true),
+            thrust_limit_source: EngineData::default(),
+            commanded: None,
+        }
+    }
+
+    #[test]
+    fn timing_report_counts_out_of_order_snapshots() {
+        let mut report = TimingReport::default();
+        for &(sim_time, delta_t) in &[
+            (0.1, 0.1),
+            (0.2, 0.1),
+            (0.2, 0.),
+            (0.15, -0.05),
+            (0.3, 0.15),
+            (0.4, 0.1),
+        ] {
+            report.observe(&snapshot_at(sim_time, delta_t));
+        }
+
+        assert!(!report.is_monotonic());
+        assert_eq!(6, report.records);
+        assert_eq!(2, report.non_positive_delta_t);
+        assert_eq!(2, report.non_increasing_sim_time);
+    }
+
+    #[test]
+    fn timing_report_accepts_monotonic_recording() {
+        let mut report = TimingReport::default();
+        for i in 1..=10 {
+            report.observe(&snapshot_at(f64::from(i) / 30., 1. / 30.));
+        }
+
+        assert!(report.is_monotonic());
+        assert_eq!(10, report.records);
+    }
+
+    #[test]
+    fn construction_from_climb_detent_yields_climb_mode() {
+        let aircraft = Aircraft::new_with_lever(ThrottleAxis::CLIMB);
+
+        aircraft.engines.for_each(|_, engine| {
+            assert_eq!(ThrottleMode::Climb, engine.mode);
+            assert_eq!(ThrottleAxis::CLIMB, engine.physical_throttle);
+            assert_eq!(
+                ThrottlePercent::from(ThrottleAxis::CLIMB),
+                engine.visual_throttle
+            );
+            assert_eq!(engine.visual_throttle, engine.engine_throttle);
+        });
+    }
+
+    #[test]
+    fn construction_from_cruise_lever_keeps_lever_position() {
+        let axis = ThrottleAxis::from_raw(2_000.);
+        let aircraft = Aircraft::new_with_lever(axis);
+
+        aircraft.engines.for_each(|_, engine| {
+            assert_eq!(ThrottleMode::Cruise, engine.mode);
+            assert_eq!(ThrottlePercent::from(axis), engine.engine_throttle);
+        });
+    }
+
+    #[test]
+    fn non_finite_or_negative_thrust_is_invalid() {
+        let reading = |thrust| EngineReadings {
+            thrust: Force::new::<poundal>(thrust),
+            fuel_flow: Default::default(),
+        };
+
+        assert!(reading(0.).is_valid());
+        assert!(reading(1_500.).is_valid());
+        assert!(!reading(f64::NAN).is_valid());
+        assert!(!reading(f64::INFINITY).is_thrust_valid());
+        assert!(!reading(-1.).is_valid());
+    }
+
+    #[test]
+    fn lagging_engine_is_flagged() {
+        let mut aircraft = Aircraft::default();
+        aircraft.engines.update(|_, e| {
+            e.commanded_thrust = ThrustValue::from_force(Force::new::<poundal>(2000.))
+        });
+
+        let readings = EngineData::new_distinct(
+            EngineReadings {
+                thrust: Force::new::<poundal>(1950.),
+                fuel_flow: Default::default(),
+            },
+            EngineReadings {
+                thrust: Force::new::<poundal>(1700.),
+                fuel_flow: Default::default(),
+            },
+        );
+
+        let asymmetry = aircraft.thrust_asymmetry(&readings, Force::new::<poundal>(100.));
+
+        assert!(asymmetry.is_asymmetric());
+        assert!(!asymmetry.lagging[EngineNumber::Engine1]);
+        assert!(asymmetry.lagging[EngineNumber::Engine2]);
+        testing::assert_equal_within_epsilon(
+            300.,
+            asymmetry.shortfall[EngineNumber::Engine2].get::<poundal>(),
+            1e-9,
+        );
+    }
+
+    #[test]
+    fn symmetric_engines_are_not_flagged() {
+        let mut aircraft = Aircraft::default();
+        aircraft.engines.update(|_, e| {
+            e.commanded_thrust = ThrustValue::from_force(Force::new::<poundal>(2000.))
+        });
+
+        let readings = EngineData::new_distinct(
+            EngineReadings {
+                thrust: Force::new::<poundal>(1950.),
+                fuel_flow: Default::default(),
+            },
+            EngineReadings {
+                thrust: Force::new::<poundal>(1900.),
+                fuel_flow: Default::default(),
+            },
+        );
+
+        let asymmetry = aircraft.thrust_asymmetry(&readings, Force::new::<poundal>(100.));
+
+        assert!(!asymmetry.is_asymmetric());
+    }
+
+    /// Instruments as they might be extended in a later version
+    #[cfg(feature = "serde")]
+    #[derive(Debug, Default, serde::Deserialize)]
+    #[serde(default)]
+    struct ExtendedInstruments {
+        #[serde(flatten)]
+        instruments: Instruments,
+        ambient_temperature: uom::si::f64::ThermodynamicTemperature,
+    }
+
+    /// An environment as it might be extended in a later version
+    #[cfg(feature = "serde")]
+    #[derive(Debug, Default, serde::Deserialize)]
+    #[serde(default)]
+    struct ExtendedEnvironment {
+        instruments: ExtendedInstruments,
+        engines: EngineData<EngineReadings>,
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn environment_reads_into_extended_environment_with_defaults() {
+        let environment = Environment {
+            instruments: Instruments {
+                mach_number: MachNumber::new::<ratio>(0.5),
+                on_ground: true,
+                ..Instruments::default()
+            },
+            engines: EngineData::new(EngineReadings {
+                thrust: Force::new::<uom::si::force::newton>(8_000.),
+                fuel_flow: Default::default(),
+            }),
+        };
+        let record = serde_json::to_string(&environment).unwrap();
+
+        let extended: ExtendedEnvironment = serde_json::from_str(&record).unwrap();
+
+        assert_eq!(environment.instruments, extended.instruments.instruments);
+        assert_eq!(environment.engines, extended.engines);
+        assert_eq!(
+            uom::si::f64::ThermodynamicTemperature::default(),
+            extended.instruments.ambient_temperature
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn environment_missing_fields_reads_with_defaults() {
+        let environment: Environment = serde_json::from_str(
+            r#"{
+                "instruments": { "mach_number": 0.4 },
+                "engines": { "engine1": { "thrust": 10.0 }, "engine2": {} }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            MachNumber::new::<ratio>(0.4),
+            environment.instruments.mach_number
+        );
+        assert_eq!(Velocity::default(), environment.instruments.vertical_speed);
+        assert!(!environment.instruments.on_ground);
+        assert_eq!(EngineReadings::default(), environment.engines.engine2);
+    }
 }
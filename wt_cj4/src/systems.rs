@@ -0,0 +1,378 @@
+//! Orchestration of the aircraft systems
+
+use crate::control_params::{ThrottleAxis, ThrottleMode, ThrottlePercent, ThrustValue};
+use crate::display::{DisplayedThrustTarget, ThrustRate};
+use crate::engines::EngineData;
+use crate::{Aircraft, CommandedOutputs, Environment, ThrustAsymmetry};
+use uom::si::{
+    f64::{Force, Ratio, Time},
+    force::poundal,
+};
+
+/// Difference in thrust shortfall between engines before an engine is
+/// considered to be lagging, in poundals
+const THRUST_ASYMMETRY_THRESHOLD: f64 = 180.;
+
+/// Commands to be sent to the simulator after updating the aircraft systems
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SystemOutputs {
+    /// The throttle command to send to each engine
+    pub engine_throttle: EngineData<ThrottlePercent>,
+
+    /// The throttle position to display on the console
+    pub visual_throttle: EngineData<ThrottlePercent>,
+
+    /// The FADEC throttle mode to display
+    pub mode: EngineData<ThrottleMode>,
+
+    /// The change in FADEC throttle mode during this update, if any
+    pub mode_transitions: EngineData<Option<ModeTransition>>,
+
+    /// The thrust target of each engine to display, which ramps toward the
+    /// control target when a display rate has been set
+    pub thrust_target: EngineData<ThrustValue>,
+
+    /// Whether the FADEC is controlling each engine, rather than passing the
+    /// lever through
+    pub fadec_active: EngineData<bool>,
+}
+
+impl SystemOutputs {
+    /// The values to be written to the simulator
+    pub fn commanded(&self) -> CommandedOutputs {
+        CommandedOutputs {
+            throttle_position: self.visual_throttle,
+            throttle_mode: self.mode,
+            engine_throttle: self.engine_throttle,
+            thrust_target: self.thrust_target,
+            fadec_active: self.fadec_active,
+        }
+    }
+}
+
+/// A change in the FADEC throttle mode of an engine
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ModeTransition {
+    /// The mode prior to the update
+    pub from: ThrottleMode,
+
+    /// The mode after the update
+    pub to: ThrottleMode,
+}
+
+/// The aircraft systems, independent of any gauge
+///
+/// Owns the aircraft state and steps all systems forward given readings from
+/// the environment.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AircraftSystems {
+    aircraft: Aircraft,
+    thrust_asymmetry: ThrustAsymmetry,
+    #[cfg_attr(feature = "serde", serde(default))]
+    center_detent: Option<Ratio>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    displayed_thrust_target: EngineData<DisplayedThrustTarget>,
+}
+
+impl AircraftSystems {
+    /// Constructs the aircraft systems from an existing aircraft state
+    pub fn new(aircraft: Aircraft) -> Self {
+        Self {
+            aircraft,
+            thrust_asymmetry: ThrustAsymmetry::default(),
+            center_detent: None,
+            displayed_thrust_target: EngineData::default(),
+        }
+    }
+
+    /// Holds the current thrust of an engine while its lever is within
+    /// `detent` of the center of the axis, or maps the center like any other
+    /// lever position if `None`
+    ///
+    /// Spring-centered levers rest at the center of the axis when released.
+    /// With a detent, the FADEC is not stepped while the lever rests there,
+    /// and the engine keeps the mode, thrust target, and throttle commanded
+    /// before the lever was released.
+    pub fn set_center_detent(&mut self, detent: Option<Ratio>) {
+        self.center_detent = detent;
+    }
+
+    /// The detent either side of center within which the current thrust is
+    /// held, if any
+    pub fn center_detent(&self) -> Option<Ratio> {
+        self.center_detent
+    }
+
+    /// Limits the rate at which the displayed thrust target of each engine
+    /// follows the control target, or displays the control target directly if
+    /// `None`
+    ///
+    /// Only the thrust target in the outputs is affected. The FADEC continues
+    /// to control toward the unsmoothed target.
+    pub fn set_thrust_target_rate(&mut self, max_rate: Option<ThrustRate>) {
+        self.displayed_thrust_target
+            .update(|_, display| display.set_max_rate(max_rate));
+    }
+
+    /// The current aircraft state
+    pub fn aircraft(&self) -> &Aircraft {
+        &self.aircraft
+    }
+
+    /// Mutable access to the aircraft state, such as for applying control
+    /// inputs
+    pub fn aircraft_mut(&mut self) -> &mut Aircraft {
+        &mut self.aircraft
+    }
+
+    /// Asymmetry between commanded and achieved thrust as of the last update
+    pub fn thrust_asymmetry(&self) -> ThrustAsymmetry {
+        self.thrust_asymmetry
+    }
+
+    /// Steps all aircraft systems forward in time
+    pub fn update(&mut self, environment: &Environment, delta_t: Time) -> SystemOutputs {
+        let mut mode_transitions = EngineData::default();
+        let center_detent = self.center_detent;
+        self.aircraft
+            .engines
+            .zip(&environment.engines, |n, engine, input| {
+                if let Some(detent) = center_detent {
+                    if engine.physical_throttle.is_centered(detent) {
+                        return;
+                    }
+                }
+
+                let prior_mode = engine.mode;
+                engine.mode =
+                    select_throttle_mode(engine.physical_throttle, engine.fadec.has_reverse());
+                if engine.mode != prior_mode {
+                    mode_transitions[n] = Some(ModeTransition {
+                        from: prior_mode,
+                        to: engine.mode,
+                    });
+                }
+
+                let output = engine.fadec.get_desired_output(
+                    engine.physical_throttle.to_ratio(),
+                    engine.mode,
+                    input.thrust,
+                    &environment.instruments,
+                    delta_t,
+                );
+                engine.commanded_thrust = output.thrust_target;
+                engine.engine_throttle = output.throttle;
+                engine.visual_throttle =
+                    calculate_throttle_position(engine.mode, engine.physical_throttle);
+            });
+
+        self.thrust_asymmetry = self.aircraft.thrust_asymmetry(
+            &environment.engines,
+            Force::new::<poundal>(THRUST_ASYMMETRY_THRESHOLD),
+        );
+
+        let mut thrust_target = EngineData::default();
+        let engines = &self.aircraft.engines;
+        self.displayed_thrust_target.update(|n, display| {
+            thrust_target[n] = display.step(engines[n].commanded_thrust, delta_t);
+        });
+
+        SystemOutputs {
+            engine_throttle: self.aircraft.engines.map(|_, e| e.engine_throttle),
+            visual_throttle: self.aircraft.engines.map(|_, e| e.visual_throttle),
+            mode: self.aircraft.engines.map(|_, e| e.mode),
+            mode_transitions,
+            thrust_target,
+            fadec_active: self.aircraft.engines.map(|_, e| e.fadec.is_active()),
+        }
+    }
+}
+
+/// Selects the throttle mode for the lever position
+///
+/// The reverse range is only distinguished from the idle detent when the
+/// FADEC has a reverse limit configured. Otherwise, the bottom of the lever
+/// travel remains in the idle detent.
+pub(crate) fn select_throttle_mode(axis: ThrottleAxis, reverse_available: bool) -> ThrottleMode {
+    if axis > ThrottleAxis::CLIMB_MAX {
+        ThrottleMode::Takeoff
+    } else if axis > ThrottleAxis::CRUISE_MAX {
+        ThrottleMode::Climb
+    } else if axis > ThrottleAxis::UNDEF_MAX {
+        ThrottleMode::Cruise
+    } else if axis > ThrottleAxis::REVERSE_MAX || !reverse_available {
+        ThrottleMode::Undefined
+    } else {
+        ThrottleMode::Reverse
+    }
+}
+
+pub(crate) fn calculate_throttle_position(
+    mode: ThrottleMode,
+    axis: ThrottleAxis,
+) -> ThrottlePercent {
+    let target_throttle = match mode {
+        ThrottleMode::Takeoff => ThrottleAxis::TAKEOFF,
+        ThrottleMode::Climb => ThrottleAxis::CLIMB,
+        ThrottleMode::Cruise | ThrottleMode::Undefined | ThrottleMode::Reverse => axis,
+    };
+
+    ThrottlePercent::from(target_throttle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engines::EngineNumber;
+    use crate::{EngineReadings, Instruments};
+    use avmath::airspeed::MachNumber;
+    use avmath::isa::{GeometricAltitude, PressureAltitude};
+    use uom::si::{
+        f64::{MassDensity, Velocity},
+        length::foot,
+        mass_density::slug_per_cubic_foot,
+        ratio::ratio,
+        time::second,
+        velocity::knot,
+    };
+
+    fn environment(thrust: f64) -> Environment {
+        Environment {
+            instruments: Instruments {
+                mach_number: MachNumber::new::<ratio>(0.4),
+                ambient_density: MassDensity::new::<slug_per_cubic_foot>(0.0017),
+                geometric_altitude: GeometricAltitude::new::<foot>(10_000.),
+                pressure_altitude: PressureAltitude::new::<foot>(10_000.),
+                airspeed_indicated: Velocity::new::<knot>(220.),
+                airspeed_true: Velocity::new::<knot>(255.),
+                vertical_speed: Velocity::new::<knot>(15.),
+                on_ground: false,
+            },
+            engines: EngineData::new(EngineReadings {
+                thrust: Force::new::<poundal>(thrust),
+                fuel_flow: Default::default(),
+            }),
+        }
+    }
+
+    #[test]
+    fn updates_drive_fadec_through_facade() {
+        let mut systems = AircraftSystems::default();
+        systems
+            .aircraft_mut()
+            .engines
+            .update(|_, e| e.physical_throttle = ThrottleAxis::from_raw(12_000.));
+
+        let delta_t = Time::new::<second>(1. / 30.);
+        let mut outputs = SystemOutputs::default();
+        for _ in 0..30 {
+            outputs = systems.update(&environment(1_000.), delta_t);
+        }
+
+        assert_eq!(EngineData::new(ThrottleMode::Climb), outputs.mode);
+        assert_eq!(
+            EngineData::new(ThrottlePercent::from(ThrottleAxis::CLIMB)),
+            outputs.visual_throttle
+        );
+        assert_eq!(
+            systems.aircraft().engines.map(|_, e| e.engine_throttle),
+            outputs.engine_throttle
+        );
+        assert!(!systems.aircraft().engines[EngineNumber::Engine1]
+            .fadec
+            .is_locked_out());
+        assert!(!systems.thrust_asymmetry().is_asymmetric());
+    }
+
+    #[test]
+    fn lever_sweep_emits_ordered_mode_transitions() {
+        let mut systems = AircraftSystems::default();
+        let delta_t = Time::new::<second>(1. / 30.);
+
+        let sweep = [
+            ThrottleAxis::MIN,
+            ThrottleAxis::from_raw(0.),
+            ThrottleAxis::from_raw(4_000.),
+            ThrottleAxis::CLIMB,
+            ThrottleAxis::CLIMB,
+            ThrottleAxis::TAKEOFF,
+            ThrottleAxis::CLIMB,
+        ];
+        let transitions: Vec<ModeTransition> = sweep
+            .iter()
+            .filter_map(|&axis| {
+                systems.aircraft_mut().engines.engine1.physical_throttle = axis;
+                let outputs = systems.update(&environment(1_000.), delta_t);
+                assert_eq!(None, outputs.mode_transitions[EngineNumber::Engine2]);
+                outputs.mode_transitions[EngineNumber::Engine1]
+            })
+            .collect();
+
+        let transition = |from, to| ModeTransition { from, to };
+        assert_eq!(
+            vec![
+                transition(ThrottleMode::Undefined, ThrottleMode::Cruise),
+                transition(ThrottleMode::Cruise, ThrottleMode::Climb),
+                transition(ThrottleMode::Climb, ThrottleMode::Takeoff),
+                transition(ThrottleMode::Takeoff, ThrottleMode::Climb),
+            ],
+            transitions
+        );
+    }
+
+    #[test]
+    fn cruise_lever_is_passed_through_to_visual_throttle() {
+        let mut systems = AircraftSystems::default();
+        let axis = ThrottleAxis::from_raw(0.);
+        systems
+            .aircraft_mut()
+            .engines
+            .update(|_, e| e.physical_throttle = axis);
+
+        let outputs = systems.update(&environment(1_000.), Time::new::<second>(1. / 30.));
+
+        assert_eq!(EngineData::new(ThrottleMode::Cruise), outputs.mode);
+        assert_eq!(
+            EngineData::new(ThrottlePercent::from(axis)),
+            outputs.visual_throttle
+        );
+    }
+
+    #[test]
+    fn centered_lever_holds_prior_commanded_thrust() {
+        let delta_t = Time::new::<second>(1. / 30.);
+        let set_lever = |systems: &mut AircraftSystems, axis: ThrottleAxis| {
+            systems
+                .aircraft_mut()
+                .engines
+                .update(|_, e| e.physical_throttle = axis);
+        };
+
+        let mut systems = AircraftSystems::default();
+        systems.set_center_detent(Some(Ratio::new::<ratio>(0.02)));
+        set_lever(&mut systems, ThrottleAxis::CLIMB);
+        let mut prior = SystemOutputs::default();
+        for _ in 0..30 {
+            prior = systems.update(&environment(1_000.), delta_t);
+        }
+        let prior_thrust = systems.aircraft().engines.map(|_, e| e.commanded_thrust);
+
+        set_lever(&mut systems, ThrottleAxis::CENTER);
+        for _ in 0..30 {
+            let outputs = systems.update(&environment(1_000.), delta_t);
+            assert_eq!(prior.engine_throttle, outputs.engine_throttle);
+            assert_eq!(EngineData::new(ThrottleMode::Climb), outputs.mode);
+            assert_eq!(EngineData::default(), outputs.mode_transitions);
+        }
+        assert_eq!(
+            prior_thrust,
+            systems.aircraft().engines.map(|_, e| e.commanded_thrust)
+        );
+
+        systems.set_center_detent(None);
+        let outputs = systems.update(&environment(1_000.), delta_t);
+        assert_eq!(EngineData::new(ThrottleMode::Cruise), outputs.mode);
+        assert_ne!(prior.engine_throttle, outputs.engine_throttle);
+    }
+}
@@ -0,0 +1,246 @@
+//! Replays a scripted takeoff, climb, and cruise through the aircraft systems
+//!
+//! The engines are modeled as a first-order lag toward the thrust that the
+//! FADEC's own thrust model predicts for the commanded throttle, so the climb
+//! PID has a plant to close the loop around.
+
+use avmath::airspeed::MachNumber;
+use avmath::isa::{GeometricAltitude, PressureAltitude};
+use uom::si::{
+    acceleration::foot_per_second_squared,
+    f64::{Acceleration, Force, MassDensity, Ratio, Time, Velocity, Volume},
+    force::poundal,
+    length::foot,
+    mass_density::slug_per_cubic_foot,
+    ratio::ratio,
+    time::second,
+    velocity::{foot_per_minute, knot},
+    volume::cubic_foot,
+};
+use wt_cj4::calibration::ThrustModelCalibration;
+use wt_cj4::control_params::{ThrottleAxis, ThrottleMode};
+use wt_cj4::engines::{EngineData, EngineNumber};
+use wt_cj4::{Aircraft, AircraftSystems, EngineReadings, Environment, Instruments};
+
+const PROFILE: &str = include_str!("data/takeoff_climb_cruise.txt");
+
+/// The time taken for the engine thrust to cover most of a change in the
+/// commanded thrust, in seconds
+const ENGINE_TIME_CONSTANT: f64 = 1.5;
+
+/// A single line of the scripted profile
+#[derive(Clone, Copy, Debug)]
+struct Keyframe {
+    time: f64,
+    lever: f64,
+    pressure_altitude: f64,
+    density: f64,
+    mach: f64,
+    airspeed_indicated: f64,
+    on_ground: bool,
+}
+
+fn parse_profile(profile: &str) -> Vec<Keyframe> {
+    profile
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let fields: Vec<f64> = line
+                .split_whitespace()
+                .map(|field| field.parse().unwrap())
+                .collect();
+            assert_eq!(7, fields.len(), "malformed keyframe: {}", line);
+            Keyframe {
+                time: fields[0],
+                lever: fields[1],
+                pressure_altitude: fields[2],
+                density: fields[3],
+                mach: fields[4],
+                airspeed_indicated: fields[5],
+                on_ground: fields[6] != 0.,
+            }
+        })
+        .collect()
+}
+
+/// The scripted readings at `time`, or `None` past the end of the profile
+fn keyframe_at(profile: &[Keyframe], time: f64) -> Option<Keyframe> {
+    let next = profile.iter().position(|k| k.time > time)?;
+    let prior = profile[next.checked_sub(1)?];
+    let next = profile[next];
+    let t = (time - prior.time) / (next.time - prior.time);
+    let lerp = |a: f64, b: f64| a + (b - a) * t;
+    Some(Keyframe {
+        time,
+        pressure_altitude: lerp(prior.pressure_altitude, next.pressure_altitude),
+        density: lerp(prior.density, next.density),
+        mach: lerp(prior.mach, next.mach),
+        airspeed_indicated: lerp(prior.airspeed_indicated, next.airspeed_indicated),
+        ..prior
+    })
+}
+
+fn instruments(keyframe: &Keyframe) -> Instruments {
+    Instruments {
+        mach_number: MachNumber::new::<ratio>(keyframe.mach),
+        ambient_density: MassDensity::new::<slug_per_cubic_foot>(keyframe.density),
+        geometric_altitude: GeometricAltitude::new::<foot>(keyframe.pressure_altitude),
+        pressure_altitude: PressureAltitude::new::<foot>(keyframe.pressure_altitude),
+        airspeed_indicated: Velocity::new::<knot>(keyframe.airspeed_indicated),
+        airspeed_true: Velocity::new::<knot>(keyframe.airspeed_indicated),
+        vertical_speed: Velocity::new::<foot_per_minute>(0.),
+        on_ground: keyframe.on_ground,
+    }
+}
+
+/// The ratio of gross to net thrust due to ram pressure
+fn ram_factor(keyframe: &Keyframe) -> f64 {
+    (1. + keyframe.mach.powi(2) / 5.).powf(3.5)
+}
+
+/// The net thrust that the engine settles at for a commanded throttle
+fn settled_thrust(model: &ThrustModelCalibration, throttle: Ratio, keyframe: &Keyframe) -> Force {
+    let gross = model.predict_gross_thrust(
+        throttle,
+        MassDensity::new::<slug_per_cubic_foot>(keyframe.density),
+    );
+    gross / ram_factor(keyframe)
+}
+
+/// The state of the aircraft recorded after a single frame
+#[derive(Clone, Copy, Debug)]
+struct Frame {
+    keyframe: Keyframe,
+    mode: ThrottleMode,
+    throttle: f64,
+    thrust_target: f64,
+    gross_thrust: f64,
+}
+
+fn fly_profile() -> (Vec<Frame>, Vec<ThrottleMode>) {
+    let profile = parse_profile(PROFILE);
+    let model = ThrustModelCalibration {
+        density_factor: Volume::new::<cubic_foot>(42_009.034_569_669_5)
+            * Acceleration::new::<foot_per_second_squared>(1.),
+        thrust_efficiency: Ratio::new::<ratio>(1.),
+    };
+    let delta_t = 1. / 30.;
+
+    let mut systems = AircraftSystems::new(Aircraft::new_with_lever(ThrottleAxis::from_raw(
+        profile[0].lever,
+    )));
+    let mut thrust = Force::new::<poundal>(0.);
+    let mut frames = Vec::new();
+    let mut transitions = Vec::new();
+
+    let mut step = 0;
+    while let Some(keyframe) = keyframe_at(&profile, f64::from(step) * delta_t) {
+        step += 1;
+        systems
+            .aircraft_mut()
+            .engines
+            .update(|_, e| e.physical_throttle = ThrottleAxis::from_raw(keyframe.lever));
+
+        let environment = Environment {
+            instruments: instruments(&keyframe),
+            engines: EngineData::new(EngineReadings {
+                thrust,
+                fuel_flow: Default::default(),
+            }),
+        };
+        let outputs = systems.update(&environment, Time::new::<second>(delta_t));
+
+        if let Some(transition) = outputs.mode_transitions[EngineNumber::Engine1] {
+            transitions.push(transition.to);
+        }
+        assert_eq!(
+            outputs.engine_throttle[EngineNumber::Engine1],
+            outputs.engine_throttle[EngineNumber::Engine2],
+            "engines diverged at {:.3} s",
+            keyframe.time
+        );
+
+        let engine = &systems.aircraft().engines[EngineNumber::Engine1];
+        frames.push(Frame {
+            keyframe,
+            mode: engine.mode,
+            throttle: engine.engine_throttle.into(),
+            thrust_target: engine.commanded_thrust.to_force().get::<poundal>(),
+            gross_thrust: thrust.get::<poundal>() * ram_factor(&keyframe),
+        });
+
+        let settled = settled_thrust(&model, engine.engine_throttle.to_ratio(), &keyframe);
+        thrust += (settled - thrust) * (1. - (-delta_t / ENGINE_TIME_CONSTANT).exp());
+    }
+
+    (frames, transitions)
+}
+
+#[test]
+fn profile_is_flown_without_invalid_commands() {
+    let (frames, _) = fly_profile();
+
+    assert!(frames.len() > 1_500 * 30 - 2);
+    for frame in &frames {
+        assert!(
+            frame.throttle.is_finite() && frame.thrust_target.is_finite(),
+            "{:?}",
+            frame
+        );
+        assert!((0. ..=100.).contains(&frame.throttle), "{:?}", frame);
+    }
+}
+
+#[test]
+fn modes_transition_in_order() {
+    let (_, transitions) = fly_profile();
+
+    assert_eq!(
+        vec![
+            ThrottleMode::Takeoff,
+            ThrottleMode::Climb,
+            ThrottleMode::Cruise
+        ],
+        transitions
+    );
+}
+
+#[test]
+fn climb_thrust_target_falls_with_altitude() {
+    let (frames, _) = fly_profile();
+    let climb: Vec<&Frame> = frames
+        .iter()
+        .filter(|f| f.mode == ThrottleMode::Climb)
+        .collect();
+
+    assert!(!climb.is_empty());
+    for pair in climb.windows(2) {
+        if pair[1].keyframe.pressure_altitude > pair[0].keyframe.pressure_altitude {
+            assert!(
+                pair[1].thrust_target <= pair[0].thrust_target + 1e-9,
+                "{:?} then {:?}",
+                pair[0],
+                pair[1]
+            );
+        }
+    }
+
+    let first = climb.first().unwrap();
+    let last = climb.last().unwrap();
+    assert!(last.thrust_target < first.thrust_target * 0.75);
+}
+
+#[test]
+fn climb_thrust_tracks_the_target() {
+    let (frames, _) = fly_profile();
+
+    // Allow the engine and PID to settle after leaving the takeoff detent
+    for frame in frames
+        .iter()
+        .filter(|f| f.mode == ThrottleMode::Climb && f.keyframe.time > 120.)
+    {
+        let error = (frame.thrust_target - frame.gross_thrust) / frame.thrust_target;
+        assert!(error.abs() < 0.05, "{:?}", frame);
+    }
+}
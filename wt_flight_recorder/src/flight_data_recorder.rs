@@ -2,6 +2,7 @@ use flate2::write::GzEncoder;
 use std::{fmt, fs, marker::PhantomData};
 
 const MAX_EVENTS_PER_FILE: u32 = 20 * 60 * 30;
+const DEFAULT_DIRECTORY: &str = r#"\work\"#;
 
 /// A flight data recorder for aircraft data
 ///
@@ -39,10 +40,12 @@ const MAX_EVENTS_PER_FILE: u32 = 20 * 60 * 30;
 ///     eprintln!("Unable to log event: {}", e);
 /// }
 /// ```
-
 pub struct FlightDataRecorder<T> {
     events: u32,
     file_num: u32,
+    directory: String,
+    stream: Option<String>,
+    session: u32,
     prefix: String,
     writer: GzEncoder<fs::File>,
     _phantom: PhantomData<T>,
@@ -53,6 +56,9 @@ impl<T> fmt::Debug for FlightDataRecorder<T> {
         f.debug_struct("FlightDataRecorder")
             .field("events", &self.events)
             .field("file", &self.file_num)
+            .field("directory", &self.directory)
+            .field("stream", &self.stream)
+            .field("session", &self.session)
             .field("prefix", &self.prefix)
             .field("writer", &"<boxed>")
             .finish()
@@ -62,23 +68,88 @@ impl<T> fmt::Debug for FlightDataRecorder<T> {
 impl<T> FlightDataRecorder<T> {
     /// Constructs a new flight data recorder instance
     pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        let prefix = format!("{}", chrono::Utc::now().format("%Y-%m-%dT%H-%M-%SZ"));
+        Self::new_in(DEFAULT_DIRECTORY)
+    }
+
+    /// Constructs a new flight data recorder instance that writes files into
+    /// `directory`
+    ///
+    /// The directory is used as a literal prefix to the filename, and so
+    /// should end with a path separator.
+    pub fn new_in(directory: impl Into<String>) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::open(directory.into(), None)
+    }
+
+    /// Constructs a new flight data recorder instance for a side channel
+    /// named `stream`
+    ///
+    /// The stream name is added after the prefix in each filename, so that
+    /// side channel files sort alongside the primary recording, as in
+    /// `%Y-%m-%dT%H-%M-%SZ_axis_##.msgpack.gz`.
+    pub fn new_stream(stream: impl Into<String>) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::new_stream_in(DEFAULT_DIRECTORY, stream)
+    }
+
+    /// Constructs a new flight data recorder instance for a side channel
+    /// named `stream` that writes files into `directory`
+    pub fn new_stream_in(
+        directory: impl Into<String>,
+        stream: impl Into<String>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::open(directory.into(), Some(stream.into()))
+    }
+
+    fn open(directory: String, stream: Option<String>) -> Result<Self, Box<dyn std::error::Error>> {
+        let prefix = session_prefix(None, 1);
         println!("Logging using the {} prefix", prefix);
         let mut file_num = 0;
-        let writer = open_file(&prefix, &mut file_num)?;
+        let writer = open_file(&directory, &file_stem(&prefix, &stream), &mut file_num)?;
         Ok(FlightDataRecorder {
             events: 0,
             file_num,
+            directory,
+            stream,
+            session: 1,
             prefix,
             writer,
             _phantom: PhantomData,
         })
     }
 
+    /// The prefix shared by all files in the current session
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Closes the files for the current session and begins a new session
+    /// with a fresh prefix
+    ///
+    /// Useful when the simulation restarts, such as when a flight is
+    /// reloaded, so that recordings from different flights are kept apart.
+    pub fn start_new_session(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.writer.try_finish()?;
+
+        let session = self.session + 1;
+        let prefix = session_prefix(Some(&self.prefix), session);
+        println!("Starting a new session using the {} prefix", prefix);
+        let mut file_num = 0;
+        self.writer = open_file(
+            &self.directory,
+            &file_stem(&prefix, &self.stream),
+            &mut file_num,
+        )?;
+        self.file_num = file_num;
+        self.session = session;
+        self.prefix = prefix;
+        self.events = 0;
+        Ok(())
+    }
+
     fn manage_files(&mut self) {
         if self.events >= MAX_EVENTS_PER_FILE {
             println!("Recorded {} events; rotating...", self.events);
-            match open_file(&self.prefix, &mut self.file_num) {
+            let stem = file_stem(&self.prefix, &self.stream);
+            match open_file(&self.directory, &stem, &mut self.file_num) {
                 Ok(w) => self.writer = w,
                 Err(err) => println!(
                     "Error opening next file for logging; will try again later: {}",
@@ -105,12 +176,33 @@ where
     }
 }
 
+/// Builds the file prefix for a session from the current time
+///
+/// When the prior session started within the same second, the session number
+/// is appended so that the prefixes remain distinct.
+fn session_prefix(prior: Option<&str>, session: u32) -> String {
+    let prefix = format!("{}", chrono::Utc::now().format("%Y-%m-%dT%H-%M-%SZ"));
+    match prior {
+        Some(prior) if prior.starts_with(&prefix) => format!("{}-{}", prefix, session),
+        _ => prefix,
+    }
+}
+
+/// Builds the part of the filename preceding the file number
+fn file_stem(prefix: &str, stream: &Option<String>) -> String {
+    match stream {
+        Some(stream) => format!("{}_{}", prefix, stream),
+        None => prefix.to_owned(),
+    }
+}
+
 fn open_file(
-    prefix: &str,
+    directory: &str,
+    stem: &str,
     file_num: &mut u32,
 ) -> Result<GzEncoder<fs::File>, Box<dyn std::error::Error>> {
     let next = *file_num + 1;
-    let filename = format!(r#"\work\{}_{:02}.msgpack.gz"#, prefix, next);
+    let filename = format!("{}{}_{:02}.msgpack.gz", directory, stem, next);
     println!("Opening {} for logging", &filename[..filename.len() - 2]);
     let file = std::fs::File::create(&filename)?;
     println!("Opened {} for logging", &filename[..filename.len() - 2]);
@@ -120,3 +212,75 @@ fn open_file(
         flate2::Compression::best(),
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_directory() -> std::path::PathBuf {
+        let directory = std::env::temp_dir().join(format!(
+            "wt_flight_recorder_{}_{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn new_session_uses_a_distinct_prefix() {
+        let directory = scratch_directory();
+
+        let mut recorder: FlightDataRecorder<u32> =
+            FlightDataRecorder::new_in(format!("{}/", directory.display())).unwrap();
+        recorder.publish(&1).unwrap();
+        let first = recorder.prefix().to_owned();
+
+        recorder.start_new_session().unwrap();
+        recorder.publish(&2).unwrap();
+        let second = recorder.prefix().to_owned();
+        drop(recorder);
+
+        assert_ne!(first, second);
+        assert!(directory.join(format!("{}_01.msgpack.gz", first)).is_file());
+        assert!(directory
+            .join(format!("{}_01.msgpack.gz", second))
+            .is_file());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn stream_records_events_verbatim_alongside_primary() {
+        let directory = scratch_directory();
+        let events = [
+            (1_u32, 4_u32, 0_u32),
+            (1, 6, 16_384),
+            (1, 6, u32::MAX),
+            (2, 3, 7),
+        ];
+
+        let mut recorder: FlightDataRecorder<(u32, u32, u32)> =
+            FlightDataRecorder::new_stream_in(format!("{}/", directory.display()), "axis").unwrap();
+        for event in &events {
+            recorder.publish(event).unwrap();
+        }
+        let prefix = recorder.prefix().to_owned();
+        drop(recorder);
+
+        let file =
+            fs::File::open(directory.join(format!("{}_axis_01.msgpack.gz", prefix))).unwrap();
+        let mut decoder = flate2::read::GzDecoder::new(file);
+        let recorded: Vec<(u32, u32, u32)> = (0..events.len())
+            .map(|_| rmp_serde::from_read(&mut decoder).unwrap())
+            .collect();
+
+        assert_eq!(events.to_vec(), recorded);
+        assert!(rmp_serde::from_read::<_, (u32, u32, u32)>(&mut decoder).is_err());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,256 @@
+//! Differences between two recordings of the same flight
+//!
+//! Recordings made with different builds of the FADEC rarely sample at exactly
+//! the same simulation times, so each record of the first recording is paired
+//! with the record of the second recording nearest to it in simulation time.
+//! The differences are reported as the second recording less the first.
+
+use uom::si::{force::poundal, ratio::ratio, time::second};
+use wt_cj4::engines::EngineNumber;
+use wt_cj4::Snapshot;
+
+/// The values of a single engine that are compared between recordings
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EngineSample {
+    /// The throttle commanded to the engine, in percent
+    pub throttle: f64,
+    /// The thrust produced by the engine, in poundals
+    pub thrust: f64,
+    /// The output of the FADEC PID controller, as a ratio
+    pub pid_output: f64,
+}
+
+impl EngineSample {
+    fn difference(&self, other: &Self) -> Self {
+        Self {
+            throttle: other.throttle - self.throttle,
+            thrust: other.thrust - self.thrust,
+            pid_output: other.pid_output - self.pid_output,
+        }
+    }
+}
+
+/// The values of a single record that are compared between recordings
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Sample {
+    /// The simulation time of the record, in seconds
+    pub sim_time: f64,
+    /// The compared values for each engine
+    pub engines: Vec<EngineSample>,
+}
+
+impl Sample {
+    /// Extracts the compared values from a snapshot
+    pub fn from_snapshot(snapshot: &Snapshot) -> Self {
+        let engines = EngineNumber::iter()
+            .into_iter()
+            .map(|n| EngineSample {
+                throttle: snapshot.aircraft.engines[n].engine_throttle.into(),
+                thrust: snapshot.environment.engines[n].thrust.get::<poundal>(),
+                pid_output: snapshot.aircraft.engines[n]
+                    .fadec
+                    .last_pid_outputs()
+                    .output()
+                    .get::<ratio>(),
+            })
+            .collect();
+
+        Self {
+            sim_time: snapshot.sim_time.get::<second>(),
+            engines,
+        }
+    }
+}
+
+/// Reads every snapshot from a single decompressed file into `samples`
+///
+/// Reading stops cleanly at the end of the file. A truncated or corrupt
+/// record is returned as an error, with the samples before it kept.
+pub fn read_file<R: std::io::Read>(
+    samples: &mut Vec<Sample>,
+    reader: R,
+) -> Result<(), rmp_serde::decode::Error> {
+    let mut input = rmp_serde::Deserializer::new(reader);
+    loop {
+        match serde::de::Deserialize::deserialize(&mut input) {
+            Ok(snapshot) => samples.push(Sample::from_snapshot(&snapshot)),
+            Err(rmp_serde::decode::Error::InvalidMarkerRead(err))
+                if err.kind() == std::io::ErrorKind::UnexpectedEof =>
+            {
+                return Ok(())
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// A record of the first recording paired with its nearest counterpart
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlignedDifference {
+    /// The simulation time of the record in the first recording, in seconds
+    pub sim_time: f64,
+    /// The simulation time of the paired record in the second recording less
+    /// that of the first, in seconds
+    pub sim_time_offset: f64,
+    /// The differences for each engine present in both records
+    pub engines: Vec<EngineSample>,
+}
+
+/// Pairs each sample of `a` with the nearest sample of `b` in simulation time
+///
+/// Both recordings are expected to be ordered by simulation time. Returns no
+/// differences if `b` is empty.
+pub fn align(a: &[Sample], b: &[Sample]) -> Vec<AlignedDifference> {
+    a.iter()
+        .filter_map(|sample| {
+            let nearest = nearest(b, sample.sim_time)?;
+            Some(AlignedDifference {
+                sim_time: sample.sim_time,
+                sim_time_offset: nearest.sim_time - sample.sim_time,
+                engines: sample
+                    .engines
+                    .iter()
+                    .zip(&nearest.engines)
+                    .map(|(a, b)| a.difference(b))
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// The sample nearest in simulation time, preferring the earlier on a tie
+fn nearest(samples: &[Sample], sim_time: f64) -> Option<&Sample> {
+    let after = samples.partition_point(|s| s.sim_time < sim_time);
+    let before = after.checked_sub(1).and_then(|i| samples.get(i));
+    match (before, samples.get(after)) {
+        (Some(b), Some(a)) if a.sim_time - sim_time < sim_time - b.sim_time => Some(a),
+        (Some(b), _) => Some(b),
+        (None, a) => a,
+    }
+}
+
+/// Writes the aligned differences as CSV, one row per record of the first
+/// recording
+pub fn write_csv<W: std::io::Write>(
+    writer: W,
+    differences: &[AlignedDifference],
+) -> csv::Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+
+    let engine_count = differences.first().map_or(0, |d| d.engines.len());
+    let mut headers = vec![
+        "simulation_time".to_owned(),
+        "simulation_time_offset".to_owned(),
+    ];
+    for n in 1..=engine_count {
+        headers.push(format!("engine{}_engine_throttle_diff", n));
+        headers.push(format!("engine{}_thrust_pdl_diff", n));
+        headers.push(format!("engine{}_pid_output_diff", n));
+    }
+    writer.write_record(&headers)?;
+
+    for difference in differences {
+        let mut record = vec![
+            difference.sim_time.to_string(),
+            difference.sim_time_offset.to_string(),
+        ];
+        for engine in &difference.engines {
+            record.push(engine.throttle.to_string());
+            record.push(engine.thrust.to_string());
+            record.push(engine.pid_output.to_string());
+        }
+        writer.write_record(&record)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::snapshot;
+    use uom::si::f64::{Force, Time};
+    use wt_cj4::control_params::ThrottlePercent;
+
+    fn recording(sim_times: &[f64], throttle: f64, thrust: f64) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        for &sim_time in sim_times {
+            let mut snapshot = Snapshot {
+                sim_time: Time::new::<second>(sim_time),
+                ..snapshot()
+            };
+            snapshot
+                .aircraft
+                .engines
+                .update(|_, e| e.engine_throttle = ThrottlePercent::from(throttle + sim_time));
+            snapshot
+                .environment
+                .engines
+                .update(|_, e| e.thrust = Force::new::<poundal>(thrust + sim_time));
+            rmp_serde::encode::write_named(&mut encoded, &snapshot).unwrap();
+        }
+        encoded
+    }
+
+    fn samples(encoded: &[u8]) -> Vec<Sample> {
+        let mut samples = Vec::new();
+        read_file(&mut samples, encoded).unwrap();
+        samples
+    }
+
+    #[test]
+    fn constant_offset_is_reported_across_jittered_sample_times() {
+        let a = samples(&recording(&[1., 2., 3., 4.], 50., 1_000.));
+        let b = samples(&recording(&[0.99, 2.02, 2.98, 4.01, 5.], 52.5, 1_100.));
+
+        let differences = align(&a, &b);
+
+        assert_eq!(4, differences.len());
+        for difference in &differences {
+            assert!(difference.sim_time_offset.abs() <= 0.02 + 1e-9);
+            assert_eq!(2, difference.engines.len());
+            for engine in &difference.engines {
+                let drift = difference.sim_time_offset;
+                assert!((engine.throttle - (2.5 + drift)).abs() < 1e-9);
+                assert!((engine.thrust - (100. + drift)).abs() < 1e-9);
+                assert_eq!(0., engine.pid_output);
+            }
+        }
+    }
+
+    #[test]
+    fn nearest_prefers_closer_and_then_earlier_samples() {
+        let b: Vec<Sample> = [1., 2., 4.]
+            .iter()
+            .map(|&sim_time| Sample {
+                sim_time,
+                engines: Vec::new(),
+            })
+            .collect();
+
+        assert_eq!(Some(1.), nearest(&b, 0.).map(|s| s.sim_time));
+        assert_eq!(Some(2.), nearest(&b, 2.9).map(|s| s.sim_time));
+        assert_eq!(Some(2.), nearest(&b, 3.).map(|s| s.sim_time));
+        assert_eq!(Some(4.), nearest(&b, 3.1).map(|s| s.sim_time));
+        assert_eq!(Some(4.), nearest(&b, 9.).map(|s| s.sim_time));
+        assert_eq!(None, nearest(&[], 1.).map(|s| s.sim_time));
+    }
+
+    #[test]
+    fn csv_has_a_column_per_engine_value() {
+        let a = samples(&recording(&[1.], 50., 1_000.));
+        let b = samples(&recording(&[1.], 60., 1_200.));
+
+        let mut output = Vec::new();
+        write_csv(&mut output, &align(&a, &b)).unwrap();
+
+        assert_eq!(
+            "simulation_time,simulation_time_offset,\
+             engine1_engine_throttle_diff,engine1_thrust_pdl_diff,engine1_pid_output_diff,\
+             engine2_engine_throttle_diff,engine2_thrust_pdl_diff,engine2_pid_output_diff\n\
+             1,0,10,200,0,10,200,0\n",
+            String::from_utf8(output).unwrap()
+        );
+    }
+}
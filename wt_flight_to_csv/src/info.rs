@@ -0,0 +1,138 @@
+//! Summaries of recordings for triage without converting them
+//!
+//! Every record is decoded, but nothing is written, so a directory of
+//! recordings can be surveyed quickly.
+
+use std::fmt;
+use uom::si::{f64::Time, time::second};
+use wt_cj4::Snapshot;
+
+/// A summary of the snapshots in a recording
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RecordingInfo {
+    /// The number of files read, including split continuations
+    pub files: u32,
+
+    /// The number of snapshots read
+    pub records: u64,
+
+    /// The distinct schema versions seen, in the order first encountered
+    pub schema_versions: Vec<u32>,
+
+    /// The simulation time of the first snapshot
+    pub first_sim_time: Option<Time>,
+
+    /// The simulation time of the last snapshot
+    pub last_sim_time: Option<Time>,
+}
+
+impl RecordingInfo {
+    /// Adds a snapshot to the summary
+    pub fn observe(&mut self, snapshot: &Snapshot) {
+        self.records += 1;
+        if !self.schema_versions.contains(&snapshot.schema_version) {
+            self.schema_versions.push(snapshot.schema_version);
+        }
+        self.first_sim_time.get_or_insert(snapshot.sim_time);
+        self.last_sim_time = Some(snapshot.sim_time);
+    }
+
+    /// Reads every snapshot from a single decompressed file
+    ///
+    /// Reading stops cleanly at the end of the file. A truncated or corrupt
+    /// record is returned as an error, with the snapshots before it counted.
+    pub fn read_file<R: std::io::Read>(
+        &mut self,
+        reader: R,
+    ) -> Result<(), rmp_serde::decode::Error> {
+        self.files += 1;
+        let mut input = rmp_serde::Deserializer::new(reader);
+        loop {
+            match serde::de::Deserialize::deserialize(&mut input) {
+                Ok(snapshot) => self.observe(&snapshot),
+                Err(rmp_serde::decode::Error::InvalidMarkerRead(err))
+                    if err.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    return Ok(())
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// The simulation time elapsed between the first and last snapshots
+    pub fn span(&self) -> Option<Time> {
+        Some(self.last_sim_time? - self.first_sim_time?)
+    }
+}
+
+impl fmt::Display for RecordingInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let versions: Vec<String> = self.schema_versions.iter().map(|v| v.to_string()).collect();
+        writeln!(f, "Files: {}", self.files)?;
+        writeln!(f, "Schema versions: {}", versions.join(", "))?;
+        writeln!(f, "Records: {}", self.records)?;
+        match (self.first_sim_time, self.last_sim_time, self.span()) {
+            (Some(first), Some(last), Some(span)) => write!(
+                f,
+                "Time span: {:.3} s ({:.3} s to {:.3} s)",
+                span.get::<second>(),
+                first.get::<second>(),
+                last.get::<second>()
+            ),
+            _ => write!(f, "Time span: none"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::snapshot;
+
+    fn recording(sim_times: &[f64]) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        for &sim_time in sim_times {
+            let snapshot = Snapshot {
+                sim_time: Time::new::<second>(sim_time),
+                ..snapshot()
+            };
+            rmp_serde::encode::write_named(&mut encoded, &snapshot).unwrap();
+        }
+        encoded
+    }
+
+    #[test]
+    fn info_reports_count_and_span_across_splits() {
+        let mut info = RecordingInfo::default();
+        info.read_file(recording(&[10., 10.5, 11.]).as_slice())
+            .unwrap();
+        info.read_file(recording(&[11.5, 12.25]).as_slice())
+            .unwrap();
+
+        assert_eq!(2, info.files);
+        assert_eq!(5, info.records);
+        assert_eq!(vec![wt_cj4::SNAPSHOT_SCHEMA_VERSION], info.schema_versions);
+        assert_eq!(Some(Time::new::<second>(2.25)), info.span());
+    }
+
+    #[test]
+    fn empty_recording_has_no_span() {
+        let mut info = RecordingInfo::default();
+        info.read_file(&[][..]).unwrap();
+
+        assert_eq!(0, info.records);
+        assert_eq!(None, info.span());
+        assert!(info.to_string().ends_with("Time span: none"));
+    }
+
+    #[test]
+    fn truncated_record_is_reported() {
+        let mut encoded = recording(&[1., 2.]);
+        encoded.truncate(encoded.len() - 4);
+
+        let mut info = RecordingInfo::default();
+        assert!(info.read_file(encoded.as_slice()).is_err());
+        assert_eq!(1, info.records);
+    }
+}
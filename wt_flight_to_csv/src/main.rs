@@ -11,14 +11,56 @@
 //! ```sh
 //! wt_flight_to_csv 2021-01-05T11-43-44_01.msgpack.gz 2021-01-05T11-43-44_01.csv
 //! ```
+//!
+//! A converted file can be validated against an expected file. Numeric fields
+//! may differ by up to a per-column tolerance, which defaults to zero:
+//!
+//! ```sh
+//! wt_flight_to_csv validate <expected.csv> <actual.csv> [<column>=<epsilon>...]
+//! ```
+//!
+//! A recording can be summarized without converting it. The record count and
+//! time span include any split continuations of the file:
+//!
+//! ```sh
+//! wt_flight_to_csv info 2021-01-05T11-43-44_01.msgpack.gz
+//! ```
+//!
+//! Two recordings of the same flight can be compared. Each record of the first
+//! recording is paired with the record of the second nearest in simulation
+//! time, and the differences in throttle, thrust, and PID output are written
+//! as CSV to the output file, or to standard output if none is given:
+//!
+//! ```sh
+//! wt_flight_to_csv diff <a.msgpack.gz> <b.msgpack.gz> [<output.csv>]
+//! ```
+//!
+//! A recording made with an older schema can be rewritten to the current
+//! schema, reconstructing the fields that newer recordings carry wherever the
+//! older record holds what they were derived from. Split continuations of the
+//! input are rewritten into the single output file:
+//!
+//! ```sh
+//! wt_flight_to_csv migrate <input.msgpack.gz> <output.msgpack.gz>
+//! ```
 
+use std::fmt::Write;
 use wt_cj4::control_params::{ThrottleAxis, ThrottleMode, ThrottlePercent};
 use wt_cj4::engines::EngineNumber;
+use wt_cj4::ThrustLimitSource;
+
+mod diff;
+mod info;
+mod migrate;
+mod validate;
 
+/// The columns of a CSV row shared by all engines
 #[derive(serde::Serialize)]
 struct FlatSnapshot {
+    schema_version: u32,
     simulation_time: f64,
     delta_t: f64,
+    marker: Option<u32>,
     airspeed_indicated: f64,
     airspeed_true: f64,
     vertical_speed: f64,
@@ -26,32 +68,121 @@ struct FlatSnapshot {
     ambient_density: f64,
     geometric_altitude: f64,
     pressure_altitude: f64,
-    engine1_thrust: f64,
-    engine1_fadec_mode: ThrottleMode,
-    engine1_physical_throttle: ThrottleAxis,
-    engine1_engine_throttle: ThrottlePercent,
-    engine1_visual_throttle: ThrottlePercent,
-    engine1_pid_config: String,
-    engine1_pid_last_error: f64,
-    engine1_pid_retained_error: f64,
-    engine1_pid_proportional: f64,
-    engine1_pid_integral: f64,
-    engine1_pid_derivative: f64,
-    engine1_pid_output: f64,
-    engine1_fadec_enabled: bool,
-    engine2_thrust: f64,
-    engine2_fadec_mode: ThrottleMode,
-    engine2_physical_throttle: ThrottleAxis,
-    engine2_engine_throttle: ThrottlePercent,
-    engine2_visual_throttle: ThrottlePercent,
-    engine2_pid_config: String,
-    engine2_pid_last_error: f64,
-    engine2_pid_retained_error: f64,
-    engine2_pid_proportional: f64,
-    engine2_pid_integral: f64,
-    engine2_pid_derivative: f64,
-    engine2_pid_output: f64,
-    engine2_fadec_enabled: bool,
+    on_ground: bool,
+    #[serde(rename = "specific_air_range_nm_per_lb")]
+    specific_air_range: Option<f64>,
+}
+
+/// The columns of a CSV row for a single engine
+///
+/// Each column is prefixed with `engine<n>_` in the header, where `n` counts
+/// from 1.
+#[derive(serde::Serialize)]
+struct FlatEngine {
+    #[serde(rename = "thrust_pdl")]
+    thrust: f64,
+    #[serde(rename = "fuel_flow_pph")]
+    fuel_flow: f64,
+    fadec_mode: ThrottleMode,
+    physical_throttle: ThrottleAxis,
+    engine_throttle: ThrottlePercent,
+    visual_throttle: ThrottlePercent,
+    pid_config: String,
+    pid_last_error: f64,
+    pid_retained_error: f64,
+    pid_proportional: f64,
+    pid_integral: f64,
+    pid_derivative: f64,
+    pid_output: f64,
+    pid_saturation_duty_cycle: f64,
+    fadec_enabled: bool,
+    #[serde(rename = "thrust_shortfall_pdl")]
+    thrust_shortfall: f64,
+    thrust_lagging: bool,
+    overspeed_protection: bool,
+    turbine_temperature_limit: bool,
+    readings_valid: bool,
+    thrust_limit_source: ThrustLimitSource,
+}
+
+/// A CSV row, with the engine columns following the shared columns
+struct FlatRow {
+    snapshot: FlatSnapshot,
+    engines: Vec<FlatEngine>,
+}
+
+impl FlatRow {
+    /// The header naming each column of the row
+    fn headers(&self) -> csv::Result<Vec<String>> {
+        let mut headers = column_names(&self.snapshot)?;
+        if let Some(engine) = self.engines.first() {
+            let engine_columns = column_names(engine)?;
+            for n in 1..=self.engines.len() {
+                headers.extend(
+                    engine_columns
+                        .iter()
+                        .map(|column| format!("engine{}_{}", n, column)),
+                );
+            }
+        }
+        Ok(headers)
+    }
+}
+
+/// The names of the columns that `value` serializes to
+fn column_names<T: serde::Serialize>(value: &T) -> csv::Result<Vec<String>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.serialize(value)?;
+    let data = writer.into_inner().map_err(|err| err.into_error())?;
+    let mut reader = csv::Reader::from_reader(data.as_slice());
+    Ok(reader.headers()?.iter().map(String::from).collect())
+}
+
+/// Writes CSV rows, with a header sized to the engine count of the first row
+struct RowWriter<W: std::io::Write> {
+    writer: csv::Writer<W>,
+    engine_count: Option<usize>,
+}
+
+impl<W: std::io::Write> RowWriter<W> {
+    fn new(writer: W) -> Self {
+        Self {
+            writer: csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_writer(writer),
+            engine_count: None,
+        }
+    }
+
+    /// Writes a row, preceded by the header if it is the first row
+    ///
+    /// Rows with a different engine count than the first row are rejected.
+    fn write(&mut self, row: &FlatRow) -> csv::Result<()> {
+        match self.engine_count {
+            None => {
+                self.writer.write_record(row.headers()?)?;
+                self.engine_count = Some(row.engines.len());
+            }
+            Some(count) if count != row.engines.len() => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "row has {} engines, but the header has {}",
+                        row.engines.len(),
+                        count
+                    ),
+                )
+                .into())
+            }
+            Some(_) => {}
+        }
+        self.writer.serialize((&row.snapshot, &row.engines))
+    }
+
+    /// Flushes any buffered rows and returns the underlying writer
+    fn into_inner(self) -> W {
+        self.writer.into_inner().unwrap()
+    }
 }
 
 fn find_splits(path: &str) -> Option<(&str, u32)> {
@@ -67,10 +198,20 @@ type Input = rmp_serde::Deserializer<
     rmp_serde::config::DefaultConfig,
 >;
 
-fn open_next(multi: &mut (&str, u32)) -> Option<Input> {
-    multi.1 += 1;
-    let path = format!("{}_{:02}.msgpack.gz", multi.0, multi.1);
-    open(&path).ok()
+/// The paths of the files making up a recording, starting with `path`
+///
+/// A recording split across files continues with the splits numbered after
+/// `path`. The paths are not checked, so readers stop at the first split
+/// that cannot be opened.
+fn recording_paths(path: &str) -> impl Iterator<Item = String> + '_ {
+    let splits = find_splits(path).into_iter().flat_map(|(stem, sequence)| {
+        (sequence + 1..).map(move |n| format!("{}_{:02}.msgpack.gz", stem, n))
+    });
+    std::iter::once(path.to_owned()).chain(splits)
+}
+
+fn open_next(paths: &mut impl Iterator<Item = String>) -> Option<Input> {
+    open(&paths.next()?).ok()
 }
 
 fn open(path: &str) -> std::io::Result<Input> {
@@ -84,29 +225,205 @@ fn main() {
     let mut args = std::env::args();
     args.next();
     let ipath = args.next().unwrap();
-    let opath_maybe = args.next();
 
-    let mut multi = find_splits(&ipath);
+    if ipath == "validate" {
+        std::process::exit(run_validate(args));
+    }
+    if ipath == "info" {
+        std::process::exit(run_info(args));
+    }
+    if ipath == "diff" {
+        std::process::exit(run_diff(args));
+    }
+    if ipath == "migrate" {
+        std::process::exit(run_migrate(args));
+    }
+    let opath_maybe = args.next();
 
     let opath = opath_maybe
-        .or_else(|| multi.map(|m| format!("{}.csv", m.0)))
+        .or_else(|| find_splits(&ipath).map(|m| format!("{}.csv", m.0)))
         .unwrap();
 
     println!("Output: {}", opath);
 
+    let mut paths = recording_paths(&ipath).skip(1);
     let mut input = open(&ipath).unwrap();
     let o = std::fs::File::create(opath).unwrap();
 
-    let mut o = csv::WriterBuilder::new().has_headers(true).from_writer(o);
+    let mut o = RowWriter::new(o);
+    let mut row = RowBuffer::default();
 
     let mut records = 0;
     let mut files = 1;
+    let mut timing = wt_cj4::TimingReport::default();
 
-    while process_record(&mut multi, &mut input, &mut o, &mut files, true) == Loop::Continue {
+    while process_record(
+        &mut paths,
+        &mut input,
+        &mut o,
+        &mut row,
+        &mut files,
+        &mut timing,
+        true,
+    ) == Loop::Continue
+    {
         records += 1;
     }
 
+    o.into_inner();
     println!("Processed {} records across {} files", records, files);
+    report_timing(&timing);
+}
+
+/// Reports records whose simulation time does not advance, which can break
+/// downstream integration of the recording
+fn report_timing(timing: &wt_cj4::TimingReport) {
+    if !timing.is_monotonic() {
+        println!(
+            "Warning: {} records with non-positive delta_t and {} records where sim_time did not increase",
+            timing.non_positive_delta_t, timing.non_increasing_sim_time
+        );
+    }
+}
+
+fn run_info(mut args: impl Iterator<Item = String>) -> i32 {
+    let ipath = args.next().unwrap();
+    let mut info = info::RecordingInfo::default();
+
+    for path in recording_paths(&ipath) {
+        let file = match std::fs::File::open(&path) {
+            Ok(file) => file,
+            Err(err) if info.files == 0 => {
+                eprintln!("Error opening {}: {}", path, err);
+                return 2;
+            }
+            Err(_) => break,
+        };
+
+        println!("Reading {}", path);
+        if let Err(err) = info.read_file(flate2::read::GzDecoder::new(file)) {
+            eprintln!("Error deserializing {}: {}", path, err);
+            println!("{}", info);
+            return 1;
+        }
+    }
+
+    println!("{}", info);
+    0
+}
+
+/// Reads the compared values of every record in a recording, including any
+/// split continuations of the file
+fn read_diff_samples(ipath: &str) -> Result<Vec<diff::Sample>, i32> {
+    let mut samples = Vec::new();
+
+    for (files, path) in recording_paths(ipath).enumerate() {
+        let file = match std::fs::File::open(&path) {
+            Ok(file) => file,
+            Err(err) if files == 0 => {
+                eprintln!("Error opening {}: {}", path, err);
+                return Err(2);
+            }
+            Err(_) => break,
+        };
+
+        eprintln!("Reading {}", path);
+        if let Err(err) = diff::read_file(&mut samples, flate2::read::GzDecoder::new(file)) {
+            eprintln!("Error deserializing {}: {}", path, err);
+            return Err(1);
+        }
+    }
+
+    Ok(samples)
+}
+
+fn run_diff(mut args: impl Iterator<Item = String>) -> i32 {
+    let a_path = args.next().unwrap();
+    let b_path = args.next().unwrap();
+    let opath = args.next();
+
+    let (a, b) = match (read_diff_samples(&a_path), read_diff_samples(&b_path)) {
+        (Ok(a), Ok(b)) => (a, b),
+        (Err(code), _) | (_, Err(code)) => return code,
+    };
+
+    let differences = diff::align(&a, &b);
+    let result = match opath {
+        Some(opath) => diff::write_csv(std::fs::File::create(opath).unwrap(), &differences),
+        None => diff::write_csv(std::io::stdout(), &differences),
+    };
+    if let Err(err) = result {
+        eprintln!("Error writing differences: {}", err);
+        return 1;
+    }
+
+    eprintln!("Compared {} records against {}", a.len(), b.len());
+    0
+}
+
+fn run_migrate(mut args: impl Iterator<Item = String>) -> i32 {
+    let ipath = args.next().unwrap();
+    let opath = args.next().unwrap();
+    let mut migration = migrate::Migration::default();
+
+    let mut output = flate2::write::GzEncoder::new(
+        std::fs::File::create(&opath).unwrap(),
+        flate2::Compression::default(),
+    );
+    for (files, path) in recording_paths(&ipath).enumerate() {
+        let file = match std::fs::File::open(&path) {
+            Ok(file) => file,
+            Err(err) if files == 0 => {
+                eprintln!("Error opening {}: {}", path, err);
+                return 2;
+            }
+            Err(_) => break,
+        };
+
+        println!("Migrating {}", path);
+        if let Err(err) = migration.rewrite_file(flate2::read::GzDecoder::new(file), &mut output) {
+            eprintln!("{} in {}", err, path);
+            println!("{}", migration);
+            return 1;
+        }
+    }
+
+    if let Err(err) = output.finish() {
+        eprintln!("Error writing {}: {}", opath, err);
+        return 1;
+    }
+    println!("{}", migration);
+    0
+}
+
+fn run_validate(mut args: impl Iterator<Item = String>) -> i32 {
+    let expected_path = args.next().unwrap();
+    let actual_path = args.next().unwrap();
+
+    let tolerances = match args.try_fold(validate::ColumnTolerances::new(0.), |t, arg| {
+        t.parse_override(&arg)
+    }) {
+        Ok(tolerances) => tolerances,
+        Err(err) => {
+            eprintln!("{}", err);
+            return 2;
+        }
+    };
+
+    let mut expected = csv::Reader::from_path(expected_path).unwrap();
+    let mut actual = csv::Reader::from_path(actual_path).unwrap();
+
+    let mismatches = validate::compare(&mut expected, &mut actual, &tolerances).unwrap();
+    for mismatch in &mismatches {
+        println!("{}", mismatch);
+    }
+
+    println!("Found {} mismatches", mismatches.len());
+    if mismatches.is_empty() {
+        0
+    } else {
+        1
+    }
 }
 
 #[derive(PartialEq, Eq)]
@@ -116,10 +433,12 @@ enum Loop {
 }
 
 fn process_record(
-    multi: &mut Option<(&str, u32)>,
+    paths: &mut impl Iterator<Item = String>,
     input: &mut Input,
-    output: &mut csv::Writer<std::fs::File>,
+    output: &mut RowWriter<std::fs::File>,
+    row: &mut RowBuffer,
     files: &mut i32,
+    timing: &mut wt_cj4::TimingReport,
     recurse: bool,
 ) -> Loop {
     let x: wt_cj4::Snapshot = match serde::de::Deserialize::deserialize(&mut *input) {
@@ -127,18 +446,14 @@ fn process_record(
         Err(rmp_serde::decode::Error::InvalidMarkerRead(err))
             if err.kind() == std::io::ErrorKind::UnexpectedEof =>
         {
-            if let Some(m) = multi {
-                *input = if let Some(next) = open_next(m) {
-                    *files += 1;
-                    next
-                } else {
-                    return Loop::Break;
-                };
-                if recurse {
-                    return process_record(multi, &mut *input, output, files, false);
-                } else {
-                    return Loop::Break;
-                }
+            *input = if let Some(next) = open_next(paths) {
+                *files += 1;
+                next
+            } else {
+                return Loop::Break;
+            };
+            if recurse {
+                return process_record(paths, &mut *input, output, row, files, timing, false);
             } else {
                 return Loop::Break;
             }
@@ -148,132 +463,474 @@ fn process_record(
             return Loop::Break;
         }
     };
-    output
-        .serialize(&FlatSnapshot {
-            simulation_time: x.sim_time.get::<uom::si::time::second>(),
-            delta_t: x.delta_t.get::<uom::si::time::second>(),
-            airspeed_indicated: x
-                .environment
-                .instruments
-                .airspeed_indicated
-                .get::<uom::si::velocity::knot>(),
-            airspeed_true: x
-                .environment
-                .instruments
-                .airspeed_true
-                .get::<uom::si::velocity::knot>(),
-            vertical_speed: x
-                .environment
-                .instruments
-                .vertical_speed
-                .get::<uom::si::velocity::foot_per_minute>(),
-            mach_number: x
-                .environment
-                .instruments
-                .mach_number
-                .get::<uom::si::ratio::ratio>(),
-            ambient_density: x
-                .environment
-                .instruments
-                .ambient_density
-                .get::<uom::si::mass_density::slug_per_cubic_foot>(),
-            geometric_altitude: x
-                .environment
-                .instruments
-                .geometric_altitude
-                .get::<uom::si::length::foot>(),
-            pressure_altitude: x
-                .environment
-                .instruments
-                .pressure_altitude
-                .get::<uom::si::length::foot>(),
-            engine1_thrust: x.environment.engines[EngineNumber::Engine1]
-                .thrust
-                .get::<uom::si::force::poundal>(),
-            engine1_fadec_mode: x.aircraft.engines[EngineNumber::Engine1].mode,
-            engine1_physical_throttle: x.aircraft.engines[EngineNumber::Engine1].physical_throttle,
-            engine1_engine_throttle: x.aircraft.engines[EngineNumber::Engine1].engine_throttle,
-            engine1_visual_throttle: x.aircraft.engines[EngineNumber::Engine1].visual_throttle,
-            engine1_pid_config: format!(
-                "{:?}",
-                x.aircraft.engines[EngineNumber::Engine1].fadec.pid_config()
-            ),
-            engine1_pid_last_error: x.aircraft.engines[EngineNumber::Engine1]
-                .fadec
-                .pid_state()
-                .prior_error
-                .get::<uom::si::force::poundal>(),
-            engine1_pid_retained_error: (x.aircraft.engines[EngineNumber::Engine1]
-                .fadec
-                .pid_state()
-                .retained_error
-                / uom::si::f64::Time::new::<uom::si::time::second>(1.))
+    timing.observe(&x);
+    output.write(row.fill(&x)).unwrap();
+    Loop::Continue
+}
+
+/// Flattens a snapshot into a single CSV row
+fn flatten(x: &wt_cj4::Snapshot) -> FlatRow {
+    FlatRow {
+        snapshot: flatten_snapshot(x),
+        engines: EngineNumber::iter()
+            .into_iter()
+            .map(|n| {
+                let pid_config = x.aircraft.engines[n].fadec.pid_config().to_string();
+                flatten_engine(x, n, pid_config)
+            })
+            .collect(),
+    }
+}
+
+/// A CSV row reused across records
+///
+/// Converting a long recording through [`flatten`] allocates the engine
+/// columns and the text of each PID configuration anew for every record.
+/// Refilling a buffered row instead reuses those allocations, so that once
+/// the first record has been converted, later records are converted without
+/// allocating.
+#[derive(Default)]
+struct RowBuffer {
+    row: Option<FlatRow>,
+}
+
+impl RowBuffer {
+    /// Refills the buffered row from a snapshot, producing the same row as
+    /// [`flatten`]
+    fn fill(&mut self, x: &wt_cj4::Snapshot) -> &FlatRow {
+        let row = match &mut self.row {
+            Some(row) => row,
+            empty @ None => return empty.insert(flatten(x)),
+        };
+
+        row.snapshot = flatten_snapshot(x);
+        for (engine, n) in row.engines.iter_mut().zip(EngineNumber::iter()) {
+            let mut pid_config = std::mem::take(&mut engine.pid_config);
+            pid_config.clear();
+            write!(pid_config, "{}", x.aircraft.engines[n].fadec.pid_config()).unwrap();
+            *engine = flatten_engine(x, n, pid_config);
+        }
+        row
+    }
+}
+
+/// Flattens the columns of a snapshot shared by all engines
+fn flatten_snapshot(x: &wt_cj4::Snapshot) -> FlatSnapshot {
+    FlatSnapshot {
+        schema_version: x.schema_version,
+        simulation_time: x.sim_time.get::<uom::si::time::second>(),
+        delta_t: x.delta_t.get::<uom::si::time::second>(),
+        marker: x.marker,
+        airspeed_indicated: x
+            .environment
+            .instruments
+            .airspeed_indicated
+            .get::<uom::si::velocity::knot>(),
+        airspeed_true: x
+            .environment
+            .instruments
+            .airspeed_true
+            .get::<uom::si::velocity::knot>(),
+        vertical_speed: x
+            .environment
+            .instruments
+            .vertical_speed
+            .get::<uom::si::velocity::foot_per_minute>(),
+        mach_number: x
+            .environment
+            .instruments
+            .mach_number
+            .get::<uom::si::ratio::ratio>(),
+        ambient_density: x
+            .environment
+            .instruments
+            .ambient_density
+            .get::<uom::si::mass_density::slug_per_cubic_foot>(),
+        geometric_altitude: x
+            .environment
+            .instruments
+            .geometric_altitude
+            .get::<uom::si::length::foot>(),
+        pressure_altitude: x
+            .environment
+            .instruments
+            .pressure_altitude
+            .get::<uom::si::length::foot>(),
+        on_ground: x.environment.instruments.on_ground,
+        specific_air_range: specific_air_range(
+            x.environment.instruments.airspeed_true,
+            x.environment
+                .engines
+                .iter()
+                .into_iter()
+                .map(|e| e.fuel_flow)
+                .sum(),
+        ),
+    }
+}
+
+/// Flattens the columns of a single engine, given the text of its PID
+/// configuration
+fn flatten_engine(x: &wt_cj4::Snapshot, n: EngineNumber, pid_config: String) -> FlatEngine {
+    FlatEngine {
+        thrust: x.environment.engines[n]
+            .thrust
             .get::<uom::si::force::poundal>(),
-            engine1_pid_proportional: x.aircraft.engines[EngineNumber::Engine1]
-                .fadec
-                .last_pid_outputs()
-                .proportional
-                .get::<uom::si::ratio::ratio>(),
-            engine1_pid_integral: x.aircraft.engines[EngineNumber::Engine1]
-                .fadec
-                .last_pid_outputs()
-                .integral
-                .get::<uom::si::ratio::ratio>(),
-            engine1_pid_derivative: x.aircraft.engines[EngineNumber::Engine1]
-                .fadec
-                .last_pid_outputs()
-                .derivative
-                .get::<uom::si::ratio::ratio>(),
-            engine1_pid_output: x.aircraft.engines[EngineNumber::Engine1]
-                .fadec
-                .last_pid_outputs()
-                .output()
-                .get::<uom::si::ratio::ratio>(),
-            engine1_fadec_enabled: x.aircraft.engines[EngineNumber::Engine1].fadec.is_enabled(),
-            engine2_thrust: x.environment.engines[EngineNumber::Engine2]
-                .thrust
-                .get::<uom::si::force::poundal>(),
-            engine2_fadec_mode: x.aircraft.engines[EngineNumber::Engine2].mode,
-            engine2_physical_throttle: x.aircraft.engines[EngineNumber::Engine2].physical_throttle,
-            engine2_engine_throttle: x.aircraft.engines[EngineNumber::Engine2].engine_throttle,
-            engine2_visual_throttle: x.aircraft.engines[EngineNumber::Engine2].visual_throttle,
-            engine2_pid_config: format!(
-                "{:?}",
-                x.aircraft.engines[EngineNumber::Engine2].fadec.pid_config()
-            ),
-            engine2_pid_last_error: x.aircraft.engines[EngineNumber::Engine2]
-                .fadec
-                .pid_state()
-                .prior_error
-                .get::<uom::si::force::poundal>(),
-            engine2_pid_retained_error: (x.aircraft.engines[EngineNumber::Engine2]
-                .fadec
-                .pid_state()
-                .retained_error
-                / uom::si::f64::Time::new::<uom::si::time::second>(1.))
+        fuel_flow: x.environment.engines[n]
+            .fuel_flow
+            .get::<uom::si::mass_rate::pound_per_hour>(),
+        fadec_mode: x.aircraft.engines[n].mode,
+        physical_throttle: x.aircraft.engines[n].physical_throttle,
+        engine_throttle: x.aircraft.engines[n].engine_throttle,
+        visual_throttle: x.aircraft.engines[n].visual_throttle,
+        pid_config,
+        pid_last_error: x.aircraft.engines[n]
+            .fadec
+            .pid_state()
+            .prior_error
             .get::<uom::si::force::poundal>(),
-            engine2_pid_proportional: x.aircraft.engines[EngineNumber::Engine2]
-                .fadec
-                .last_pid_outputs()
-                .proportional
-                .get::<uom::si::ratio::ratio>(),
-            engine2_pid_integral: x.aircraft.engines[EngineNumber::Engine2]
-                .fadec
-                .last_pid_outputs()
-                .integral
-                .get::<uom::si::ratio::ratio>(),
-            engine2_pid_derivative: x.aircraft.engines[EngineNumber::Engine2]
-                .fadec
-                .last_pid_outputs()
-                .derivative
-                .get::<uom::si::ratio::ratio>(),
-            engine2_pid_output: x.aircraft.engines[EngineNumber::Engine2]
+        pid_retained_error: x.aircraft.engines[n]
+            .fadec
+            .pid_state()
+            .retained_error_as_force()
+            .get::<uom::si::force::poundal>(),
+        pid_proportional: x.aircraft.engines[n]
+            .fadec
+            .last_pid_outputs()
+            .proportional
+            .get::<uom::si::ratio::ratio>(),
+        pid_integral: x.aircraft.engines[n]
+            .fadec
+            .last_pid_outputs()
+            .integral
+            .get::<uom::si::ratio::ratio>(),
+        pid_derivative: x.aircraft.engines[n]
+            .fadec
+            .last_pid_outputs()
+            .derivative
+            .get::<uom::si::ratio::ratio>(),
+        pid_output: x.aircraft.engines[n]
+            .fadec
+            .last_pid_outputs()
+            .output()
+            .get::<uom::si::ratio::ratio>(),
+        pid_saturation_duty_cycle: x.aircraft.engines[n]
+            .fadec
+            .saturation_duty_cycle()
+            .get::<uom::si::ratio::ratio>(),
+        fadec_enabled: x.aircraft.engines[n].fadec.is_enabled(),
+        thrust_shortfall: x.thrust_asymmetry.shortfall[n].get::<uom::si::force::poundal>(),
+        thrust_lagging: x.thrust_asymmetry.lagging[n],
+        overspeed_protection: x.overspeed_protection[n],
+        turbine_temperature_limit: x.turbine_temperature_limit[n],
+        readings_valid: x.readings_valid[n],
+        thrust_limit_source: x.thrust_limit_source[n],
+    }
+}
+
+/// The distance flown per pound of fuel burned, in nautical miles
+///
+/// Not available without any fuel flow, such as with the engines shut down or
+/// in recordings made before fuel flow was recorded.
+fn specific_air_range(
+    airspeed_true: uom::si::f64::Velocity,
+    fuel_flow: uom::si::f64::MassRate,
+) -> Option<f64> {
+    let one_hour = uom::si::f64::Time::new::<uom::si::time::hour>(1.);
+    let distance: uom::si::f64::Length = airspeed_true * one_hour;
+    let fuel: uom::si::f64::Mass = fuel_flow * one_hour;
+    if fuel.get::<uom::si::mass::pound>() > 0. {
+        Some(distance.get::<uom::si::length::nautical_mile>() / fuel.get::<uom::si::mass::pound>())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use avmath::airspeed::MachNumber;
+    use avmath::isa::{GeometricAltitude, PressureAltitude};
+    use uom::si::{
+        f64::{Force, MassDensity, MassRate, Momentum, Time, Velocity},
+        force::poundal,
+        length::foot,
+        mass_density::slug_per_cubic_foot,
+        mass_rate::pound_per_hour,
+        momentum::pound_foot_per_second,
+        ratio::ratio,
+        time::second,
+        velocity::{foot_per_minute, knot},
+    };
+    use wt_cj4::control_params::ThrustValue;
+    use wt_cj4::engines::EngineData;
+    use wt_cj4::{
+        Aircraft, EngineReadings, Environment, Instruments, Snapshot, SNAPSHOT_SCHEMA_VERSION,
+    };
+
+    pub(crate) fn snapshot() -> Snapshot {
+        let mut aircraft = Aircraft::default();
+        aircraft.engines.update(|_, e| {
+            e.commanded_thrust = ThrustValue::from_force(Force::new::<poundal>(2_000.))
+        });
+        Snapshot {
+            schema_version: SNAPSHOT_SCHEMA_VERSION,
+            sim_time: Time::new::<second>(12.),
+            delta_t: Time::new::<second>(1. / 30.),
+            environment: Environment {
+                instruments: Instruments {
+                    mach_number: MachNumber::new::<ratio>(0.4),
+                    ambient_density: MassDensity::new::<slug_per_cubic_foot>(0.0017),
+                    geometric_altitude: GeometricAltitude::new::<foot>(10_000.),
+                    pressure_altitude: PressureAltitude::new::<foot>(10_000.),
+                    airspeed_indicated: Velocity::new::<knot>(220.),
+                    airspeed_true: Velocity::new::<knot>(255.),
+                    vertical_speed: Velocity::new::<foot_per_minute>(500.),
+                    on_ground: true,
+                },
+                engines: EngineData::new(EngineReadings {
+                    thrust: Force::new::<poundal>(1_500.),
+                    fuel_flow: Default::default(),
+                }),
+            },
+            aircraft,
+            thrust_asymmetry: Default::default(),
+            marker: None,
+            overspeed_protection: EngineData::new(false),
+            turbine_temperature_limit: EngineData::new(false),
+            readings_valid: EngineData::new(true),
+            thrust_limit_source: EngineData::new(ThrustLimitSource::ClimbLowAltitude),
+            commanded: Some(Default::default()),
+        }
+    }
+
+    /// Removes a field from a MessagePack map, as if recorded before it existed
+    pub(crate) fn remove_field(value: &mut rmpv::Value, path: &[&str]) {
+        if let rmpv::Value::Map(entries) = value {
+            if let [name] = path {
+                entries.retain(|(k, _)| k.as_str() != Some(name));
+            } else if let Some((_, child)) = entries
+                .iter_mut()
+                .find(|(k, _)| k.as_str() == Some(path[0]))
+            {
+                remove_field(child, &path[1..]);
+            }
+        }
+    }
+
+    #[test]
+    fn recording_paths_continue_through_splits() {
+        assert_eq!(
+            vec![
+                "2021-01-05T11-43-44_09.msgpack.gz",
+                "2021-01-05T11-43-44_10.msgpack.gz",
+                "2021-01-05T11-43-44_11.msgpack.gz",
+            ],
+            recording_paths("2021-01-05T11-43-44_09.msgpack.gz")
+                .take(3)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["flight.msgpack.gz"],
+            recording_paths("flight.msgpack.gz").collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn current_recording_reports_current_schema_version() {
+        let encoded = rmp_serde::to_vec_named(&snapshot()).unwrap();
+        let decoded: Snapshot = rmp_serde::from_slice(&encoded).unwrap();
+
+        let row = flatten(&decoded);
+        assert_eq!(SNAPSHOT_SCHEMA_VERSION, row.snapshot.schema_version);
+        assert!(row.snapshot.on_ground);
+    }
+
+    #[test]
+    fn v1_recording_converts_with_defaults() {
+        let encoded = rmp_serde::to_vec_named(&snapshot()).unwrap();
+        let mut value = rmpv::decode::read_value(&mut encoded.as_slice()).unwrap();
+        remove_field(&mut value, &["schema_version"]);
+        remove_field(&mut value, &["thrust_asymmetry"]);
+        remove_field(&mut value, &["environment", "instruments", "on_ground"]);
+        remove_field(&mut value, &["overspeed_protection"]);
+        remove_field(&mut value, &["turbine_temperature_limit"]);
+        remove_field(&mut value, &["readings_valid"]);
+        remove_field(&mut value, &["thrust_limit_source"]);
+        remove_field(&mut value, &["commanded"]);
+        for engine in &["engine1", "engine2"] {
+            remove_field(&mut value, &["environment", "engines", engine, "fuel_flow"]);
+            remove_field(
+                &mut value,
+                &["aircraft", "engines", engine, "commanded_thrust"],
+            );
+        }
+
+        let mut v1 = Vec::new();
+        rmpv::encode::write_value(&mut v1, &value).unwrap();
+        let decoded: Snapshot = rmp_serde::from_slice(&v1).unwrap();
+
+        let row = flatten(&decoded);
+        assert_eq!(1, row.snapshot.schema_version);
+        assert!(!row.snapshot.on_ground);
+        assert_eq!(0., row.engines[0].thrust_shortfall);
+        assert!(!row.engines[0].thrust_lagging);
+        assert_eq!(1_500., row.engines[0].thrust);
+        assert_eq!(None, row.snapshot.marker);
+        assert!(!row.engines[0].overspeed_protection);
+        assert!(!row.engines[0].turbine_temperature_limit);
+        assert!(row.engines[0].readings_valid);
+        assert_eq!(ThrustLimitSource::Lever, row.engines[0].thrust_limit_source);
+        assert_eq!(None, row.snapshot.specific_air_range);
+
+        let mut csv = RowWriter::new(Vec::new());
+        csv.write(&row).unwrap();
+        let csv = String::from_utf8(csv.into_inner()).unwrap();
+        assert!(csv.starts_with("schema_version,"));
+    }
+
+    #[test]
+    fn specific_air_range_divides_true_airspeed_by_total_fuel_flow() {
+        let mut cruise = snapshot();
+        cruise.environment.instruments.airspeed_true = Velocity::new::<knot>(420.);
+        cruise
+            .environment
+            .engines
+            .update(|_, e| e.fuel_flow = MassRate::new::<pound_per_hour>(600.));
+
+        let row = flatten(&cruise);
+
+        assert_eq!(600., row.engines[1].fuel_flow);
+        let specific_air_range = row.snapshot.specific_air_range.unwrap();
+        assert!(
+            (specific_air_range - 0.35).abs() < 1e-9,
+            "{}",
+            specific_air_range
+        );
+        assert_eq!(None, flatten(&snapshot()).snapshot.specific_air_range);
+    }
+
+    #[test]
+    fn marker_column_is_set_only_on_marked_rows() {
+        let marked = Snapshot {
+            marker: Some(7),
+            ..snapshot()
+        };
+
+        let mut csv = RowWriter::new(Vec::new());
+        for x in &[snapshot(), marked, snapshot()] {
+            csv.write(&flatten(x)).unwrap();
+        }
+        let csv = csv.into_inner();
+        let mut csv = csv::Reader::from_reader(csv.as_slice());
+        let column = csv
+            .headers()
+            .unwrap()
+            .iter()
+            .position(|h| h == "marker")
+            .unwrap();
+
+        let markers: Vec<String> = csv
+            .records()
+            .map(|r| r.unwrap()[column].to_owned())
+            .collect();
+        assert_eq!(vec!["", "7", ""], markers);
+    }
+
+    #[test]
+    fn buffered_rows_match_flattened_rows() {
+        let mut limited = snapshot();
+        limited.sim_time = Time::new::<second>(12.5);
+        limited.marker = Some(3);
+        limited.aircraft.engines.update(|_, e| {
+            e.fadec = e
                 .fadec
-                .last_pid_outputs()
-                .output()
-                .get::<uom::si::ratio::ratio>(),
-            engine2_fadec_enabled: x.aircraft.engines[EngineNumber::Engine2].fadec.is_enabled(),
-        })
-        .unwrap();
-    Loop::Continue
+                .with_climb_integral_limit(Momentum::new::<pound_foot_per_second>(20_000.))
+        });
+        limited.thrust_limit_source = EngineData::new(ThrustLimitSource::ClimbHighAltitude);
+        let mut cruise = snapshot();
+        cruise.sim_time = Time::new::<second>(13.);
+        cruise.environment.instruments.airspeed_true = Velocity::new::<knot>(420.);
+        cruise
+            .environment
+            .engines
+            .update(|_, e| e.fuel_flow = MassRate::new::<pound_per_hour>(600.));
+        let records = [snapshot(), limited, cruise, limited];
+
+        let mut flattened = RowWriter::new(Vec::new());
+        let mut buffered = RowWriter::new(Vec::new());
+        let mut row = RowBuffer::default();
+        for x in &records {
+            flattened.write(&flatten(x)).unwrap();
+            buffered.write(row.fill(x)).unwrap();
+        }
+
+        let flattened = String::from_utf8(flattened.into_inner()).unwrap();
+        let buffered = String::from_utf8(buffered.into_inner()).unwrap();
+        assert_eq!(records.len() + 1, buffered.lines().count());
+        assert_eq!(flattened, buffered);
+    }
+
+    #[test]
+    fn thrust_columns_are_labeled_in_poundals() {
+        let mut csv = RowWriter::new(Vec::new());
+        csv.write(&flatten(&snapshot())).unwrap();
+        let csv = csv.into_inner();
+        let mut csv = csv::Reader::from_reader(csv.as_slice());
+        let headers = csv.headers().unwrap().clone();
+        let record = csv.records().next().unwrap().unwrap();
+
+        let column = |name| &record[headers.iter().position(|h| h == name).unwrap()];
+        assert_eq!("1500.0", column("engine1_thrust_pdl"));
+        assert_eq!("1500.0", column("engine2_thrust_pdl"));
+        assert!(headers.iter().any(|h| h == "engine1_thrust_shortfall_pdl"));
+        assert!(!headers.iter().any(|h| h == "engine1_thrust"));
+    }
+
+    /// Writes a row with the engines of `snapshot` repeated to `engine_count`
+    fn export_with_engines(engine_count: usize) -> (csv::StringRecord, csv::StringRecord) {
+        let row = flatten(&snapshot());
+        let engines = (0..engine_count)
+            .map(|_| flatten(&snapshot()).engines.remove(0))
+            .collect();
+        let row = FlatRow { engines, ..row };
+
+        let mut csv = RowWriter::new(Vec::new());
+        csv.write(&row).unwrap();
+        let csv = csv.into_inner();
+        let mut csv = csv::Reader::from_reader(csv.as_slice());
+        let headers = csv.headers().unwrap().clone();
+        let record = csv.records().next().unwrap().unwrap();
+        (headers, record)
+    }
+
+    #[test]
+    fn engine_columns_follow_engine_count() {
+        let engine_columns = column_names(&flatten(&snapshot()).engines[0])
+            .unwrap()
+            .len();
+        let common_columns = column_names(&flatten(&snapshot()).snapshot).unwrap().len();
+
+        let (headers, record) = export_with_engines(1);
+        assert_eq!(common_columns + engine_columns, headers.len());
+        assert_eq!(headers.len(), record.len());
+        assert!(headers.iter().any(|h| h == "engine1_thrust_pdl"));
+        assert!(!headers.iter().any(|h| h.starts_with("engine2_")));
+
+        let (headers, record) = export_with_engines(4);
+        assert_eq!(common_columns + 4 * engine_columns, headers.len());
+        assert_eq!(headers.len(), record.len());
+        assert!(headers.iter().any(|h| h == "engine4_readings_valid"));
+        assert!(!headers.iter().any(|h| h.starts_with("engine5_")));
+    }
+
+    #[test]
+    fn rows_with_a_different_engine_count_are_rejected() {
+        let mut csv = RowWriter::new(Vec::new());
+        csv.write(&flatten(&snapshot())).unwrap();
+
+        let mut row = flatten(&snapshot());
+        row.engines.pop();
+        assert!(csv.write(&row).is_err());
+    }
 }
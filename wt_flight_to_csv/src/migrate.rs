@@ -0,0 +1,249 @@
+//! Rewriting of recordings to the current snapshot schema
+//!
+//! Each record is decoded, migrated with [`wt_cj4::migrate`], and encoded
+//! again in the same format the recorder writes, so that historical
+//! recordings carry the fields that later builds reconstruct from them.
+
+use std::fmt;
+use wt_cj4::Snapshot;
+
+/// An error encountered while rewriting a recording
+#[derive(Debug)]
+pub enum Error {
+    /// A record could not be decoded
+    Decode(rmp_serde::decode::Error),
+    /// A migrated record could not be encoded
+    Encode(rmp_serde::encode::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Decode(err) => write!(f, "Error deserializing: {}", err),
+            Self::Encode(err) => write!(f, "Error serializing: {}", err),
+        }
+    }
+}
+
+/// A summary of the snapshots rewritten from a recording
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Migration {
+    /// The number of snapshots rewritten
+    pub records: u64,
+
+    /// The number of snapshots recorded with an older schema version
+    pub upgraded: u64,
+}
+
+impl Migration {
+    /// Rewrites every snapshot from a single decompressed file to `writer`
+    ///
+    /// Reading stops cleanly at the end of the file. A truncated or corrupt
+    /// record is returned as an error, with the snapshots before it written.
+    pub fn rewrite_file<R: std::io::Read, W: std::io::Write>(
+        &mut self,
+        reader: R,
+        writer: &mut W,
+    ) -> Result<(), Error> {
+        let mut input = rmp_serde::Deserializer::new(reader);
+        loop {
+            let snapshot: Snapshot = match serde::de::Deserialize::deserialize(&mut input) {
+                Ok(snapshot) => snapshot,
+                Err(rmp_serde::decode::Error::InvalidMarkerRead(err))
+                    if err.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    return Ok(())
+                }
+                Err(err) => return Err(Error::Decode(err)),
+            };
+
+            let migrated = wt_cj4::migrate::migrate(snapshot);
+            rmp_serde::encode::write_named(writer, &migrated).map_err(Error::Encode)?;
+            self.records += 1;
+            if migrated.schema_version != snapshot.schema_version {
+                self.upgraded += 1;
+            }
+        }
+    }
+}
+
+impl fmt::Display for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Migrated {} records, of which {} were upgraded to schema version {}",
+            self.records,
+            self.upgraded,
+            wt_cj4::SNAPSHOT_SCHEMA_VERSION
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{remove_field, snapshot};
+    use wt_cj4::control_params::{ThrottleAxis, ThrottleMode, ThrottlePercent, ThrustValue};
+    use wt_cj4::engines::EngineData;
+    use wt_cj4::{CommandedOutputs, ThrustLimitSource};
+
+    /// Encodes a snapshot without the fields at `paths`, as a recorder from
+    /// before those fields existed would have written it
+    fn encode_without(recorded: &Snapshot, paths: &[&[&str]]) -> Vec<u8> {
+        let encoded = rmp_serde::to_vec_named(recorded).unwrap();
+        let mut value = rmpv::decode::read_value(&mut encoded.as_slice()).unwrap();
+        for path in paths {
+            remove_field(&mut value, path);
+        }
+        let mut old = Vec::new();
+        rmpv::encode::write_value(&mut old, &value).unwrap();
+        old
+    }
+
+    fn rewrite(old: &[u8]) -> Snapshot {
+        let mut rewritten = Vec::new();
+        Migration::default()
+            .rewrite_file(old, &mut rewritten)
+            .unwrap();
+        rmp_serde::from_slice(&rewritten).unwrap()
+    }
+
+    /// The paths of a FADEC state field in each engine
+    fn fadec_field(name: &'static str) -> [[&'static str; 5]; 2] {
+        ["engine1", "engine2"].map(|engine| ["aircraft", "engines", engine, "fadec", name])
+    }
+
+    #[test]
+    fn v1_record_migrates_to_current_schema() {
+        let mut recorded = snapshot();
+        recorded.aircraft.engines.engine1.mode = ThrottleMode::Climb;
+        recorded.aircraft.engines.engine1.engine_throttle =
+            ThrottlePercent::from(ThrottleAxis::CLIMB);
+        recorded.environment.engines.engine2.thrust =
+            uom::si::f64::Force::new::<uom::si::force::poundal>(f64::NAN);
+        let encoded = rmp_serde::to_vec_named(&recorded).unwrap();
+        let mut value = rmpv::decode::read_value(&mut encoded.as_slice()).unwrap();
+        remove_field(&mut value, &["schema_version"]);
+        remove_field(&mut value, &["readings_valid"]);
+        remove_field(&mut value, &["commanded"]);
+        let mut v1 = Vec::new();
+        rmpv::encode::write_value(&mut v1, &value).unwrap();
+
+        let mut migration = Migration::default();
+        let mut rewritten = Vec::new();
+        migration
+            .rewrite_file(v1.as_slice(), &mut rewritten)
+            .unwrap();
+        migration
+            .rewrite_file(rewritten.clone().as_slice(), &mut Vec::new())
+            .unwrap();
+        assert_eq!(2, migration.records);
+        assert_eq!(1, migration.upgraded);
+
+        let migrated: Snapshot = rmp_serde::from_slice(&rewritten).unwrap();
+        assert_eq!(wt_cj4::SNAPSHOT_SCHEMA_VERSION, migrated.schema_version);
+        assert_eq!(
+            EngineData::new_distinct(true, false),
+            migrated.readings_valid
+        );
+        let commanded = migrated.commanded.unwrap();
+        assert_eq!(
+            recorded.aircraft.engines.map(|_, e| e.engine_throttle),
+            commanded.engine_throttle
+        );
+        assert_eq!(
+            recorded.aircraft.engines.map(|_, e| e.mode),
+            commanded.throttle_mode
+        );
+
+        let row = crate::flatten(&migrated);
+        assert_eq!(wt_cj4::SNAPSHOT_SCHEMA_VERSION, row.snapshot.schema_version);
+        assert!(!row.engines[1].readings_valid);
+    }
+
+    #[test]
+    fn v3_record_reports_untracked_fadec_flags_as_defaults() {
+        let mut recorded = snapshot();
+        recorded.schema_version = 3;
+        recorded.overspeed_protection = EngineData::new(true);
+        recorded.turbine_temperature_limit = EngineData::new(true);
+        let [active1, active2] = fadec_field("active");
+        let [overspeed1, overspeed2] = fadec_field("overspeed_protection_active");
+        let [source1, source2] = fadec_field("thrust_limit_source");
+        let [limited1, limited2] = fadec_field("turbine_temperature_limited");
+        let v3 = encode_without(
+            &recorded,
+            &[
+                &["overspeed_protection"],
+                &["turbine_temperature_limit"],
+                &["thrust_limit_source"],
+                &["readings_valid"],
+                &["commanded"],
+                &active1,
+                &active2,
+                &overspeed1,
+                &overspeed2,
+                &source1,
+                &source2,
+                &limited1,
+                &limited2,
+            ],
+        );
+
+        let migrated = rewrite(&v3);
+        assert_eq!(EngineData::new(false), migrated.overspeed_protection);
+        assert_eq!(EngineData::new(false), migrated.turbine_temperature_limit);
+        assert_eq!(
+            EngineData::new(ThrustLimitSource::Lever),
+            migrated.thrust_limit_source
+        );
+        assert_eq!(EngineData::new(true), migrated.readings_valid);
+
+        let commanded = migrated.commanded.unwrap();
+        assert_eq!(
+            recorded.aircraft.engines.map(|_, e| e.commanded_thrust),
+            commanded.thrust_target
+        );
+        assert_eq!(EngineData::new(false), commanded.fadec_active);
+    }
+
+    #[test]
+    fn v9_record_takes_thrust_target_from_commanded_thrust() {
+        let mut recorded = snapshot();
+        recorded.schema_version = 9;
+        recorded.aircraft.engines.engine2.commanded_thrust = ThrustValue::MIN;
+        recorded.commanded = Some(CommandedOutputs {
+            engine_throttle: EngineData::new(ThrottlePercent::from(ThrottleAxis::CLIMB)),
+            throttle_mode: EngineData::new(ThrottleMode::Climb),
+            ..CommandedOutputs::default()
+        });
+        let [active1, active2] = fadec_field("active");
+        let v9 = encode_without(
+            &recorded,
+            &[
+                &["commanded", "thrust_target"],
+                &["commanded", "fadec_active"],
+                &active1,
+                &active2,
+            ],
+        );
+
+        let migrated = rewrite(&v9);
+        assert_eq!(wt_cj4::SNAPSHOT_SCHEMA_VERSION, migrated.schema_version);
+        assert_eq!(
+            Some(CommandedOutputs {
+                thrust_target: EngineData::new_distinct(
+                    recorded.aircraft.engines.engine1.commanded_thrust,
+                    ThrustValue::MIN
+                ),
+                fadec_active: EngineData::new(false),
+                ..recorded.commanded.unwrap()
+            }),
+            migrated.commanded
+        );
+        assert_eq!(
+            EngineData::new(ThrustLimitSource::ClimbLowAltitude),
+            migrated.thrust_limit_source
+        );
+    }
+}
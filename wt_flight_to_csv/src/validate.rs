@@ -0,0 +1,246 @@
+//! Validation of converted recordings against an expected CSV file
+//!
+//! Fields that parse as numbers are compared within a per-column tolerance so
+//! that values which drift in the last few bits, such as floating-point
+//! encodings of enumerations, are not reported as mismatches. All other fields
+//! must match exactly.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// Tolerances permitted when comparing numeric fields
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColumnTolerances {
+    default: f64,
+    columns: HashMap<String, f64>,
+}
+
+impl ColumnTolerances {
+    /// Constructs a set of tolerances applying `default` to every column
+    pub fn new(default: f64) -> Self {
+        Self {
+            default,
+            columns: HashMap::new(),
+        }
+    }
+
+    /// Overrides the tolerance for a single column
+    pub fn with_column(mut self, column: impl Into<String>, epsilon: f64) -> Self {
+        self.columns.insert(column.into(), epsilon);
+        self
+    }
+
+    /// Parses a tolerance override in the form `<column>=<epsilon>`
+    pub fn parse_override(self, arg: &str) -> Result<Self, String> {
+        let (column, epsilon) = arg
+            .split_once('=')
+            .ok_or_else(|| format!("expected <column>=<epsilon>, got {:?}", arg))?;
+        let epsilon = epsilon
+            .parse::<f64>()
+            .map_err(|err| format!("invalid tolerance for {}: {}", column, err))?;
+        Ok(self.with_column(column, epsilon))
+    }
+
+    /// The tolerance for a column
+    pub fn tolerance(&self, column: &str) -> f64 {
+        self.columns.get(column).copied().unwrap_or(self.default)
+    }
+
+    /// Whether two fields in a column are considered equal
+    pub fn fields_match(&self, column: &str, expected: &str, actual: &str) -> bool {
+        match (expected.parse::<f64>(), actual.parse::<f64>()) {
+            (Ok(e), Ok(a)) => e == a || (e - a).abs() < self.tolerance(column),
+            _ => expected == actual,
+        }
+    }
+}
+
+/// A difference between the expected and actual records
+#[derive(Clone, Debug, PartialEq)]
+pub enum Mismatch {
+    /// A column in the expected file is not present in the actual file
+    MissingColumn(String),
+
+    /// A field differs by more than the tolerance for its column
+    Field {
+        /// The record number, starting from 1
+        row: usize,
+        /// The column name
+        column: String,
+        /// The expected field value
+        expected: String,
+        /// The actual field value
+        actual: String,
+    },
+
+    /// The files contain a different number of records
+    RecordCount {
+        /// The number of expected records
+        expected: usize,
+        /// The number of actual records
+        actual: usize,
+    },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingColumn(column) => write!(f, "missing column {}", column),
+            Self::Field {
+                row,
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "row {}, {}: expected {}, got {}",
+                row, column, expected, actual
+            ),
+            Self::RecordCount { expected, actual } => {
+                write!(f, "expected {} records, got {}", expected, actual)
+            }
+        }
+    }
+}
+
+/// Compares two CSV files, returning any mismatches found
+pub fn compare<E: std::io::Read, A: std::io::Read>(
+    expected: &mut csv::Reader<E>,
+    actual: &mut csv::Reader<A>,
+    tolerances: &ColumnTolerances,
+) -> csv::Result<Vec<Mismatch>> {
+    let mut mismatches = Vec::new();
+
+    let expected_headers = expected.headers()?.clone();
+    let actual_headers = actual.headers()?.clone();
+
+    let columns: Vec<(&str, usize, Option<usize>)> = expected_headers
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name, i, actual_headers.iter().position(|h| h == name)))
+        .collect();
+
+    for &(name, _, position) in &columns {
+        if position.is_none() {
+            mismatches.push(Mismatch::MissingColumn(name.to_owned()));
+        }
+    }
+
+    let mut expected_records = expected.records();
+    let mut actual_records = actual.records();
+    let mut expected_count = 0;
+    let mut actual_count = 0;
+
+    loop {
+        let (e, a) = match (expected_records.next(), actual_records.next()) {
+            (None, None) => break,
+            (Some(e), None) => {
+                e?;
+                expected_count += 1;
+                continue;
+            }
+            (None, Some(a)) => {
+                a?;
+                actual_count += 1;
+                continue;
+            }
+            (Some(e), Some(a)) => (e?, a?),
+        };
+        expected_count += 1;
+        actual_count += 1;
+
+        for &(name, i, position) in &columns {
+            let (e, a) = match (e.get(i), position.and_then(|p| a.get(p))) {
+                (Some(e), Some(a)) => (e, a),
+                _ => continue,
+            };
+
+            if !tolerances.fields_match(name, e, a) {
+                mismatches.push(Mismatch::Field {
+                    row: expected_count,
+                    column: name.to_owned(),
+                    expected: e.to_owned(),
+                    actual: a.to_owned(),
+                });
+            }
+        }
+    }
+
+    if expected_count != actual_count {
+        mismatches.push(Mismatch::RecordCount {
+            expected: expected_count,
+            actual: actual_count,
+        });
+    }
+
+    Ok(mismatches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reader(data: &str) -> csv::Reader<&[u8]> {
+        csv::Reader::from_reader(data.as_bytes())
+    }
+
+    #[test]
+    fn fields_within_tolerance_match() {
+        let tolerances = ColumnTolerances::new(0.).with_column("engine1_fadec_mode", 1e-6);
+
+        assert!(tolerances.fields_match("engine1_fadec_mode", "2", "2.0000001"));
+        assert!(!tolerances.fields_match("engine1_fadec_mode", "2", "2.001"));
+        assert!(!tolerances.fields_match("delta_t", "2", "2.0000001"));
+        assert!(tolerances.fields_match("delta_t", "0.25", "2.5e-1"));
+        assert!(tolerances.fields_match("engine1_fadec_mode", "Climb", "Climb"));
+        assert!(!tolerances.fields_match("engine1_fadec_mode", "Climb", "Cruise"));
+    }
+
+    #[test]
+    fn compare_reports_only_fields_outside_tolerance() {
+        let mut expected = reader("delta_t,thrust,mode\n0.033,1500,Climb\n0.033,1510,Climb\n");
+        let mut actual = reader("delta_t,thrust,mode\n0.0330001,1500.4,Climb\n0.033,1512,Climb\n");
+        let tolerances = ColumnTolerances::new(1e-3)
+            .parse_override("thrust=0.5")
+            .unwrap();
+
+        let mismatches = compare(&mut expected, &mut actual, &tolerances).unwrap();
+
+        assert_eq!(
+            vec![Mismatch::Field {
+                row: 2,
+                column: "thrust".into(),
+                expected: "1510".into(),
+                actual: "1512".into(),
+            }],
+            mismatches
+        );
+    }
+
+    #[test]
+    fn compare_reports_missing_columns_and_records() {
+        let mut expected = reader("delta_t,on_ground\n0.033,false\n0.033,false\n");
+        let mut actual = reader("delta_t\n0.033\n");
+
+        let mismatches = compare(&mut expected, &mut actual, &ColumnTolerances::new(0.)).unwrap();
+
+        assert_eq!(
+            vec![
+                Mismatch::MissingColumn("on_ground".into()),
+                Mismatch::RecordCount {
+                    expected: 2,
+                    actual: 1
+                },
+            ],
+            mismatches
+        );
+    }
+
+    #[test]
+    fn malformed_override_is_rejected() {
+        assert!(ColumnTolerances::new(0.).parse_override("thrust").is_err());
+        assert!(ColumnTolerances::new(0.)
+            .parse_override("thrust=wide")
+            .is_err());
+    }
+}
@@ -0,0 +1,398 @@
+//! Discrete filters for conditioning control signals
+//!
+//! Filters are stepped once per frame with the elapsed time, and recompute
+//! their coefficients from the frame time so that their response is preserved
+//! as the frame rate varies.
+
+use std::{f64::consts::PI, ops};
+use uom::num_traits::Zero;
+use uom::si::{
+    f64::{Frequency, Time},
+    frequency::hertz,
+    ratio::ratio,
+    time::second,
+};
+
+/// Normalized coefficients of a second-order (biquad) filter
+///
+/// The filter implements the difference equation
+/// `y[n] = b0 x[n] + b1 x[n-1] + b2 x[n-2] - a1 y[n-1] - a2 y[n-2]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BiquadCoefficients {
+    /// Feed-forward coefficient of the current input
+    pub b0: f64,
+    /// Feed-forward coefficient of the prior input
+    pub b1: f64,
+    /// Feed-forward coefficient of the input two steps prior
+    pub b2: f64,
+    /// Feedback coefficient of the prior output
+    pub a1: f64,
+    /// Feedback coefficient of the output two steps prior
+    pub a2: f64,
+}
+
+impl BiquadCoefficients {
+    /// Coefficients that pass the input through unchanged
+    pub const IDENTITY: Self = Self {
+        b0: 1.,
+        b1: 0.,
+        b2: 0.,
+        a1: 0.,
+        a2: 0.,
+    };
+
+    /// Coefficients for a notch filter rejecting `center` when sampled every
+    /// `delta_t`
+    ///
+    /// The `quality` factor sets the width of the notch relative to its center
+    /// frequency, with higher values producing a narrower notch. When
+    /// `center` is at or above the Nyquist frequency for the frame time, the
+    /// notch cannot be represented and the input is passed through unchanged.
+    pub fn notch(center: Frequency, quality: f64, delta_t: Time) -> Self {
+        let omega = 2. * PI * center.get::<hertz>() * delta_t.get::<second>();
+        if !(omega > 0. && omega < PI && quality > 0.) {
+            return Self::IDENTITY;
+        }
+
+        let alpha = omega.sin() / (2. * quality);
+        let cos_omega = omega.cos();
+        let a0 = 1. + alpha;
+
+        Self {
+            b0: 1. / a0,
+            b1: -2. * cos_omega / a0,
+            b2: 1. / a0,
+            a1: -2. * cos_omega / a0,
+            a2: (1. - alpha) / a0,
+        }
+    }
+}
+
+/// The state of a second-order (biquad) filter
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Biquad<T> {
+    inputs: [T; 2],
+    outputs: [T; 2],
+}
+
+impl<T> Biquad<T>
+where
+    T: ops::Add<Output = T> + ops::Sub<Output = T> + ops::Mul<f64, Output = T> + Zero + Copy,
+{
+    /// Constructs a filter at rest
+    pub fn new() -> Self {
+        Self {
+            inputs: [T::zero(); 2],
+            outputs: [T::zero(); 2],
+        }
+    }
+
+    /// Filters the next input sample
+    pub fn step(&mut self, input: T, coefficients: &BiquadCoefficients) -> T {
+        let output = input * coefficients.b0
+            + self.inputs[0] * coefficients.b1
+            + self.inputs[1] * coefficients.b2
+            - self.outputs[0] * coefficients.a1
+            - self.outputs[1] * coefficients.a2;
+
+        self.inputs = [input, self.inputs[0]];
+        self.outputs = [output, self.outputs[0]];
+        output
+    }
+
+    /// Returns the filter to rest
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+/// A filter that rejects a narrow band around a single frequency
+///
+/// Useful for suppressing a known oscillation in a control signal without
+/// slowing the response to other changes.
+///
+/// # Example
+///
+/// ```
+/// use wt_systems::filter::NotchFilter;
+/// use uom::si::f64::{Force, Frequency, Time};
+/// use uom::si::{force::poundal, frequency::hertz, time::second};
+///
+/// let mut filter = NotchFilter::new(Frequency::new::<hertz>(1.), 2.);
+/// let delta_t = Time::new::<second>(1. / 60.);
+///
+/// let mut output = Force::new::<poundal>(0.);
+/// for _ in 0..600 {
+///     output = filter.step(Force::new::<poundal>(50.), delta_t);
+/// }
+/// assert!((output.get::<poundal>() - 50.).abs() < 1e-3);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NotchFilter<T> {
+    center: Frequency,
+    quality: f64,
+    state: Biquad<T>,
+}
+
+impl<T> NotchFilter<T>
+where
+    T: ops::Add<Output = T> + ops::Sub<Output = T> + ops::Mul<f64, Output = T> + Zero + Copy,
+{
+    /// Constructs a notch filter rejecting `center`, with the width of the
+    /// notch set by the `quality` factor
+    pub fn new(center: Frequency, quality: f64) -> Self {
+        Self {
+            center,
+            quality,
+            state: Biquad::new(),
+        }
+    }
+
+    /// The frequency rejected by the filter
+    pub fn center(&self) -> Frequency {
+        self.center
+    }
+
+    /// Filters the next input sample, taken `delta_t` after the prior sample
+    pub fn step(&mut self, input: T, delta_t: Time) -> T {
+        let coefficients = BiquadCoefficients::notch(self.center, self.quality, delta_t);
+        self.state.step(input, &coefficients)
+    }
+
+    /// Returns the filter to rest
+    pub fn reset(&mut self) {
+        self.state.reset();
+    }
+}
+
+/// An exponential moving average, which follows its input with a first-order
+/// lag
+///
+/// After a step in the input, the output covers 63% of the step in one time
+/// constant, regardless of the frame rate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ema<T> {
+    time_constant: Time,
+    value: Option<T>,
+}
+
+impl<T> Ema<T>
+where
+    T: ops::Add<Output = T> + ops::Sub<Output = T> + ops::Mul<f64, Output = T> + Copy,
+{
+    /// Constructs an average that lags its input by `time_constant`
+    pub fn new(time_constant: Time) -> Self {
+        Self {
+            time_constant,
+            value: None,
+        }
+    }
+
+    /// The time constant of the lag
+    pub fn time_constant(&self) -> Time {
+        self.time_constant
+    }
+
+    /// Changes the time constant of the lag, keeping the current average
+    pub fn set_time_constant(&mut self, time_constant: Time) {
+        self.time_constant = time_constant;
+    }
+
+    /// The average as of the last step, if any
+    pub fn value(&self) -> Option<T> {
+        self.value
+    }
+
+    /// Averages the next input sample, taken `delta_t` after the prior sample
+    ///
+    /// The first sample after construction or a reset is passed through
+    /// unchanged, as is every sample when the time constant is not positive.
+    pub fn step(&mut self, input: T, delta_t: Time) -> T {
+        let output = match self.value {
+            Some(prior) if self.time_constant > Time::new::<second>(0.) => {
+                let weight = 1. - (-(delta_t / self.time_constant).get::<ratio>()).exp();
+                prior + (input - prior) * weight
+            }
+            _ => input,
+        };
+        self.value = Some(output);
+        output
+    }
+
+    /// Forgets the current average, so that the next sample is passed through
+    pub fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+/// A limit on the rate at which a signal may change
+///
+/// The output follows its input, moving by no more than `max_rate` per unit
+/// of time. `R` is the rate of change of the signal, such as a force per
+/// unit of time for a force.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlewLimiter<T, R> {
+    max_rate: R,
+    value: Option<T>,
+}
+
+impl<T, R> SlewLimiter<T, R>
+where
+    T: ops::Add<Output = T> + ops::Sub<Output = T> + ops::Neg<Output = T> + PartialOrd + Copy,
+    R: ops::Mul<Time, Output = T> + Copy,
+{
+    /// Constructs a limiter that moves no faster than `max_rate`
+    pub fn new(max_rate: R) -> Self {
+        Self {
+            max_rate,
+            value: None,
+        }
+    }
+
+    /// The fastest rate at which the output moves
+    pub fn max_rate(&self) -> R {
+        self.max_rate
+    }
+
+    /// Changes the fastest rate at which the output moves, keeping the
+    /// current output
+    pub fn set_max_rate(&mut self, max_rate: R) {
+        self.max_rate = max_rate;
+    }
+
+    /// The output as of the last step, if any
+    pub fn value(&self) -> Option<T> {
+        self.value
+    }
+
+    /// Moves the output toward the next input sample, taken `delta_t` after
+    /// the prior sample
+    ///
+    /// The first sample after construction or a reset is passed through
+    /// unchanged.
+    pub fn step(&mut self, input: T, delta_t: Time) -> T {
+        let output = match self.value {
+            Some(prior) => {
+                let max_change = self.max_rate * delta_t;
+                let change = input - prior;
+                if change > max_change {
+                    prior + max_change
+                } else if change < -max_change {
+                    prior - max_change
+                } else {
+                    input
+                }
+            }
+            None => input,
+        };
+        self.value = Some(output);
+        output
+    }
+
+    /// Forgets the current output, so that the next sample is passed through
+    pub fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_equal_within_epsilon;
+    use uom::si::{f64::Force, force::poundal};
+
+    const DELTA_T: f64 = 1. / 60.;
+
+    /// Runs a signal through a 1 Hz notch for 20 seconds, returning the
+    /// largest output magnitude over the final 5 seconds and the final output
+    fn filter_signal(signal: impl Fn(f64) -> f64) -> (f64, f64) {
+        let mut filter = NotchFilter::new(Frequency::new::<hertz>(1.), 2.);
+        let delta_t = Time::new::<second>(DELTA_T);
+
+        let outputs: Vec<f64> = (0..1200)
+            .map(|i| filter.step(signal(f64::from(i) * DELTA_T), delta_t))
+            .collect();
+
+        let peak = outputs[900..]
+            .iter()
+            .fold(0_f64, |peak, y| peak.max(y.abs()));
+        (peak, outputs[outputs.len() - 1])
+    }
+
+    #[test]
+    fn sinusoid_at_notch_frequency_is_attenuated() {
+        let (peak, _) = filter_signal(|t| 100. * (2. * PI * t).sin());
+
+        assert!(peak < 1., "peak {} was not attenuated", peak);
+    }
+
+    #[test]
+    fn dc_offset_passes_through() {
+        let (_, last) = filter_signal(|_| 50.);
+
+        assert_equal_within_epsilon(50., last, 1e-6);
+    }
+
+    #[test]
+    fn frequencies_away_from_notch_are_preserved() {
+        let (peak, _) = filter_signal(|t| 100. * (2. * PI * 0.1 * t).sin());
+
+        assert!(peak > 95., "peak {} was attenuated", peak);
+    }
+
+    #[test]
+    fn notch_above_nyquist_passes_input_through() {
+        let coefficients = BiquadCoefficients::notch(
+            Frequency::new::<hertz>(40.),
+            2.,
+            Time::new::<second>(DELTA_T),
+        );
+
+        assert_eq!(BiquadCoefficients::IDENTITY, coefficients);
+    }
+
+    #[test]
+    fn ema_step_response_follows_time_constant() {
+        let delta_t = Time::new::<second>(DELTA_T);
+        let mut ema = Ema::new(Time::new::<second>(1.));
+        ema.step(Force::new::<poundal>(0.), delta_t);
+
+        let mut output = Force::new::<poundal>(0.);
+        for _ in 0..60 {
+            output = ema.step(Force::new::<poundal>(100.), delta_t);
+        }
+        assert_equal_within_epsilon(63.212, output.get::<poundal>(), 1e-3);
+
+        for _ in 0..1200 {
+            output = ema.step(Force::new::<poundal>(100.), delta_t);
+        }
+        assert_equal_within_epsilon(100., output.get::<poundal>(), 1e-3);
+    }
+
+    #[test]
+    fn slew_limiter_ramps_toward_step_input() {
+        let delta_t = Time::new::<second>(0.1);
+        let max_rate = Force::new::<poundal>(50.) / Time::new::<second>(1.);
+        let mut limiter = SlewLimiter::new(max_rate);
+        limiter.step(Force::new::<poundal>(0.), delta_t);
+
+        let outputs: Vec<f64> = (0..25)
+            .map(|_| {
+                limiter
+                    .step(Force::new::<poundal>(100.), delta_t)
+                    .get::<poundal>()
+            })
+            .collect();
+        for (i, output) in outputs[..20].iter().enumerate() {
+            assert_equal_within_epsilon(5. * (i + 1) as f64, *output, 1e-9);
+        }
+        assert_eq!(100., outputs[24]);
+
+        let output = limiter.step(Force::new::<poundal>(-100.), delta_t);
+        assert_equal_within_epsilon(95., output.get::<poundal>(), 1e-9);
+    }
+}
@@ -11,5 +11,7 @@
 #![deny(missing_debug_implementations, unused_must_use)]
 #![forbid(unsafe_code)]
 
+pub mod filter;
 pub mod pid;
+pub mod table;
 pub mod testing;
@@ -0,0 +1,306 @@
+//! A combinator that chains two PID controllers together
+//!
+//! The outer controller is run first, and its output is translated into the
+//! set point for the inner controller. This allows, for example, an altitude
+//! loop to command an airspeed loop, which in turn commands thrust.
+
+use super::{Pid, PidComponents};
+use std::{fmt, ops};
+use uom::si::f64::*;
+
+/// Configuration for a cascade of PID controllers
+///
+/// # Example
+///
+/// ```
+/// use wt_systems::pid::{cascade::CascadeConfiguration, integral_zeroing::PidConfiguration};
+/// use uom::si::f64::{Length, Ratio, Velocity};
+/// use uom::si::velocity::meter_per_second;
+///
+/// fn velocity_set_point(output: Ratio) -> Velocity {
+///     output * Velocity::new::<meter_per_second>(50.)
+/// }
+///
+/// fn make_config(
+///     outer: PidConfiguration<Length>,
+///     inner: PidConfiguration<Velocity>,
+/// ) -> CascadeConfiguration<PidConfiguration<Length>, PidConfiguration<Velocity>, Velocity> {
+///     CascadeConfiguration {
+///         outer,
+///         inner,
+///         set_point: velocity_set_point,
+///     }
+/// }
+/// ```
+pub struct CascadeConfiguration<OuterConfig, InnerConfig, InnerIn> {
+    /// Configuration for the outer controller
+    pub outer: OuterConfig,
+
+    /// Configuration for the inner controller
+    pub inner: InnerConfig,
+
+    /// Translates the output of the outer controller into the set point for
+    /// the inner controller
+    ///
+    /// The output of the outer controller is clamped by the outer
+    /// configuration prior to translation.
+    pub set_point: fn(Ratio) -> InnerIn,
+}
+
+impl<OuterConfig, InnerConfig, InnerIn> Clone
+    for CascadeConfiguration<OuterConfig, InnerConfig, InnerIn>
+where
+    OuterConfig: Clone,
+    InnerConfig: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            outer: self.outer.clone(),
+            inner: self.inner.clone(),
+            set_point: self.set_point,
+        }
+    }
+}
+
+impl<OuterConfig, InnerConfig, InnerIn> Copy
+    for CascadeConfiguration<OuterConfig, InnerConfig, InnerIn>
+where
+    OuterConfig: Copy,
+    InnerConfig: Copy,
+{
+}
+
+impl<OuterConfig, InnerConfig, InnerIn> fmt::Debug
+    for CascadeConfiguration<OuterConfig, InnerConfig, InnerIn>
+where
+    OuterConfig: fmt::Debug,
+    InnerConfig: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CascadeConfiguration")
+            .field("outer", &self.outer)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// Outputs from both controllers in a cascade
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CascadeComponents<InnerIn> {
+    /// The outputs from the outer controller
+    pub outer: PidComponents,
+
+    /// The set point commanded to the inner controller
+    pub inner_set_point: InnerIn,
+
+    /// The outputs from the inner controller
+    pub inner: PidComponents,
+}
+
+/// A cascade of two PID controllers
+///
+/// The outer controller drives the set point of the inner controller, and the
+/// output of the inner controller is the output of the cascade.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cascade<Outer, Inner> {
+    /// The outer controller
+    pub outer: Outer,
+
+    /// The inner controller
+    pub inner: Inner,
+}
+
+impl<Outer, Inner> Cascade<Outer, Inner> {
+    /// Constructs a new cascade from an outer and an inner controller
+    pub fn new(outer: Outer, inner: Inner) -> Self {
+        Self { outer, inner }
+    }
+
+    /// Steps both controllers forward in time with intermediate outputs
+    ///
+    /// The outer controller is stepped using the error and plant value for the
+    /// outer loop. Its clamped output is translated into the set point for the
+    /// inner controller, which is then stepped against the inner plant value.
+    pub fn step_with_components<OuterIn, InnerIn, Over>(
+        &mut self,
+        outer_error: OuterIn,
+        config: &CascadeConfiguration<Outer::Configuration, Inner::Configuration, InnerIn>,
+        outer_plant_value: OuterIn,
+        inner_plant_value: InnerIn,
+        delta_t: Over,
+    ) -> CascadeComponents<InnerIn>
+    where
+        Outer: Pid<OuterIn, Over>,
+        Inner: Pid<InnerIn, Over>,
+        InnerIn: ops::Sub<Output = InnerIn> + Copy,
+        Over: Copy,
+    {
+        use super::Configuration;
+
+        let outer =
+            self.outer
+                .step_with_components(outer_error, &config.outer, outer_plant_value, delta_t);
+        let inner_set_point = (config.set_point)(config.outer.clamp_output(outer.output()));
+        let inner = self.inner.step_with_components(
+            inner_set_point - inner_plant_value,
+            &config.inner,
+            inner_plant_value,
+            delta_t,
+        );
+
+        CascadeComponents {
+            outer,
+            inner_set_point,
+            inner,
+        }
+    }
+
+    /// Steps both controllers forward in time
+    ///
+    /// The output is the clamped output of the inner controller.
+    pub fn step<OuterIn, InnerIn, Over>(
+        &mut self,
+        outer_error: OuterIn,
+        config: &CascadeConfiguration<Outer::Configuration, Inner::Configuration, InnerIn>,
+        outer_plant_value: OuterIn,
+        inner_plant_value: InnerIn,
+        delta_t: Over,
+    ) -> Ratio
+    where
+        Outer: Pid<OuterIn, Over>,
+        Inner: Pid<InnerIn, Over>,
+        InnerIn: ops::Sub<Output = InnerIn> + Copy,
+        Over: Copy,
+    {
+        use super::Configuration;
+
+        let components = self.step_with_components(
+            outer_error,
+            config,
+            outer_plant_value,
+            inner_plant_value,
+            delta_t,
+        );
+
+        config.inner.clamp_output(components.inner.output())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pid::{
+        integral_zeroing::{PidConfiguration, PidController, ZeroStrategy},
+        DerivativeSource,
+    };
+    use uom::si::{
+        acceleration::meter_per_second_squared, frequency::hertz, length::meter, ratio::ratio,
+        time::second, velocity::meter_per_second,
+    };
+
+    fn velocity_set_point(output: Ratio) -> Velocity {
+        output * Velocity::new::<meter_per_second>(20.)
+    }
+
+    fn config(
+    ) -> CascadeConfiguration<PidConfiguration<Length>, PidConfiguration<Velocity>, Velocity> {
+        CascadeConfiguration {
+            outer: PidConfiguration {
+                gain_proportion: Ratio::new::<ratio>(1.) / Length::new::<meter>(200.),
+                gain_integral: Ratio::new::<ratio>(0.)
+                    / (Length::new::<meter>(1.) * Time::new::<second>(1.)),
+                gain_derivative: Time::new::<second>(0.) / Length::new::<meter>(1.),
+                output_range: (Ratio::new::<ratio>(-1.), Ratio::new::<ratio>(1.)),
+                derivative_range: (Ratio::new::<ratio>(-1.), Ratio::new::<ratio>(1.)),
+                derivative_source: DerivativeSource::PlantValue,
+                integral_range: None,
+                integral_leak: Frequency::new::<hertz>(0.),
+                tracking_gain: Frequency::new::<hertz>(0.),
+                reference_step: None,
+                zero_strategy: ZeroStrategy::OnSignChange,
+                tolerance: Length::new::<meter>(0.),
+            },
+            inner: PidConfiguration {
+                gain_proportion: Ratio::new::<ratio>(1.) / Velocity::new::<meter_per_second>(5.),
+                gain_integral: Ratio::new::<ratio>(0.1)
+                    / (Velocity::new::<meter_per_second>(5.) * Time::new::<second>(1.)),
+                gain_derivative: Time::new::<second>(0.) / Velocity::new::<meter_per_second>(1.),
+                output_range: (Ratio::new::<ratio>(-1.), Ratio::new::<ratio>(1.)),
+                derivative_range: (Ratio::new::<ratio>(-1.), Ratio::new::<ratio>(1.)),
+                derivative_source: DerivativeSource::PlantValue,
+                integral_range: None,
+                integral_leak: Frequency::new::<hertz>(0.),
+                tracking_gain: Frequency::new::<hertz>(0.),
+                reference_step: None,
+                zero_strategy: ZeroStrategy::OnSignChange,
+                tolerance: Velocity::new::<meter_per_second>(0.),
+            },
+            set_point: velocity_set_point,
+        }
+    }
+
+    #[test]
+    fn cascade_converges_on_two_state_plant() {
+        let config = config();
+        let mut cascade = Cascade::new(
+            PidController::<Length>::default(),
+            PidController::<Velocity>::default(),
+        );
+
+        let target = Length::new::<meter>(1_000.);
+        let max_acceleration = Acceleration::new::<meter_per_second_squared>(3.);
+        let drag = Frequency::new::<hertz>(0.05);
+        let delta_t = Time::new::<second>(0.1);
+
+        let mut position = Length::new::<meter>(0.);
+        let mut velocity = Velocity::new::<meter_per_second>(0.);
+
+        for _ in 0..6_000 {
+            let command = cascade.step(target - position, &config, position, velocity, delta_t);
+            let acceleration = command * max_acceleration - drag * velocity;
+            velocity += acceleration * delta_t;
+            position += velocity * delta_t;
+        }
+
+        assert!(
+            (target - position).abs() < Length::new::<meter>(1.),
+            "position {:?} did not converge on {:?}",
+            position,
+            target
+        );
+        assert!(
+            velocity.abs() < Velocity::new::<meter_per_second>(0.1),
+            "velocity {:?} did not settle",
+            velocity
+        );
+    }
+
+    #[test]
+    fn outer_output_commands_inner_set_point() {
+        let config = config();
+        let mut cascade = Cascade::new(
+            PidController::<Length>::default(),
+            PidController::<Velocity>::default(),
+        );
+
+        let components = cascade.step_with_components(
+            Length::new::<meter>(100.),
+            &config,
+            Length::new::<meter>(0.),
+            Velocity::new::<meter_per_second>(0.),
+            Time::new::<second>(1.),
+        );
+
+        assert_eq!(
+            velocity_set_point(components.outer.output()),
+            components.inner_set_point
+        );
+        assert_eq!(
+            components.inner_set_point,
+            cascade.inner.prior_error(),
+            "inner controller should see the commanded set point as error"
+        );
+    }
+}
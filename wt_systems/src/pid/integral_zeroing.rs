@@ -1,10 +1,40 @@
 //! A PID implementation that removes the integral component on error sign changes
 
-use super::{Derivative, ErrorRate, Integral, PidComponents, Proportion, RetainedError};
+use super::{
+    Derivative, DerivativeSource, ErrorRate, Integral, PidComponents, Proportion, Rate,
+    RetainedError,
+};
 use serde::{Deserialize, Serialize};
 use std::{fmt, ops};
 use uom::num_traits::{clamp, zero, Zero};
 use uom::si::f64::*;
+use uom::si::force::poundal;
+use uom::si::frequency::hertz;
+use uom::si::momentum::pound_foot_per_second;
+use uom::si::ratio::{percent, ratio};
+use uom::si::time::second;
+
+/// The condition under which the retained error (momentum) is discarded
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ZeroStrategy {
+    /// Discards momentum when the error changes sign
+    ///
+    /// This is the default unless the `non-zeroing` feature is enabled.
+    #[cfg_attr(not(feature = "non-zeroing"), default)]
+    OnSignChange,
+
+    /// Discards momentum when the error comes within the tolerance of the
+    /// target, keeping it through any overshoot beyond the tolerance band
+    OnTolerance,
+
+    /// Never discards momentum
+    ///
+    /// Momentum may still be shed by the integral leak or back-calculation.
+    /// This is the default when the `non-zeroing` feature is enabled.
+    #[cfg_attr(feature = "non-zeroing", default)]
+    Never,
+}
 
 /// Configuration for a PID controller
 ///
@@ -14,18 +44,25 @@ use uom::si::f64::*;
 /// are only for demonstration and are not assured to be convergent of stable.
 ///
 /// ```
-/// use wt_systems::pid::integral_zeroing::PidConfiguration;
-/// use uom::si::f64::{Velocity, Ratio, Time};
+/// use wt_systems::pid::{DerivativeSource, integral_zeroing::{PidConfiguration, ZeroStrategy}};
+/// use uom::si::f64::{Frequency, Velocity, Ratio, Time};
+/// use uom::si::frequency::hertz;
 /// use uom::si::velocity::meter_per_second;
 /// use uom::si::ratio::{basis_point, ratio};
 /// use uom::si::time::second;
 ///
-/// let config = PidConfiguration {
+/// let config: PidConfiguration<Velocity> = PidConfiguration {
 ///     gain_proportion: Ratio::new::<basis_point>(1.) / Velocity::new::<meter_per_second>(10.),
 ///     gain_integral: Ratio::new::<basis_point>(10.) / (Velocity::new::<meter_per_second>(3.) * Time::new::<second>(1.)),
 ///     gain_derivative: Time::new::<second>(1.0) / Velocity::new::<meter_per_second>(0.2),
 ///     output_range: (Ratio::new::<ratio>(-1.), Ratio::new::<ratio>(1.)),
 ///     derivative_range: (Ratio::new::<ratio>(-3.), Ratio::new::<ratio>(3.)),
+///     derivative_source: DerivativeSource::Error,
+///     integral_range: None,
+///     integral_leak: Frequency::new::<hertz>(0.),
+///     tracking_gain: Frequency::new::<hertz>(0.),
+///     reference_step: None,
+///     zero_strategy: ZeroStrategy::OnSignChange,
 ///     tolerance: Velocity::new::<meter_per_second>(0.5),
 /// };
 /// ```
@@ -33,14 +70,14 @@ use uom::si::f64::*;
 #[cfg_attr(
     feature = "serde",
     serde(bound(
-        serialize = "In: Serialize, Proportion<Ratio, In>: Serialize, Integral<Ratio, In, Time>: Serialize, Derivative<Time, In>: Serialize",
-        deserialize = "for<'d> In: Deserialize<'d>, for<'d> Proportion<Ratio, In>: Deserialize<'d>, for<'d> Integral<Ratio, In, Time>: Deserialize<'d>, for<'d> Derivative<Time, In>: Deserialize<'d>",
+        serialize = "In: Serialize, Proportion<Ratio, In>: Serialize, Integral<Ratio, In, Over>: Serialize, Derivative<Over, In>: Serialize, RetainedError<Over, In>: Serialize, Over: Serialize, Rate<Over>: Serialize",
+        deserialize = "for<'d> In: Deserialize<'d>, for<'d> Proportion<Ratio, In>: Deserialize<'d>, for<'d> Integral<Ratio, In, Over>: Deserialize<'d>, for<'d> Derivative<Over, In>: Deserialize<'d>, for<'d> RetainedError<Over, In>: Deserialize<'d>, for<'d> Over: Deserialize<'d>, for<'d> Rate<Over>: Deserialize<'d> + Default",
     ))
 )]
-pub struct PidConfiguration<In>
+pub struct PidConfiguration<In, Over = Time>
 where
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
 {
     /// The gain applied to the proportional component of error
     ///
@@ -52,10 +89,10 @@ where
     ///
     /// This is the _momentum_ that the PID gains over the course of continued
     /// errors over time.
-    pub gain_integral: Integral<Ratio, In, Time>,
+    pub gain_integral: Integral<Ratio, In, Over>,
 
     /// The gain applied to the derivative component of error
-    pub gain_derivative: Derivative<Time, In>,
+    pub gain_derivative: Derivative<Over, In>,
 
     /// Output value limits (inclusive)
     ///
@@ -68,6 +105,53 @@ where
     /// clamped to the range specified.
     pub derivative_range: (Ratio, Ratio),
 
+    /// The signal used to calculate the derivative term
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub derivative_source: DerivativeSource,
+
+    /// Retained error limits (inclusive), if the momentum should be bounded
+    ///
+    /// The retained error is clamped to the range specified after each step,
+    /// which bounds the contribution of the integral component to the output.
+    /// Without limits, momentum is only shed according to the zero strategy,
+    /// the integral leak, and back-calculation.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub integral_range: Option<(RetainedError<Over, In>, RetainedError<Over, In>)>,
+
+    /// Rate at which the retained error decays toward zero
+    ///
+    /// On each step, the retained error is scaled by `e^(-leak * delta_t)`
+    /// before any new error is accumulated. This provides an alternative to
+    /// hard clamping for shedding accumulated momentum. A leak of zero retains
+    /// all accumulated error.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub integral_leak: Rate<Over>,
+
+    /// Gain applied when feeding saturation back into the retained error
+    ///
+    /// When the combined output falls outside of the output range, the
+    /// difference between the clamped and unclamped output, scaled by this
+    /// gain and the step duration, is fed back into the integral term
+    /// (back-calculation). This unwinds accumulated momentum while the output
+    /// is saturated rather than relying solely on hard limits. A gain of zero
+    /// disables back-calculation.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tracking_gain: Rate<Over>,
+
+    /// The step duration at which the gains were tuned, if compensating for
+    /// varying step durations
+    ///
+    /// When the controller output is applied as an increment on each step,
+    /// the effective gains scale with the number of steps taken per second.
+    /// When set, each contribution is scaled by `delta_t / reference_step`
+    /// so that tuning done at one frame rate behaves the same at another.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reference_step: Option<Over>,
+
+    /// The condition under which accumulated momentum is discarded
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub zero_strategy: ZeroStrategy,
+
     /// Tolerance for deviations from the target value.
     ///
     /// When a value is within `tolerance` of the target value, the PID will
@@ -79,14 +163,17 @@ where
     pub tolerance: In,
 }
 
-impl<In> Clone for PidConfiguration<In>
+impl<In, Over> Clone for PidConfiguration<In, Over>
 where
+    Over: Clone,
+    Rate<Over>: Clone,
     In: Clone,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>> + Clone,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over> + Clone,
+    Over: ops::Mul<In> + ops::Div<In>,
     Proportion<Ratio, In>: Clone,
-    Integral<Ratio, In, Time>: Clone,
-    Derivative<Time, In>: Clone,
+    Integral<Ratio, In, Over>: Clone,
+    Derivative<Over, In>: Clone,
+    RetainedError<Over, In>: Clone,
 {
     #[inline(always)]
     fn clone(&self) -> Self {
@@ -96,30 +183,42 @@ where
             gain_derivative: self.gain_derivative.clone(),
             output_range: self.output_range,
             derivative_range: self.derivative_range,
+            derivative_source: self.derivative_source,
+            integral_range: self.integral_range.clone(),
+            integral_leak: self.integral_leak.clone(),
+            tracking_gain: self.tracking_gain.clone(),
+            reference_step: self.reference_step.clone(),
+            zero_strategy: self.zero_strategy,
             tolerance: self.tolerance.clone(),
         }
     }
 }
 
-impl<In> Copy for PidConfiguration<In>
+impl<In, Over> Copy for PidConfiguration<In, Over>
 where
+    Over: Copy,
+    Rate<Over>: Copy,
     In: Copy,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>> + Copy,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over> + Copy,
+    Over: ops::Mul<In> + ops::Div<In>,
     Proportion<Ratio, In>: Copy,
-    Integral<Ratio, In, Time>: Copy,
-    Derivative<Time, In>: Copy,
+    Integral<Ratio, In, Over>: Copy,
+    Derivative<Over, In>: Copy,
+    RetainedError<Over, In>: Copy,
 {
 }
 
-impl<In> PartialEq for PidConfiguration<In>
+impl<In, Over> PartialEq for PidConfiguration<In, Over>
 where
+    Over: PartialEq,
+    Rate<Over>: PartialEq,
     In: PartialEq,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
     Proportion<Ratio, In>: PartialEq,
-    Integral<Ratio, In, Time>: PartialEq,
-    Derivative<Time, In>: PartialEq,
+    Integral<Ratio, In, Over>: PartialEq,
+    Derivative<Over, In>: PartialEq,
+    RetainedError<Over, In>: PartialEq,
 {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
@@ -128,17 +227,26 @@ where
             && self.gain_proportion == other.gain_proportion
             && self.output_range == other.output_range
             && self.derivative_range == other.derivative_range
+            && self.derivative_source == other.derivative_source
+            && self.integral_range == other.integral_range
+            && self.integral_leak == other.integral_leak
+            && self.tracking_gain == other.tracking_gain
+            && self.reference_step == other.reference_step
+            && self.zero_strategy == other.zero_strategy
     }
 }
 
-impl<In> fmt::Debug for PidConfiguration<In>
+impl<In, Over> fmt::Debug for PidConfiguration<In, Over>
 where
+    Over: fmt::Debug,
+    Rate<Over>: fmt::Debug,
     In: fmt::Debug,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>> + fmt::Debug,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over> + fmt::Debug,
+    Over: ops::Mul<In> + ops::Div<In>,
     Proportion<Ratio, In>: fmt::Debug,
-    Integral<Ratio, In, Time>: fmt::Debug,
-    Derivative<Time, In>: fmt::Debug,
+    Integral<Ratio, In, Over>: fmt::Debug,
+    Derivative<Over, In>: fmt::Debug,
+    RetainedError<Over, In>: fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("PidConfiguration")
@@ -147,23 +255,87 @@ where
             .field("gain_derivative", &self.gain_derivative)
             .field(
                 "output_range",
-                &format_args!("[{:?}, {:?}]", &self.output_range.0, &self.output_range.1),
+                &format_args!("[{:?}, {:?}]", self.output_range.0, self.output_range.1),
             )
             .field(
                 "derivative_range",
                 &format_args!(
                     "[{:?}, {:?}]",
-                    &self.derivative_range.0, &self.derivative_range.1
+                    self.derivative_range.0, self.derivative_range.1
                 ),
             )
+            .field("derivative_source", &self.derivative_source)
+            .field("integral_range", &self.integral_range)
+            .field("integral_leak", &self.integral_leak)
+            .field("tracking_gain", &self.tracking_gain)
+            .field("reference_step", &self.reference_step)
+            .field("zero_strategy", &self.zero_strategy)
             .finish()
     }
 }
 
-impl<In> super::Configuration for PidConfiguration<In>
+/// Summarizes a force PID configuration in human terms
+///
+/// Gains are given as the percentage of output commanded per 1000 pdl of
+/// error, per 1000 pdl·s of accumulated error, and per 1000 pdl/s of change in
+/// error. Retained error limits, leak, tracking gain, and tolerance are only
+/// listed when set.
+impl fmt::Display for PidConfiguration<Force> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Rounds away noise from inexact unit conversion factors
+        fn pct(value: Ratio) -> f64 {
+            (value.get::<percent>() * 1e6).round() / 1e6
+        }
+
+        let kilopoundal = Force::new::<poundal>(1_000.);
+        let one_second = Time::new::<second>(1.);
+        write!(
+            f,
+            "Kp {} %/1000 pdl, Ki {} %/(1000 pdl·s), Kd {} %/(1000 pdl/s), output [{}, {}] %, derivative [{}, {}] %",
+            pct(self.gain_proportion * kilopoundal),
+            pct(self.gain_integral * (kilopoundal * one_second)),
+            pct(self.gain_derivative * (kilopoundal / one_second)),
+            pct(self.output_range.0),
+            pct(self.output_range.1),
+            pct(self.derivative_range.0),
+            pct(self.derivative_range.1),
+        )?;
+        if self.derivative_source == DerivativeSource::PlantValue {
+            write!(f, ", derivative on plant value")?;
+        }
+        if let Some((min, max)) = self.integral_range {
+            write!(
+                f,
+                ", retained error [{}, {}] pdl·s",
+                min.get::<pound_foot_per_second>(),
+                max.get::<pound_foot_per_second>()
+            )?;
+        }
+        if !self.integral_leak.is_zero() {
+            write!(f, ", leak {} Hz", self.integral_leak.get::<hertz>())?;
+        }
+        if !self.tracking_gain.is_zero() {
+            write!(f, ", tracking {} Hz", self.tracking_gain.get::<hertz>())?;
+        }
+        if let Some(reference_step) = self.reference_step {
+            write!(f, ", reference step {} s", reference_step.get::<second>())?;
+        }
+        match self.zero_strategy {
+            ZeroStrategy::OnSignChange => {}
+            ZeroStrategy::OnTolerance => write!(f, ", zeroing within tolerance")?,
+            ZeroStrategy::Never => write!(f, ", no zeroing")?,
+        }
+        if !self.tolerance.is_zero() {
+            write!(f, ", tolerance {} pdl", self.tolerance.get::<poundal>())?;
+        }
+        Ok(())
+    }
+}
+
+impl<In, Over> super::Configuration for PidConfiguration<In, Over>
 where
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
 {
     #[inline(always)]
     fn clamp_output(&self, output: Ratio) -> Ratio {
@@ -179,8 +351,9 @@ where
 /// are only for demonstration and are not assured to be convergent of stable.
 ///
 /// ```
-/// use wt_systems::pid::{Pid, integral_zeroing::{PidConfiguration, PidController}};
-/// use uom::si::f64::{Velocity, Ratio, Time};
+/// use wt_systems::pid::{DerivativeSource, Pid, integral_zeroing::{PidConfiguration, PidController, ZeroStrategy}};
+/// use uom::si::f64::{Frequency, Velocity, Ratio, Time};
+/// use uom::si::frequency::hertz;
 /// use uom::si::velocity::meter_per_second;
 /// use uom::si::ratio::{basis_point, ratio};
 /// use uom::si::time::second;
@@ -191,6 +364,12 @@ where
 ///     gain_derivative: Time::new::<second>(1.0) / Velocity::new::<meter_per_second>(0.2),
 ///     output_range: (Ratio::new::<ratio>(-1.), Ratio::new::<ratio>(1.)),
 ///     derivative_range: (Ratio::new::<ratio>(-3.), Ratio::new::<ratio>(3.)),
+///     derivative_source: DerivativeSource::Error,
+///     integral_range: None,
+///     integral_leak: Frequency::new::<hertz>(0.),
+///     tracking_gain: Frequency::new::<hertz>(0.),
+///     reference_step: None,
+///     zero_strategy: ZeroStrategy::OnSignChange,
 ///     tolerance: Velocity::new::<meter_per_second>(0.5),
 /// };
 ///
@@ -207,101 +386,116 @@ where
 #[cfg_attr(
     feature = "serde",
     serde(bound(
-        serialize = "In: Serialize, RetainedError<Time, In>: Serialize",
-        deserialize = "for<'d> In: Deserialize<'d>, for<'d> RetainedError<Time, In>: Deserialize<'d>",
+        serialize = "In: Serialize, RetainedError<Over, In>: Serialize",
+        deserialize = "for<'d> In: Deserialize<'d> + Default, for<'d> RetainedError<Over, In>: Deserialize<'d>",
     ))
 )]
-pub struct PidController<In>
+pub struct PidController<In, Over = Time>
 where
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
 {
     /// Error identified during the last step
     pub prior_error: In,
 
+    /// Plant value observed during the last step
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub prior_plant_value: In,
+
     /// Retained error (momentum) due to accumulated errors over time
-    pub retained_error: RetainedError<Time, In>,
+    pub retained_error: RetainedError<Over, In>,
 }
 
-impl<In> Clone for PidController<In>
+impl<In, Over> Clone for PidController<In, Over>
 where
     In: Clone,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
-    RetainedError<Time, In>: Clone,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
+    RetainedError<Over, In>: Clone,
 {
     #[inline(always)]
     fn clone(&self) -> Self {
         Self {
             prior_error: self.prior_error.clone(),
+            prior_plant_value: self.prior_plant_value.clone(),
             retained_error: self.retained_error.clone(),
         }
     }
 }
 
-impl<In> Copy for PidController<In>
+impl<In, Over> Copy for PidController<In, Over>
 where
     In: Copy,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
-    RetainedError<Time, In>: Copy,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
+    RetainedError<Over, In>: Copy,
 {
 }
 
-impl<In> fmt::Debug for PidController<In>
+impl<In, Over> fmt::Debug for PidController<In, Over>
 where
     In: fmt::Debug,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
-    RetainedError<Time, In>: fmt::Debug,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
+    RetainedError<Over, In>: fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("PidController")
             .field("prior_error", &self.prior_error)
+            .field("prior_plant_value", &self.prior_plant_value)
             .field("retained_error", &self.retained_error)
             .finish()
     }
 }
 
-impl<In> Default for PidController<In>
+impl<In, Over> Default for PidController<In, Over>
 where
     In: Zero,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
-    RetainedError<Time, In>: Zero,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
+    RetainedError<Over, In>: Zero,
 {
     #[inline]
     fn default() -> Self {
         Self {
             prior_error: zero(),
+            prior_plant_value: zero(),
             retained_error: zero(),
         }
     }
 }
 
-impl<In> PartialEq for PidController<In>
+impl<In, Over> PartialEq for PidController<In, Over>
 where
     In: PartialEq,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
-    RetainedError<Time, In>: PartialEq,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
+    RetainedError<Over, In>: PartialEq,
 {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
-        self.prior_error == other.prior_error && self.retained_error == other.retained_error
+        self.prior_error == other.prior_error
+            && self.prior_plant_value == other.prior_plant_value
+            && self.retained_error == other.retained_error
     }
 }
 
-impl<In> PidController<In>
+impl<In, Over> PidController<In, Over>
 where
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
 {
     /// Constructs a PID controller with existing values
+    ///
+    /// The prior plant value is assumed to be zero.
     #[inline]
-    pub fn with_initial(prior_error: In, retained_error: RetainedError<Time, In>) -> Self {
+    pub fn with_initial(prior_error: In, retained_error: RetainedError<Over, In>) -> Self
+    where
+        In: Zero,
+    {
         Self {
             prior_error,
+            prior_plant_value: zero(),
             retained_error,
         }
     }
@@ -314,16 +508,16 @@ where
 
     /// Obtains a reference to the current retained error value
     #[inline]
-    pub fn retained_error_ref(&self) -> &RetainedError<Time, In> {
+    pub fn retained_error_ref(&self) -> &RetainedError<Over, In> {
         &self.retained_error
     }
 }
 
-impl<In> PidController<In>
+impl<In, Over> PidController<In, Over>
 where
     In: Clone,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
 {
     /// Gets the current prior error value
     #[inline]
@@ -332,23 +526,23 @@ where
     }
 }
 
-impl<In> PidController<In>
+impl<In, Over> PidController<In, Over>
 where
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
-    RetainedError<Time, In>: Clone,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
+    RetainedError<Over, In>: Clone,
 {
     /// Gets the current retained error value
     #[inline]
-    pub fn retained_error(&self) -> RetainedError<Time, In> {
+    pub fn retained_error(&self) -> RetainedError<Over, In> {
         self.retained_error.clone()
     }
 }
 
-impl<In> PidController<In>
+impl<In, Over> PidController<In, Over>
 where
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
     Self: Default,
 {
     /// Resets the PID controller to a zeroed state
@@ -361,49 +555,90 @@ where
     }
 }
 
-impl<In> super::Pid<In> for PidController<In>
+impl<In, Over> PidController<In, Over>
+where
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
+    RetainedError<Over, In>: ops::Mul<Integral<Ratio, In, Over>, Output = Ratio> + Copy,
+    Integral<Ratio, In, Over>: Copy,
+{
+    /// The integral term that the retained error contributes to the output
+    /// under `config`
+    ///
+    /// Equivalent to multiplying the retained error by the integral gain.
+    #[inline]
+    pub fn integral_contribution(&self, config: &PidConfiguration<In, Over>) -> Ratio {
+        self.retained_error * config.gain_integral
+    }
+}
+
+impl PidController<Force> {
+    /// Expresses the retained error as a force for display purposes
+    ///
+    /// The retained error is accumulated as momentum (force over time). For
+    /// debugging and logging, this value is presented as the force that
+    /// would accumulate the same momentum over a single second.
+    #[inline]
+    pub fn retained_error_as_force(&self) -> Force {
+        self.retained_error / Time::new::<second>(1.)
+    }
+}
+
+impl<In, Over> super::Pid<In, Over> for PidController<In, Over>
 where
-    In: PartialOrd + Zero + ops::Neg<Output = In> + ops::Sub<Output = In> + ops::Div<Time> + Copy,
-    Ratio: Zero + PartialOrd + ops::Div<In> + ops::Div<RetainedError<Time, In>> + Copy,
+    In: PartialOrd + Zero + ops::Neg<Output = In> + ops::Sub<Output = In> + ops::Div<Over> + Copy,
+    Ratio: Zero
+        + PartialOrd
+        + ops::Div<In>
+        + ops::Div<RetainedError<Over, In>>
+        + ops::Div<Over>
+        + ops::Div<Integral<Ratio, In, Over>, Output = RetainedError<Over, In>>
+        + Copy,
     Proportion<Ratio, In>: ops::Mul<In, Output = Ratio> + Copy,
-    Integral<Ratio, In, Time>: Copy,
-    Time: ops::Mul<In> + ops::Div<In> + Copy,
-    Derivative<Time, In>: ops::Mul<ErrorRate<In, Time>, Output = Ratio> + Copy,
-    RetainedError<Time, In>: Zero
-        + ops::Div<f64, Output = RetainedError<Time, In>>
-        + ops::Mul<Integral<Ratio, In, Time>, Output = Ratio>
+    Integral<Ratio, In, Over>: Zero + PartialEq + Copy,
+    Over: ops::Mul<In> + ops::Div<In> + ops::Div<Over, Output = Ratio> + Copy,
+    Rate<Over>: ops::Mul<Over, Output = Ratio> + Copy,
+    Derivative<Over, In>: ops::Mul<ErrorRate<In, Over>, Output = Ratio> + Copy,
+    RetainedError<Over, In>: Zero
+        + PartialOrd
+        + ops::Div<f64, Output = RetainedError<Over, In>>
+        + ops::Mul<Integral<Ratio, In, Over>, Output = Ratio>
         + Copy,
-    ErrorRate<In, Time>: ops::Mul<ErrorRate<In, Time>>,
+    ErrorRate<In, Over>: ops::Mul<ErrorRate<In, Over>>,
 {
-    type Configuration = PidConfiguration<In>;
+    type Configuration = PidConfiguration<In, Over>;
 
     fn step_with_components(
         &mut self,
         error: In,
         config: &Self::Configuration,
-        _plant_value: In,
-        delta_t: Time,
+        plant_value: In,
+        delta_t: Over,
     ) -> PidComponents {
         // Proportional
         let proportional: Ratio = config.gain_proportion * error;
 
         // Integral
-        // If the new error has changed signs, remove momentum
-        #[cfg(not(feature = "non-zeroing"))]
-        let retained_error: RetainedError<Time, In> = if (error > zero())
-            != (self.prior_error >= zero())
-        {
+        let leaked_retained_error: RetainedError<Over, In> =
+            self.retained_error / (config.integral_leak * delta_t).get::<ratio>().exp();
+        let zero_momentum = match config.zero_strategy {
+            ZeroStrategy::OnSignChange => (error > zero()) != (self.prior_error >= zero()),
+            ZeroStrategy::OnTolerance => error <= config.tolerance && error >= -config.tolerance,
+            ZeroStrategy::Never => false,
+        };
+        let retained_error: RetainedError<Over, In> = if zero_momentum {
             zero()
         } else {
-            self.retained_error + (delta_t * error) + (delta_t * (error - self.prior_error) / 2.)
+            leaked_retained_error + (delta_t * error) + (delta_t * (error - self.prior_error) / 2.)
         };
-        #[cfg(feature = "non-zeroing")]
-        let retained_error: RetainedError<Time, In> =
-            self.retained_error + (delta_t * error) + (delta_t * (error - self.prior_error) / 2.);
+        let retained_error = limit_retained_error(retained_error, config);
         let integral: Ratio = retained_error * config.gain_integral;
 
         // Derivative
-        let error_over_time: ErrorRate<In, Time> = (error - self.prior_error) / delta_t;
+        let error_over_time: ErrorRate<In, Over> = match config.derivative_source {
+            DerivativeSource::Error => (error - self.prior_error) / delta_t,
+            DerivativeSource::PlantValue => (self.prior_plant_value - plant_value) / delta_t,
+        };
         let raw_gained_derivative: Ratio = config.gain_derivative * error_over_time;
         let derivative: Ratio = clamp(
             raw_gained_derivative,
@@ -411,9 +646,33 @@ where
             config.derivative_range.1,
         );
 
+        // Sample-time compensation
+        let (proportional, integral, derivative) = match config.reference_step {
+            Some(reference_step) => {
+                let scale = (delta_t / reference_step).get::<ratio>();
+                (proportional * scale, integral * scale, derivative * scale)
+            }
+            None => (proportional, integral, derivative),
+        };
+
         // println!("Output: {} ({}): Derivative: {} ({}), Integral: {}, proportion: {}", output.into_format_args(ratio, uom::fmt::DisplayStyle::Abbreviation), raw_output.into_format_args(ratio, uom::fmt::DisplayStyle::Abbreviation), gained_derivative.into_format_args(ratio, uom::fmt::DisplayStyle::Abbreviation), raw_gained_derivative.into_format_args(ratio, uom::fmt::DisplayStyle::Abbreviation), gained_integral.into_format_args(ratio, uom::fmt::DisplayStyle::Abbreviation), gained_error.into_format_args(ratio, uom::fmt::DisplayStyle::Abbreviation));
 
+        // Anti-windup
+        let raw_output = proportional + integral + derivative;
+        let correction = super::back_calculation(
+            raw_output,
+            super::Configuration::clamp_output(config, raw_output),
+            config.tracking_gain,
+            delta_t,
+        );
+        let retained_error = if correction != zero::<Ratio>() && config.gain_integral != zero() {
+            limit_retained_error(retained_error + correction / config.gain_integral, config)
+        } else {
+            retained_error
+        };
+
         self.prior_error = error;
+        self.prior_plant_value = plant_value;
         self.retained_error = retained_error;
         PidComponents {
             proportional,
@@ -423,15 +682,106 @@ where
     }
 }
 
+/// Clamps the retained error to the integral range, if configured
+fn limit_retained_error<In, Over>(
+    retained_error: RetainedError<Over, In>,
+    config: &PidConfiguration<In, Over>,
+) -> RetainedError<Over, In>
+where
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
+    RetainedError<Over, In>: PartialOrd + Copy,
+{
+    match config.integral_range {
+        Some((min, max)) => clamp(retained_error, min, max),
+        None => retained_error,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::pid::Pid;
-    use uom::si::f64::{Ratio, Time, Velocity};
-    use uom::si::ratio::ratio;
+    use crate::testing;
+    use uom::si::area::square_meter;
+    use uom::si::f64::{Frequency, Length, Ratio, Time, Velocity};
+    use uom::si::force::poundal;
+    use uom::si::frequency::hertz;
+    use uom::si::length::meter;
+    use uom::si::momentum::pound_foot_per_second;
+    use uom::si::ratio::{percent, ratio};
     use uom::si::time::second;
     use uom::si::velocity::meter_per_second;
 
+    #[test]
+    fn integral_contribution_applies_integral_gain_to_retained_error() {
+        let config: PidConfiguration<Velocity> = PidConfiguration {
+            gain_proportion: Ratio::new::<ratio>(0.) / Velocity::new::<meter_per_second>(1.),
+            gain_integral: Ratio::new::<ratio>(0.01)
+                / (Velocity::new::<meter_per_second>(1.) * Time::new::<second>(1.)),
+            gain_derivative: Time::new::<second>(0.) / Velocity::new::<meter_per_second>(1.),
+            output_range: (Ratio::new::<ratio>(-1.), Ratio::new::<ratio>(1.)),
+            derivative_range: (Ratio::new::<ratio>(-1.), Ratio::new::<ratio>(1.)),
+            derivative_source: DerivativeSource::Error,
+            integral_range: None,
+            integral_leak: Frequency::new::<hertz>(0.),
+            tracking_gain: Frequency::new::<hertz>(0.),
+            reference_step: None,
+            zero_strategy: ZeroStrategy::OnSignChange,
+            tolerance: Velocity::new::<meter_per_second>(0.5),
+        };
+        let mut pid = PidController::<Velocity>::default();
+
+        let components = pid.step_with_components(
+            Velocity::new::<meter_per_second>(4.),
+            &config,
+            Velocity::new::<meter_per_second>(0.),
+            Time::new::<second>(0.5),
+        );
+
+        assert_eq!(
+            config.gain_integral * pid.retained_error(),
+            pid.integral_contribution(&config)
+        );
+        assert_eq!(components.integral, pid.integral_contribution(&config));
+        assert!(pid.integral_contribution(&config) > Ratio::new::<ratio>(0.));
+    }
+
+    #[test]
+    fn integral_range_bounds_retained_error() {
+        let limit = Velocity::new::<meter_per_second>(2.) * Time::new::<second>(1.);
+        let config: PidConfiguration<Velocity> = PidConfiguration {
+            gain_proportion: Ratio::new::<ratio>(0.) / Velocity::new::<meter_per_second>(1.),
+            gain_integral: Ratio::new::<ratio>(0.01)
+                / (Velocity::new::<meter_per_second>(1.) * Time::new::<second>(1.)),
+            gain_derivative: Time::new::<second>(0.) / Velocity::new::<meter_per_second>(1.),
+            output_range: (Ratio::new::<ratio>(-1.), Ratio::new::<ratio>(1.)),
+            derivative_range: (Ratio::new::<ratio>(-1.), Ratio::new::<ratio>(1.)),
+            derivative_source: DerivativeSource::Error,
+            integral_range: Some((-limit, limit)),
+            integral_leak: Frequency::new::<hertz>(0.),
+            tracking_gain: Frequency::new::<hertz>(0.),
+            reference_step: None,
+            zero_strategy: ZeroStrategy::OnSignChange,
+            tolerance: Velocity::new::<meter_per_second>(0.),
+        };
+
+        for &error in &[4., -4.] {
+            let mut pid = PidController::<Velocity>::default();
+            for _ in 0..10 {
+                let components = pid.step_with_components(
+                    Velocity::new::<meter_per_second>(error),
+                    &config,
+                    Velocity::new::<meter_per_second>(0.),
+                    Time::new::<second>(0.5),
+                );
+                assert!(pid.retained_error().abs() <= limit);
+                assert!(components.integral.abs() <= config.gain_integral * limit);
+            }
+            assert_eq!(limit * error.signum(), pid.retained_error());
+        }
+    }
+
     #[test]
     fn test_valid_with_uom_1() {
         let config = PidConfiguration {
@@ -441,6 +791,12 @@ mod tests {
             gain_derivative: Time::new::<second>(1.0) / Velocity::new::<meter_per_second>(0.2),
             output_range: (Ratio::new::<ratio>(-1_000.), Ratio::new::<ratio>(1_000.)),
             derivative_range: (Ratio::new::<ratio>(-1_000.), Ratio::new::<ratio>(1_000.)),
+            derivative_source: DerivativeSource::Error,
+            integral_range: None,
+            integral_leak: Frequency::new::<hertz>(0.),
+            tracking_gain: Frequency::new::<hertz>(0.),
+            reference_step: None,
+            zero_strategy: ZeroStrategy::OnSignChange,
             tolerance: Velocity::new::<meter_per_second>(0.5),
         };
 
@@ -453,6 +809,306 @@ mod tests {
             Time::new::<second>(5.),
         );
     }
+
+    fn derivative_test_config(source: DerivativeSource) -> PidConfiguration<Velocity> {
+        PidConfiguration {
+            gain_proportion: Ratio::new::<ratio>(0.) / Velocity::new::<meter_per_second>(1.),
+            gain_integral: Ratio::new::<ratio>(0.)
+                / (Velocity::new::<meter_per_second>(1.) * Time::new::<second>(1.)),
+            gain_derivative: Time::new::<second>(1.) / Velocity::new::<meter_per_second>(1.),
+            output_range: (Ratio::new::<ratio>(-1_000.), Ratio::new::<ratio>(1_000.)),
+            derivative_range: (Ratio::new::<ratio>(-1_000.), Ratio::new::<ratio>(1_000.)),
+            derivative_source: source,
+            integral_range: None,
+            integral_leak: Frequency::new::<hertz>(0.),
+            tracking_gain: Frequency::new::<hertz>(0.),
+            reference_step: None,
+            zero_strategy: ZeroStrategy::OnSignChange,
+            tolerance: Velocity::new::<meter_per_second>(0.),
+        }
+    }
+
+    /// Steps a controller through a set point step from 0 to 10 m/s while
+    /// the plant value lags behind, returning the derivative terms
+    fn derivative_on_set_point_step(source: DerivativeSource) -> [f64; 3] {
+        let config = derivative_test_config(source);
+        let mut pid = PidController::default();
+        let delta_t = Time::new::<second>(1.);
+
+        let mut step = |set_point: f64, plant_value: f64| {
+            let set_point = Velocity::new::<meter_per_second>(set_point);
+            let plant_value = Velocity::new::<meter_per_second>(plant_value);
+            pid.step_with_components(set_point - plant_value, &config, plant_value, delta_t)
+                .derivative
+                .get::<ratio>()
+        };
+
+        [step(0., 0.), step(10., 0.), step(10., 4.)]
+    }
+
+    #[test]
+    fn derivative_from_error_kicks_on_set_point_step() {
+        let derivatives = derivative_on_set_point_step(DerivativeSource::Error);
+
+        testing::assert_equal_within_epsilon(0., derivatives[0], 1e-12);
+        testing::assert_equal_within_epsilon(10., derivatives[1], 1e-12);
+        testing::assert_equal_within_epsilon(-4., derivatives[2], 1e-12);
+    }
+
+    #[test]
+    fn derivative_from_plant_value_ignores_set_point_step() {
+        let derivatives = derivative_on_set_point_step(DerivativeSource::PlantValue);
+
+        testing::assert_equal_within_epsilon(0., derivatives[0], 1e-12);
+        testing::assert_equal_within_epsilon(0., derivatives[1], 1e-12);
+        testing::assert_equal_within_epsilon(-4., derivatives[2], 1e-12);
+    }
+
+    #[test]
+    fn back_calculation_unwinds_retained_error_while_saturated() {
+        let plain_config = PidConfiguration {
+            gain_proportion: Ratio::new::<ratio>(0.1) / Velocity::new::<meter_per_second>(1.),
+            gain_integral: Ratio::new::<ratio>(0.1)
+                / (Velocity::new::<meter_per_second>(1.) * Time::new::<second>(1.)),
+            output_range: (Ratio::new::<ratio>(-1.), Ratio::new::<ratio>(1.)),
+            ..derivative_test_config(DerivativeSource::PlantValue)
+        };
+        let tracked_config = PidConfiguration {
+            tracking_gain: Frequency::new::<hertz>(0.5),
+            ..plain_config
+        };
+        let mut plain = PidController::default();
+        let mut tracked = PidController::default();
+
+        for _ in 0..5 {
+            let error = Velocity::new::<meter_per_second>(25.);
+            let plant_value = Velocity::new::<meter_per_second>(0.);
+            let delta_t = Time::new::<second>(1.);
+
+            assert_eq!(
+                plain.step(error, &plain_config, plant_value, delta_t),
+                tracked.step(error, &tracked_config, plant_value, delta_t),
+            );
+        }
+
+        assert!(tracked.retained_error() < plain.retained_error());
+    }
+
+    /// Drives a plant that follows the accumulated controller output toward
+    /// 10 m/s, returning the time taken to settle within 2% of the target
+    fn settling_time(config: &PidConfiguration<Velocity>, rate: f64) -> f64 {
+        let target = Velocity::new::<meter_per_second>(10.);
+        let delta_t = Time::new::<second>(1. / rate);
+        let mut pid = PidController::default();
+        let mut plant_value = Velocity::new::<meter_per_second>(0.);
+
+        for step in 1..=(rate as usize * 20) {
+            let output = pid.step(target - plant_value, config, plant_value, delta_t);
+            plant_value += Velocity::new::<meter_per_second>(10.) * output;
+            if (target - plant_value).abs() < target * 0.02 {
+                return step as f64 / rate;
+            }
+        }
+        panic!("plant did not settle at {} Hz", rate);
+    }
+
+    #[test]
+    fn reference_step_gives_consistent_settling_across_frame_rates() {
+        let uncompensated = PidConfiguration {
+            gain_proportion: Ratio::new::<ratio>(0.005) / Velocity::new::<meter_per_second>(1.),
+            gain_integral: Ratio::new::<ratio>(0.0001)
+                / (Velocity::new::<meter_per_second>(1.) * Time::new::<second>(1.)),
+            gain_derivative: Time::new::<second>(0.0001) / Velocity::new::<meter_per_second>(1.),
+            ..derivative_test_config(DerivativeSource::PlantValue)
+        };
+        let compensated = PidConfiguration {
+            reference_step: Some(Time::new::<second>(1. / 60.)),
+            ..uncompensated
+        };
+
+        let at_60_hz = settling_time(&compensated, 60.);
+        let at_20_hz = settling_time(&compensated, 20.);
+        assert!(
+            (at_60_hz - at_20_hz).abs() <= 0.1,
+            "settled in {} s at 60 Hz but {} s at 20 Hz",
+            at_60_hz,
+            at_20_hz
+        );
+        assert_eq!(at_60_hz, settling_time(&uncompensated, 60.));
+        assert!(settling_time(&uncompensated, 20.) > at_20_hz + 1.);
+    }
+
+    /// Steps any controller over distance travelled rather than time
+    fn step_over_distance<P: Pid<Length, Length>>(
+        pid: &mut P,
+        config: &P::Configuration,
+        error: Length,
+        distance: Length,
+    ) -> PidComponents {
+        pid.step_with_components(error, config, Length::new::<meter>(0.), distance)
+    }
+
+    #[test]
+    fn controller_steps_over_distance() {
+        let config: PidConfiguration<Length, Length> = PidConfiguration {
+            gain_proportion: Ratio::new::<ratio>(0.5) / Length::new::<meter>(1.),
+            gain_integral: Ratio::new::<ratio>(0.01)
+                / (Length::new::<meter>(1.) * Length::new::<meter>(1.)),
+            gain_derivative: Length::new::<meter>(1.) / Length::new::<meter>(1.),
+            output_range: (Ratio::new::<ratio>(-1_000.), Ratio::new::<ratio>(1_000.)),
+            derivative_range: (Ratio::new::<ratio>(-1_000.), Ratio::new::<ratio>(1_000.)),
+            derivative_source: DerivativeSource::Error,
+            integral_range: None,
+            integral_leak: Ratio::new::<ratio>(0.) / Length::new::<meter>(1.),
+            tracking_gain: Ratio::new::<ratio>(0.) / Length::new::<meter>(1.),
+            reference_step: Some(Length::new::<meter>(10.)),
+            zero_strategy: ZeroStrategy::Never,
+            tolerance: Length::new::<meter>(0.),
+        };
+        let mut pid = PidController::<Length, Length>::default();
+
+        let components = step_over_distance(
+            &mut pid,
+            &config,
+            Length::new::<meter>(2.),
+            Length::new::<meter>(10.),
+        );
+
+        // 10 m at 2 m of error, plus the trapezoidal correction from 0 m
+        let retained = 10. * 2. + 10. * 2. / 2.;
+        testing::assert_equal_within_epsilon(
+            retained,
+            pid.retained_error().get::<square_meter>(),
+            1e-12,
+        );
+        testing::assert_equal_within_epsilon(1., components.proportional.get::<ratio>(), 1e-12);
+        testing::assert_equal_within_epsilon(
+            retained * 0.01,
+            components.integral.get::<ratio>(),
+            1e-12,
+        );
+        testing::assert_equal_within_epsilon(0.2, components.derivative.get::<ratio>(), 1e-12);
+    }
+
+    #[test]
+    fn retained_error_as_force_matches_one_second_of_momentum() {
+        let pid = PidController::<_, Time>::with_initial(
+            Force::new::<poundal>(12.),
+            Momentum::new::<pound_foot_per_second>(7.62),
+        );
+
+        let expected = (pid.retained_error / Time::new::<second>(1.)).get::<poundal>();
+
+        testing::assert_equal_within_epsilon(
+            expected,
+            pid.retained_error_as_force().get::<poundal>(),
+            f64::EPSILON,
+        );
+        testing::assert_equal_in_significant_figures(
+            7.62,
+            pid.retained_error_as_force().get::<poundal>(),
+            6,
+        );
+    }
+
+    // Integral-zeroing discards momentum as soon as the error reaches zero, so
+    // a negligible, persistent error stands in for "no new error" here. With
+    // a leak of ln(2) per second, the retained error halves every second.
+    crate::pid_step_tests! {
+        name: integral_leak_decays_retained_error,
+        config: PidConfiguration {
+            gain_proportion: Ratio::new::<percent>(0.) / Force::new::<poundal>(1.),
+            gain_integral: Ratio::new::<percent>(1.) / Momentum::new::<pound_foot_per_second>(1.),
+            gain_derivative: Time::new::<second>(0.) / Force::new::<poundal>(1.),
+            output_range: (Ratio::new::<percent>(-1_000.), Ratio::new::<percent>(1_000.)),
+            derivative_range: (Ratio::new::<percent>(-1_000.), Ratio::new::<percent>(1_000.)),
+            derivative_source: DerivativeSource::Error,
+            integral_range: None,
+            integral_leak: Frequency::new::<hertz>(std::f64::consts::LN_2),
+            tracking_gain: Frequency::new::<hertz>(0.),
+            reference_step: None,
+            zero_strategy: ZeroStrategy::OnSignChange,
+            tolerance: Force::new::<poundal>(0.),
+        },
+        initial: PidController::<_, Time>::with_initial(
+            -Force::new::<poundal>(0.001),
+            -Momentum::new::<pound_foot_per_second>(100.),
+        ),
+        steps: [
+            {
+                inputs: (zero::<Force>(), Force::new::<poundal>(0.001), Time::new::<second>(1.)),
+                expect: (Ratio::new::<percent>(-50.001), -Momentum::new::<pound_foot_per_second>(50.001))
+            },
+            {
+                inputs: (zero::<Force>(), Force::new::<poundal>(0.001), Time::new::<second>(1.)),
+                expect: (Ratio::new::<percent>(-25.0015), -Momentum::new::<pound_foot_per_second>(25.0015))
+            },
+            {
+                inputs: (zero::<Force>(), Force::new::<poundal>(0.001), Time::new::<second>(1.)),
+                expect: (Ratio::new::<percent>(-12.50175), -Momentum::new::<pound_foot_per_second>(12.50175))
+            },
+            {
+                inputs: (zero::<Force>(), Force::new::<poundal>(0.001), Time::new::<second>(1.)),
+                expect: (Ratio::new::<percent>(-6.251875), -Momentum::new::<pound_foot_per_second>(6.251875))
+            },
+        ],
+        tolerances: {
+            output: Ratio::new::<percent>(0.00001),
+            retained_error: Momentum::new::<pound_foot_per_second>(0.00001),
+        },
+    }
+
+    // Each zeroing strategy is run through the same error trace: an overshoot
+    // well past the 1 pdl tolerance, followed by an approach to within the
+    // tolerance on the same side. The output is 1% per pdl·s of momentum, so
+    // it tracks the retained error.
+    macro_rules! zero_strategy_step_tests {
+        ($($name:ident: $strategy:expr => [$first:expr, $second:expr],)*) => {
+            $(
+                crate::pid_step_tests! {
+                    name: $name,
+                    config: PidConfiguration {
+                        gain_proportion: Ratio::new::<percent>(0.) / Force::new::<poundal>(1.),
+                        gain_integral: Ratio::new::<percent>(1.) / Momentum::new::<pound_foot_per_second>(1.),
+                        gain_derivative: Time::new::<second>(0.) / Force::new::<poundal>(1.),
+                        output_range: (Ratio::new::<percent>(-1_000.), Ratio::new::<percent>(1_000.)),
+                        derivative_range: (Ratio::new::<percent>(-1_000.), Ratio::new::<percent>(1_000.)),
+                        derivative_source: DerivativeSource::Error,
+                        integral_range: None,
+                        integral_leak: Frequency::new::<hertz>(0.),
+                        tracking_gain: Frequency::new::<hertz>(0.),
+                        reference_step: None,
+                        zero_strategy: $strategy,
+                        tolerance: Force::new::<poundal>(1.),
+                    },
+                    initial: PidController::<_, Time>::with_initial(
+                        Force::new::<poundal>(2.),
+                        Momentum::new::<pound_foot_per_second>(10.),
+                    ),
+                    steps: [
+                        {
+                            inputs: (zero::<Force>(), Force::new::<poundal>(5.), Time::new::<second>(1.)),
+                            expect: (Ratio::new::<percent>($first), Momentum::new::<pound_foot_per_second>($first))
+                        },
+                        {
+                            inputs: (zero::<Force>(), Force::new::<poundal>(0.5), Time::new::<second>(1.)),
+                            expect: (Ratio::new::<percent>($second), Momentum::new::<pound_foot_per_second>($second))
+                        },
+                    ],
+                    tolerances: {
+                        output: Ratio::new::<percent>(0.00001),
+                        retained_error: Momentum::new::<pound_foot_per_second>(0.00001),
+                    },
+                }
+            )*
+        };
+    }
+
+    zero_strategy_step_tests! {
+        zeroing_on_sign_change_discards_momentum_on_overshoot: ZeroStrategy::OnSignChange => [0., 1.75],
+        zeroing_on_tolerance_keeps_momentum_until_within_tolerance: ZeroStrategy::OnTolerance => [1.5, 0.],
+        never_zeroing_keeps_momentum: ZeroStrategy::Never => [1.5, 3.25],
+    }
 }
 
 pub(crate) mod testing {
@@ -499,12 +1155,12 @@ pub(crate) mod testing {
 
                         let actual = state.step(error, &config, plant_value, $delta_t);
                         let expected = (
-                            PidController::with_initial(error, $expected_retained_error),
+                            PidController::<_, Time>::with_initial(error, $expected_retained_error),
                             $expected_output
                         );
 
                         let difference = (
-                            PidController::with_initial(
+                            PidController::<_, Time>::with_initial(
                                 expected.0.prior_error() - state.prior_error(),
                                 expected.0.retained_error() - state.retained_error(),
                             ),
@@ -521,12 +1177,12 @@ pub(crate) mod testing {
                             failed = true
                         }
 
-                        if difference.0.retained_error() > $retained_error_tolerance || difference.0.retained_error() < -$retained_error_tolerance  {
+                        if !$crate::testing::are_quantities_equal_within(expected.0.retained_error(), state.retained_error(), $retained_error_tolerance) {
                             eprintln!(" !!! retained error mismatch !!!");
                             failed = true;
                         }
 
-                        if difference.1 > $output_tolerance || difference.1 < -$output_tolerance {
+                        if !$crate::testing::are_quantities_equal_within(expected.1, actual, $output_tolerance) {
                             eprintln!(" !!! output mismatch !!!");
                             failed = true;
                         }
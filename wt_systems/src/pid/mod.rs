@@ -3,7 +3,9 @@
 use std::ops;
 use uom::si::f64::{Ratio, Time};
 
+pub mod cascade;
 pub mod integral_zeroing;
+pub mod presets;
 pub mod wescott;
 
 /// Over * In
@@ -16,8 +18,16 @@ pub type Integral<Out, In, Over> = <Out as ops::Div<RetainedError<Over, In>>>::O
 pub type Derivative<Over, In> = <Over as ops::Div<In>>::Output;
 /// In / Over
 pub type ErrorRate<In, Over> = <In as ops::Div<Over>>::Output;
+/// Ratio / Over
+pub type Rate<Over> = <Ratio as ops::Div<Over>>::Output;
 
 /// Outputs from a PID controller
+///
+/// PID outputs are embedded in recorded snapshots, so the serialized form must
+/// remain readable across versions. Every field takes its default when missing
+/// from a record, and fields not known to this version are ignored. New fields
+/// must therefore have a meaningful default, and existing fields must not be
+/// renamed or change meaning.
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PidComponents {
@@ -25,12 +35,14 @@ pub struct PidComponents {
     ///
     /// The proportional term is calculated by multiplying the error signal by a
     /// constant value.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub proportional: Ratio,
 
     /// The integral output from the PID
     ///
     /// The integral term is calculated by accumulating the error signal over
     /// previous steps (retained error) and multiplying it by a constant value.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub integral: Ratio,
 
     /// The derivative output from the PID
@@ -41,6 +53,7 @@ pub struct PidComponents {
     ///
     /// Using the derivative over the plant value rather than the error can
     /// provide for smoother transitions as the command value changes.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub derivative: Ratio,
 }
 
@@ -53,6 +66,68 @@ impl PidComponents {
     }
 }
 
+/// The signal from which a PID controller derives its derivative term
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DerivativeSource {
+    /// Takes the rate of change of the error signal
+    ///
+    /// Changes to the set point will produce a spike (derivative kick) in
+    /// the derivative term.
+    #[default]
+    Error,
+
+    /// Takes the rate of change of the plant value
+    ///
+    /// The rate is negated so that, for a constant set point, the result
+    /// matches the rate of change of the error. Changes to the set point do
+    /// not produce a derivative kick.
+    PlantValue,
+}
+
+/// Outputs from a PID controller both before and after clamping
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PidStepDetails {
+    /// The individual terms contributing to the output
+    pub components: PidComponents,
+
+    /// The combined output of the terms before clamping to the output range
+    pub raw_output: Ratio,
+
+    /// The output after clamping to the output range
+    pub output: Ratio,
+}
+
+impl PidStepDetails {
+    /// The amount by which the raw output exceeds the output range
+    ///
+    /// Zero when the controller is not saturated. Positive when the raw output
+    /// is above the range, and negative when it is below.
+    pub fn saturation(&self) -> Ratio {
+        self.raw_output - self.output
+    }
+}
+
+/// The correction to the integral term from back-calculation anti-windup
+///
+/// Feeds the difference between the clamped and unclamped output back into
+/// the integrator at the rate given by `tracking_gain`. The correction is zero
+/// when the output is not saturated or when the tracking gain is zero.
+#[inline]
+pub(crate) fn back_calculation<Over>(
+    raw_output: Ratio,
+    clamped_output: Ratio,
+    tracking_gain: Rate<Over>,
+    delta_t: Over,
+) -> Ratio
+where
+    Ratio: ops::Div<Over>,
+    Rate<Over>: ops::Mul<Over, Output = Ratio>,
+{
+    (clamped_output - raw_output) * (tracking_gain * delta_t)
+}
+
 /// Configuration for a PID controller
 pub trait Configuration {
     /// Constrains the output command value
@@ -63,7 +138,11 @@ pub trait Configuration {
 }
 
 /// A PID Controller
-pub trait Pid<In> {
+///
+/// Controllers are stepped over `Over`, which is ordinarily elapsed time. Any
+/// other independent variable, such as distance travelled, may be used so long
+/// as the gains are expressed in terms of it.
+pub trait Pid<In, Over = Time> {
     /// The configuration type required for this PID
     type Configuration: Configuration;
 
@@ -78,7 +157,7 @@ pub trait Pid<In> {
         error: In,
         config: &Self::Configuration,
         plant_value: In,
-        delta_t: Time,
+        delta_t: Over,
     ) -> PidComponents;
 
     /// Steps the PID controller forward in time
@@ -93,7 +172,7 @@ pub trait Pid<In> {
         error: In,
         config: &Self::Configuration,
         plant_value: In,
-        delta_t: Time,
+        delta_t: Over,
     ) -> Ratio {
         let PidComponents {
             proportional,
@@ -104,3 +183,71 @@ pub trait Pid<In> {
         config.clamp_output(proportional + integral + derivative)
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use uom::si::ratio::percent;
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct ExtendedPidComponents {
+        #[serde(flatten)]
+        components: PidComponents,
+        #[serde(default)]
+        raw_output: Ratio,
+    }
+
+    fn components() -> PidComponents {
+        PidComponents {
+            proportional: Ratio::new::<percent>(1.),
+            integral: Ratio::new::<percent>(0.5),
+            derivative: Ratio::new::<percent>(-0.25),
+        }
+    }
+
+    #[test]
+    fn record_without_new_field_reads_with_default() {
+        let record = rmp_serde::to_vec_named(&components()).unwrap();
+
+        let extended: ExtendedPidComponents = rmp_serde::from_slice(&record).unwrap();
+
+        assert_eq!(components(), extended.components);
+        assert_eq!(Ratio::default(), extended.raw_output);
+    }
+
+    #[test]
+    fn record_with_unknown_field_is_read() {
+        let record = rmp_serde::to_vec_named(&ExtendedPidComponents {
+            components: components(),
+            raw_output: Ratio::new::<percent>(1.25),
+        })
+        .unwrap();
+
+        let read: PidComponents = rmp_serde::from_slice(&record).unwrap();
+
+        assert_eq!(components(), read);
+    }
+
+    #[test]
+    fn record_missing_a_field_reads_with_default() {
+        #[derive(serde::Serialize)]
+        struct ProportionalOnly {
+            proportional: Ratio,
+        }
+
+        let record = rmp_serde::to_vec_named(&ProportionalOnly {
+            proportional: Ratio::new::<percent>(1.),
+        })
+        .unwrap();
+
+        let read: PidComponents = rmp_serde::from_slice(&record).unwrap();
+
+        assert_eq!(
+            PidComponents {
+                proportional: Ratio::new::<percent>(1.),
+                ..PidComponents::default()
+            },
+            read
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Starting points for tuning the PID controllers of common plants
+//!
+//! Each preset is a complete configuration that may be used as is or adjusted
+//! with struct update syntax. The climb thrust presets are tuned for a thrust
+//! loop in which each PID output is added to the selected throttle, as in the
+//! CJ4 FADEC, against an engine that takes a second or two to spool.
+
+use super::{
+    integral_zeroing::{PidConfiguration, ZeroStrategy},
+    DerivativeSource,
+};
+use uom::si::{
+    f64::*, force::poundal, frequency::hertz, momentum::pound_foot_per_second, ratio::percent,
+    time::second,
+};
+
+/// The climb thrust configuration used by the CJ4 FADEC
+pub fn climb_thrust() -> PidConfiguration<Force> {
+    PidConfiguration {
+        gain_proportion: Ratio::new::<percent>(1.2) / Force::new::<poundal>(1_000.),
+        gain_integral: Ratio::new::<percent>(0.0001) / Momentum::new::<pound_foot_per_second>(1.),
+        gain_derivative: Time::new::<second>(0.018) / Force::new::<poundal>(1_000.),
+        output_range: (Ratio::new::<percent>(-2.), Ratio::new::<percent>(2.)),
+        derivative_range: (Ratio::new::<percent>(-20.), Ratio::new::<percent>(20.)),
+        derivative_source: DerivativeSource::Error,
+        integral_range: None,
+        integral_leak: Frequency::new::<hertz>(0.),
+        tracking_gain: Frequency::new::<hertz>(0.),
+        reference_step: None,
+        zero_strategy: ZeroStrategy::OnSignChange,
+        tolerance: Force::new::<poundal>(0.),
+    }
+}
+
+/// A climb thrust configuration that trades response time for smoothness
+///
+/// Suited to engines with a long spool time or noisy thrust readings.
+pub fn climb_thrust_conservative() -> PidConfiguration<Force> {
+    PidConfiguration {
+        gain_proportion: Ratio::new::<percent>(0.5) / Force::new::<poundal>(1_000.),
+        gain_integral: Ratio::new::<percent>(0.00005) / Momentum::new::<pound_foot_per_second>(1.),
+        gain_derivative: Time::new::<second>(0.005) / Force::new::<poundal>(1_000.),
+        output_range: (Ratio::new::<percent>(-1.), Ratio::new::<percent>(1.)),
+        derivative_range: (Ratio::new::<percent>(-10.), Ratio::new::<percent>(10.)),
+        ..climb_thrust()
+    }
+}
+
+/// A climb thrust configuration that responds quickly to changes in the
+/// thrust target
+///
+/// Suited to engines that spool quickly.
+pub fn climb_thrust_aggressive() -> PidConfiguration<Force> {
+    PidConfiguration {
+        gain_proportion: Ratio::new::<percent>(2.5) / Force::new::<poundal>(1_000.),
+        gain_integral: Ratio::new::<percent>(0.0002) / Momentum::new::<pound_foot_per_second>(1.),
+        output_range: (Ratio::new::<percent>(-4.), Ratio::new::<percent>(4.)),
+        ..climb_thrust()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pid::{integral_zeroing::PidController, Configuration, Pid};
+    use uom::si::ratio::ratio;
+
+    /// Drives a first-order engine whose throttle accumulates the controller
+    /// output, returning the largest relative error over the final ten
+    /// seconds of two minutes
+    fn final_error(config: &PidConfiguration<Force>) -> f64 {
+        let delta_t = Time::new::<second>(1. / 60.);
+        let time_constant = Time::new::<second>(1.5);
+        let max_thrust = Force::new::<poundal>(3_360.);
+        let target = Force::new::<poundal>(2_340.);
+
+        let mut pid = PidController::default();
+        let mut throttle = Ratio::new::<ratio>(0.);
+        let mut thrust = Force::new::<poundal>(0.);
+        let mut worst = 0_f64;
+        for step in 0..120 * 60 {
+            let output = pid.step(target - thrust, config, thrust, delta_t);
+            throttle = (throttle + config.clamp_output(output))
+                .max(Ratio::new::<ratio>(0.))
+                .min(Ratio::new::<ratio>(1.));
+            let settled = max_thrust * throttle;
+            thrust += (settled - thrust) * (delta_t / time_constant).get::<ratio>();
+
+            if step >= 110 * 60 {
+                worst = worst.max(((thrust - target) / target).get::<ratio>().abs());
+            }
+        }
+        worst
+    }
+
+    #[test]
+    fn climb_thrust_converges_on_first_order_plant() {
+        let error = final_error(&climb_thrust());
+        assert!(error < 0.005, "{}", error);
+    }
+
+    #[test]
+    fn climb_thrust_conservative_converges_on_first_order_plant() {
+        let error = final_error(&climb_thrust_conservative());
+        assert!(error < 0.005, "{}", error);
+    }
+
+    #[test]
+    fn climb_thrust_aggressive_converges_on_first_order_plant() {
+        let error = final_error(&climb_thrust_aggressive());
+        assert!(error < 0.005, "{}", error);
+    }
+}
@@ -5,11 +5,14 @@
 //!
 //!   [Wes18]: https://www.wescottdesign.com/articles/pid/pidWithoutAPhd.pdf
 
-use super::{Derivative, ErrorRate, Integral, PidComponents, Proportion, RetainedError};
+use super::{
+    Derivative, ErrorRate, Integral, PidComponents, PidStepDetails, Proportion, Rate, RetainedError,
+};
 use serde::{Deserialize, Serialize};
 use std::{fmt, ops};
 use uom::num_traits::{clamp, zero, Zero};
 use uom::si::f64::*;
+use uom::si::ratio::ratio;
 
 /// Configuration for a PID controller
 ///
@@ -20,7 +23,8 @@ use uom::si::f64::*;
 ///
 /// ```
 /// use wt_systems::pid::wescott::PidConfiguration;
-/// use uom::si::f64::{Velocity, Ratio, Time};
+/// use uom::si::f64::{Frequency, Velocity, Ratio, Time};
+/// use uom::si::frequency::hertz;
 /// use uom::si::velocity::meter_per_second;
 /// use uom::si::ratio::{basis_point, ratio};
 /// use uom::si::time::second;
@@ -34,20 +38,22 @@ use uom::si::f64::*;
 ///         Velocity::new::<meter_per_second>(-30.) * Time::new::<second>(1.),
 ///         Velocity::new::<meter_per_second>(30.) * Time::new::<second>(1.)
 ///     ),
+///     integral_leak: Frequency::new::<hertz>(0.),
+///     tracking_gain: Frequency::new::<hertz>(0.),
 /// };
 /// ```
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(
     feature = "serde",
     serde(bound(
-        serialize = "In: Serialize, Proportion<Ratio, In>: Serialize, Integral<Ratio, In, Time>: Serialize, Derivative<Time, In>: Serialize, RetainedError<Time, In>: Serialize",
-        deserialize = "for<'d> In: Deserialize<'d>, for<'d> Proportion<Ratio, In>: Deserialize<'d>, for<'d> Integral<Ratio, In, Time>: Deserialize<'d>, for<'d> Derivative<Time, In>: Deserialize<'d>, for<'d> RetainedError<Time, In>: Deserialize<'d>",
+        serialize = "In: Serialize, Proportion<Ratio, In>: Serialize, Integral<Ratio, In, Over>: Serialize, Derivative<Over, In>: Serialize, RetainedError<Over, In>: Serialize, Rate<Over>: Serialize",
+        deserialize = "for<'d> In: Deserialize<'d>, for<'d> Proportion<Ratio, In>: Deserialize<'d>, for<'d> Integral<Ratio, In, Over>: Deserialize<'d>, for<'d> Derivative<Over, In>: Deserialize<'d>, for<'d> RetainedError<Over, In>: Deserialize<'d>, for<'d> Rate<Over>: Deserialize<'d> + Default",
     ))
 )]
-pub struct PidConfiguration<In>
+pub struct PidConfiguration<In, Over = Time>
 where
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
 {
     /// The gain applied to the proportional component of error
     ///
@@ -59,10 +65,10 @@ where
     ///
     /// This is the _momentum_ that the PID gains over the course of continued
     /// errors over time.
-    pub gain_integral: Integral<Ratio, In, Time>,
+    pub gain_integral: Integral<Ratio, In, Over>,
 
     /// The gain applied to the derivative component of error
-    pub gain_derivative: Derivative<Time, In>,
+    pub gain_derivative: Derivative<Over, In>,
 
     /// Output value limits (inclusive)
     ///
@@ -73,18 +79,39 @@ where
     ///
     /// Contributions to the output value from the derivative component will be
     /// clamped to the range specified.
-    pub integral_range: (RetainedError<Time, In>, RetainedError<Time, In>),
+    pub integral_range: (RetainedError<Over, In>, RetainedError<Over, In>),
+
+    /// Rate at which the retained error decays toward zero
+    ///
+    /// On each step, the retained error is scaled by `e^(-leak * delta_t)`
+    /// before any new error is accumulated. This provides an alternative to
+    /// hard clamping for shedding accumulated momentum. A leak of zero retains
+    /// all accumulated error.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub integral_leak: Rate<Over>,
+
+    /// Gain applied when feeding saturation back into the retained error
+    ///
+    /// When the combined output falls outside of the output range, the
+    /// difference between the clamped and unclamped output, scaled by this
+    /// gain and the step duration, is fed back into the integral term
+    /// (back-calculation). This unwinds accumulated momentum while the output
+    /// is saturated rather than relying solely on hard limits. A gain of zero
+    /// disables back-calculation.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tracking_gain: Rate<Over>,
 }
 
-impl<In> Clone for PidConfiguration<In>
+impl<In, Over> Clone for PidConfiguration<In, Over>
 where
+    Rate<Over>: Clone,
     In: Clone,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>> + Clone,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over> + Clone,
+    Over: ops::Mul<In> + ops::Div<In>,
     Proportion<Ratio, In>: Clone,
-    Integral<Ratio, In, Time>: Clone,
-    Derivative<Time, In>: Clone,
-    RetainedError<Time, In>: Clone,
+    Integral<Ratio, In, Over>: Clone,
+    Derivative<Over, In>: Clone,
+    RetainedError<Over, In>: Clone,
 {
     #[inline(always)]
     fn clone(&self) -> Self {
@@ -94,31 +121,35 @@ where
             gain_derivative: self.gain_derivative.clone(),
             output_range: self.output_range,
             integral_range: self.integral_range.clone(),
+            integral_leak: self.integral_leak.clone(),
+            tracking_gain: self.tracking_gain.clone(),
         }
     }
 }
 
-impl<In> Copy for PidConfiguration<In>
+impl<In, Over> Copy for PidConfiguration<In, Over>
 where
+    Rate<Over>: Copy,
     In: Copy,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>> + Copy,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over> + Copy,
+    Over: ops::Mul<In> + ops::Div<In>,
     Proportion<Ratio, In>: Copy,
-    Integral<Ratio, In, Time>: Copy,
-    Derivative<Time, In>: Copy,
-    RetainedError<Time, In>: Copy,
+    Integral<Ratio, In, Over>: Copy,
+    Derivative<Over, In>: Copy,
+    RetainedError<Over, In>: Copy,
 {
 }
 
-impl<In> PartialEq for PidConfiguration<In>
+impl<In, Over> PartialEq for PidConfiguration<In, Over>
 where
+    Rate<Over>: PartialEq,
     In: PartialEq,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
     Proportion<Ratio, In>: PartialEq,
-    Integral<Ratio, In, Time>: PartialEq,
-    Derivative<Time, In>: PartialEq,
-    RetainedError<Time, In>: PartialEq,
+    Integral<Ratio, In, Over>: PartialEq,
+    Derivative<Over, In>: PartialEq,
+    RetainedError<Over, In>: PartialEq,
 {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
@@ -127,18 +158,21 @@ where
             && self.gain_proportion == other.gain_proportion
             && self.output_range == other.output_range
             && self.integral_range == other.integral_range
+            && self.integral_leak == other.integral_leak
+            && self.tracking_gain == other.tracking_gain
     }
 }
 
-impl<In> fmt::Debug for PidConfiguration<In>
+impl<In, Over> fmt::Debug for PidConfiguration<In, Over>
 where
+    Rate<Over>: fmt::Debug,
     In: fmt::Debug,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>> + fmt::Debug,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over> + fmt::Debug,
+    Over: ops::Mul<In> + ops::Div<In>,
     Proportion<Ratio, In>: fmt::Debug,
-    Integral<Ratio, In, Time>: fmt::Debug,
-    Derivative<Time, In>: fmt::Debug,
-    RetainedError<Time, In>: fmt::Debug,
+    Integral<Ratio, In, Over>: fmt::Debug,
+    Derivative<Over, In>: fmt::Debug,
+    RetainedError<Over, In>: fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("PidConfiguration")
@@ -147,23 +181,22 @@ where
             .field("gain_derivative", &self.gain_derivative)
             .field(
                 "output_range",
-                &format_args!("[{:?}, {:?}]", &self.output_range.0, &self.output_range.1),
+                &format_args!("[{:?}, {:?}]", self.output_range.0, self.output_range.1),
             )
             .field(
                 "integral_range",
-                &format_args!(
-                    "[{:?}, {:?}]",
-                    &self.integral_range.0, &self.integral_range.1
-                ),
+                &format_args!("[{:?}, {:?}]", self.integral_range.0, self.integral_range.1),
             )
+            .field("integral_leak", &self.integral_leak)
+            .field("tracking_gain", &self.tracking_gain)
             .finish()
     }
 }
 
-impl<In> super::Configuration for PidConfiguration<In>
+impl<In, Over> super::Configuration for PidConfiguration<In, Over>
 where
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
 {
     #[inline(always)]
     fn clamp_output(&self, output: Ratio) -> Ratio {
@@ -185,7 +218,8 @@ where
 ///
 /// ```
 /// use wt_systems::pid::{Pid, wescott::{PidConfiguration, PidController}};
-/// use uom::si::f64::{Velocity, Ratio, Time};
+/// use uom::si::f64::{Frequency, Velocity, Ratio, Time};
+/// use uom::si::frequency::hertz;
 /// use uom::si::velocity::{meter_per_second};
 /// use uom::si::ratio::{basis_point, ratio};
 /// use uom::si::time::second;
@@ -199,6 +233,8 @@ where
 ///         Velocity::new::<meter_per_second>(-30.) * Time::new::<second>(1.),
 ///         Velocity::new::<meter_per_second>(30.) * Time::new::<second>(1.)
 ///     ),
+///     integral_leak: Frequency::new::<hertz>(0.),
+///     tracking_gain: Frequency::new::<hertz>(0.),
 /// };
 ///
 /// let mut pid = PidController::default();
@@ -214,28 +250,28 @@ where
 #[cfg_attr(
     feature = "serde",
     serde(bound(
-        serialize = "In: Serialize, RetainedError<Time, In>: Serialize",
-        deserialize = "for<'d> In: Deserialize<'d>, for<'d> RetainedError<Time, In>: Deserialize<'d>",
+        serialize = "In: Serialize, RetainedError<Over, In>: Serialize",
+        deserialize = "for<'d> In: Deserialize<'d>, for<'d> RetainedError<Over, In>: Deserialize<'d>",
     ))
 )]
-pub struct PidController<In>
+pub struct PidController<In, Over = Time>
 where
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
 {
     /// Plant value from the last step
     pub prior_plant_value: In,
 
     /// Retained error (momentum) due to accumulated errors over time
-    pub retained_error: RetainedError<Time, In>,
+    pub retained_error: RetainedError<Over, In>,
 }
 
-impl<In> Clone for PidController<In>
+impl<In, Over> Clone for PidController<In, Over>
 where
     In: Clone,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
-    RetainedError<Time, In>: Clone,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
+    RetainedError<Over, In>: Clone,
 {
     #[inline(always)]
     fn clone(&self) -> Self {
@@ -246,21 +282,21 @@ where
     }
 }
 
-impl<In> Copy for PidController<In>
+impl<In, Over> Copy for PidController<In, Over>
 where
     In: Copy,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
-    RetainedError<Time, In>: Copy,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
+    RetainedError<Over, In>: Copy,
 {
 }
 
-impl<In> fmt::Debug for PidController<In>
+impl<In, Over> fmt::Debug for PidController<In, Over>
 where
     In: fmt::Debug,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
-    RetainedError<Time, In>: fmt::Debug,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
+    RetainedError<Over, In>: fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("PidController")
@@ -270,12 +306,12 @@ where
     }
 }
 
-impl<In> Default for PidController<In>
+impl<In, Over> Default for PidController<In, Over>
 where
     In: Zero,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
-    RetainedError<Time, In>: Zero,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
+    RetainedError<Over, In>: Zero,
 {
     #[inline]
     fn default() -> Self {
@@ -286,12 +322,12 @@ where
     }
 }
 
-impl<In> PartialEq for PidController<In>
+impl<In, Over> PartialEq for PidController<In, Over>
 where
     In: PartialEq,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
-    RetainedError<Time, In>: PartialEq,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
+    RetainedError<Over, In>: PartialEq,
 {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
@@ -300,14 +336,14 @@ where
     }
 }
 
-impl<In> PidController<In>
+impl<In, Over> PidController<In, Over>
 where
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
 {
     /// Constructs a PID controller with existing values
     #[inline]
-    pub fn with_initial(initial_plant_value: In, retained_error: RetainedError<Time, In>) -> Self {
+    pub fn with_initial(initial_plant_value: In, retained_error: RetainedError<Over, In>) -> Self {
         Self {
             prior_plant_value: initial_plant_value,
             retained_error,
@@ -322,16 +358,16 @@ where
 
     /// Obtains a reference to the current retained error value
     #[inline]
-    pub fn retained_error_ref(&self) -> &RetainedError<Time, In> {
+    pub fn retained_error_ref(&self) -> &RetainedError<Over, In> {
         &self.retained_error
     }
 }
 
-impl<In> PidController<In>
+impl<In, Over> PidController<In, Over>
 where
     In: Clone,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
 {
     /// Gets the current plant value from the previous step
     #[inline]
@@ -340,23 +376,23 @@ where
     }
 }
 
-impl<In> PidController<In>
+impl<In, Over> PidController<In, Over>
 where
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
-    RetainedError<Time, In>: Clone,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
+    RetainedError<Over, In>: Clone,
 {
     /// Gets the current retained error value
     #[inline]
-    pub fn retained_error(&self) -> RetainedError<Time, In> {
+    pub fn retained_error(&self) -> RetainedError<Over, In> {
         self.retained_error.clone()
     }
 }
 
-impl<In> PidController<In>
+impl<In, Over> PidController<In, Over>
 where
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
     Self: Default,
 {
     /// Resets the PID controller to a zeroed state
@@ -369,34 +405,59 @@ where
     }
 }
 
-impl<In> super::Pid<In> for PidController<In>
+impl<In, Over> PidController<In, Over>
+where
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
+    Integral<Ratio, In, Over>: ops::Mul<RetainedError<Over, In>, Output = Ratio> + Copy,
+    RetainedError<Over, In>: Copy,
+{
+    /// The integral term that the retained error contributes to the output
+    /// under `config`
+    ///
+    /// Equivalent to multiplying the retained error by the integral gain.
+    #[inline]
+    pub fn integral_contribution(&self, config: &PidConfiguration<In, Over>) -> Ratio {
+        config.gain_integral * self.retained_error
+    }
+}
+
+impl<In, Over> super::Pid<In, Over> for PidController<In, Over>
 where
     In: PartialOrd
         + Zero
         + ops::Sub<In, Output = In>
-        + ops::Div<Time>
-        + ops::Mul<Time, Output = RetainedError<Time, In>>
+        + ops::Div<Over>
+        + ops::Mul<Over, Output = RetainedError<Over, In>>
         + Copy,
-    Ratio: ops::Div<In> + ops::Div<RetainedError<Time, In>>,
-    Time: ops::Mul<In> + ops::Div<In>,
+    Ratio: ops::Div<In>
+        + ops::Div<RetainedError<Over, In>>
+        + ops::Div<Over>
+        + ops::Div<Integral<Ratio, In, Over>, Output = RetainedError<Over, In>>,
+    Over: ops::Mul<In> + ops::Div<In> + Copy,
+    Rate<Over>: ops::Mul<Over, Output = Ratio> + Copy,
     Proportion<Ratio, In>: ops::Mul<In, Output = Ratio> + Copy,
-    Integral<Ratio, In, Time>: ops::Mul<RetainedError<Time, In>, Output = Ratio> + Copy,
-    Derivative<Time, In>: ops::Mul<ErrorRate<In, Time>, Output = Ratio> + Copy,
-    RetainedError<Time, In>: ops::AddAssign + PartialOrd + Copy,
+    Integral<Ratio, In, Over>:
+        ops::Mul<RetainedError<Over, In>, Output = Ratio> + Zero + PartialEq + Copy,
+    Derivative<Over, In>: ops::Mul<ErrorRate<In, Over>, Output = Ratio> + Copy,
+    RetainedError<Over, In>:
+        ops::AddAssign + ops::Div<f64, Output = RetainedError<Over, In>> + PartialOrd + Copy,
 {
-    type Configuration = PidConfiguration<In>;
+    type Configuration = PidConfiguration<In, Over>;
 
     fn step_with_components(
         &mut self,
         error: In,
         config: &Self::Configuration,
         plant_value: In,
-        delta_t: Time,
+        delta_t: Over,
     ) -> PidComponents {
         // Proportional
         let proportional: Ratio = config.gain_proportion * error;
 
         // Integral
+        self.retained_error =
+            self.retained_error / (config.integral_leak * delta_t).get::<ratio>().exp();
         self.retained_error += error * delta_t;
         self.retained_error = clamp(
             self.retained_error,
@@ -411,6 +472,23 @@ where
 
         self.prior_plant_value = plant_value;
 
+        // Anti-windup
+        let raw_output = proportional + integral + derivative;
+        let correction = super::back_calculation(
+            raw_output,
+            super::Configuration::clamp_output(config, raw_output),
+            config.tracking_gain,
+            delta_t,
+        );
+        if correction != zero::<Ratio>() && config.gain_integral != zero() {
+            self.retained_error += correction / config.gain_integral;
+            self.retained_error = clamp(
+                self.retained_error,
+                config.integral_range.0,
+                config.integral_range.1,
+            );
+        }
+
         // println!("Output: {} ({}): Derivative: {} ({}), Integral: {}, proportion: {}", output.into_format_args(ratio, uom::fmt::DisplayStyle::Abbreviation), raw_output.into_format_args(ratio, uom::fmt::DisplayStyle::Abbreviation), gained_derivative.into_format_args(ratio, uom::fmt::DisplayStyle::Abbreviation), raw_gained_derivative.into_format_args(ratio, uom::fmt::DisplayStyle::Abbreviation), gained_integral.into_format_args(ratio, uom::fmt::DisplayStyle::Abbreviation), gained_error.into_format_args(ratio, uom::fmt::DisplayStyle::Abbreviation));
 
         PidComponents {
@@ -420,3 +498,187 @@ where
         }
     }
 }
+
+impl<In, Over> PidController<In, Over>
+where
+    Self: super::Pid<In, Over, Configuration = PidConfiguration<In, Over>>,
+    Ratio: ops::Div<In> + ops::Div<RetainedError<Over, In>> + ops::Div<Over>,
+    Over: ops::Mul<In> + ops::Div<In>,
+{
+    /// Steps the PID controller forward in time, reporting the output both
+    /// before and after clamping
+    ///
+    /// The integral term reflects the retained error after it has been
+    /// clamped to the integral range.
+    pub fn step_detailed(
+        &mut self,
+        error: In,
+        config: &PidConfiguration<In, Over>,
+        plant_value: In,
+        delta_t: Over,
+    ) -> PidStepDetails {
+        use super::{Configuration, Pid};
+
+        let components = self.step_with_components(error, config, plant_value, delta_t);
+        let raw_output = components.output();
+
+        PidStepDetails {
+            components,
+            raw_output,
+            output: config.clamp_output(raw_output),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pid::Pid;
+    use uom::si::{frequency::hertz, time::second, velocity::meter_per_second};
+
+    fn config() -> PidConfiguration<Velocity> {
+        PidConfiguration {
+            gain_proportion: Ratio::new::<ratio>(1.) / Velocity::new::<meter_per_second>(10.),
+            gain_integral: Ratio::new::<ratio>(0.1)
+                / (Velocity::new::<meter_per_second>(10.) * Time::new::<second>(1.)),
+            gain_derivative: Time::new::<second>(0.) / Velocity::new::<meter_per_second>(1.),
+            output_range: (Ratio::new::<ratio>(-1.), Ratio::new::<ratio>(1.)),
+            integral_range: (
+                Velocity::new::<meter_per_second>(-30.) * Time::new::<second>(1.),
+                Velocity::new::<meter_per_second>(30.) * Time::new::<second>(1.),
+            ),
+            integral_leak: Frequency::new::<hertz>(0.),
+            tracking_gain: Frequency::new::<hertz>(0.),
+        }
+    }
+
+    #[test]
+    fn integral_contribution_applies_integral_gain_to_retained_error() {
+        let config = config();
+        let mut pid = PidController::<Velocity>::default();
+
+        let components = pid.step_with_components(
+            Velocity::new::<meter_per_second>(2.),
+            &config,
+            Velocity::new::<meter_per_second>(0.),
+            Time::new::<second>(1.),
+        );
+
+        assert_eq!(
+            config.gain_integral * pid.retained_error(),
+            pid.integral_contribution(&config)
+        );
+        assert_eq!(components.integral, pid.integral_contribution(&config));
+    }
+
+    #[test]
+    fn detailed_step_reports_raw_and_clamped_output() {
+        let mut pid = PidController::<Velocity>::default();
+
+        let details = pid.step_detailed(
+            Velocity::new::<meter_per_second>(25.),
+            &config(),
+            Velocity::new::<meter_per_second>(0.),
+            Time::new::<second>(1.),
+        );
+
+        // 2.5 proportional + 0.25 integral
+        crate::testing::assert_equal_within_epsilon(2.75, details.raw_output.get::<ratio>(), 1e-9);
+        assert_eq!(details.components.output(), details.raw_output);
+        assert_eq!(Ratio::new::<ratio>(1.), details.output);
+        crate::testing::assert_equal_within_epsilon(
+            1.75,
+            details.saturation().get::<ratio>(),
+            1e-9,
+        );
+    }
+
+    #[test]
+    fn detailed_step_matches_step_within_range() {
+        let mut detailed = PidController::<Velocity>::default();
+        let mut plain = PidController::<Velocity>::default();
+
+        for &error in &[2., -3., 0.5] {
+            let error = Velocity::new::<meter_per_second>(error);
+            let plant_value = Velocity::new::<meter_per_second>(0.);
+            let delta_t = Time::new::<second>(0.5);
+
+            let details = detailed.step_detailed(error, &config(), plant_value, delta_t);
+            let output = plain.step(error, &config(), plant_value, delta_t);
+
+            assert_eq!(output, details.output);
+            assert_eq!(details.raw_output, details.output);
+            assert_eq!(Ratio::new::<ratio>(0.), details.saturation());
+        }
+    }
+
+    /// Saturates a controller with a sustained positive error, then reverses
+    /// the error, returning the number of steps until the output leaves the
+    /// upper limit
+    fn steps_to_recover_from_saturation(tracking_gain: Frequency) -> usize {
+        let config = PidConfiguration {
+            gain_integral: Ratio::new::<ratio>(0.1)
+                / (Velocity::new::<meter_per_second>(1.) * Time::new::<second>(1.)),
+            tracking_gain,
+            ..config()
+        };
+        let mut pid = PidController::<Velocity>::default();
+        let plant_value = Velocity::new::<meter_per_second>(0.);
+        let delta_t = Time::new::<second>(1.);
+
+        for _ in 0..10 {
+            let details = pid.step_detailed(
+                Velocity::new::<meter_per_second>(25.),
+                &config,
+                plant_value,
+                delta_t,
+            );
+            assert_eq!(config.output_range.1, details.output);
+        }
+
+        (1..=100)
+            .find(|_| {
+                let details = pid.step_detailed(
+                    Velocity::new::<meter_per_second>(-5.),
+                    &config,
+                    plant_value,
+                    delta_t,
+                );
+                details.output < config.output_range.1
+            })
+            .expect("controller should recover from saturation")
+    }
+
+    #[test]
+    fn back_calculation_recovers_from_saturation_faster_than_clamping() {
+        let clamped = steps_to_recover_from_saturation(Frequency::new::<hertz>(0.));
+        let tracked = steps_to_recover_from_saturation(Frequency::new::<hertz>(0.5));
+
+        assert!(
+            tracked < clamped,
+            "expected recovery in fewer than {} steps, took {}",
+            clamped,
+            tracked
+        );
+    }
+
+    #[test]
+    fn back_calculation_has_no_effect_within_output_range() {
+        let tracked_config = PidConfiguration {
+            tracking_gain: Frequency::new::<hertz>(0.5),
+            ..config()
+        };
+        let mut tracked = PidController::<Velocity>::default();
+        let mut plain = PidController::<Velocity>::default();
+
+        for &error in &[2., -3., 0.5] {
+            let error = Velocity::new::<meter_per_second>(error);
+            let plant_value = Velocity::new::<meter_per_second>(0.);
+            let delta_t = Time::new::<second>(0.5);
+
+            let output = tracked.step(error, &tracked_config, plant_value, delta_t);
+            assert_eq!(plain.step(error, &config(), plant_value, delta_t), output);
+            assert_eq!(plain, tracked);
+        }
+    }
+}
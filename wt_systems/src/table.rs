@@ -0,0 +1,299 @@
+//! Lookup tables for piecewise-linear schedules
+//!
+//! Schedules are defined by values at a set of breakpoints. Inputs between
+//! breakpoints are linearly interpolated, while inputs beyond the first or last
+//! breakpoint are clamped to the value at that edge.
+
+use std::ops;
+use uom::si::{f64::Ratio, ratio::ratio};
+
+/// A one-dimensional lookup table
+///
+/// # Example
+///
+/// ```
+/// use wt_systems::table::Lut1;
+/// use uom::si::f64::{Force, Length};
+/// use uom::si::{force::poundal, length::foot};
+///
+/// let schedule = Lut1::new([
+///     (Length::new::<foot>(0.), Force::new::<poundal>(2_400.)),
+///     (Length::new::<foot>(7_000.), Force::new::<poundal>(2_050.)),
+/// ]);
+///
+/// let thrust = schedule.lookup(Length::new::<foot>(3_500.));
+/// assert!((thrust.get::<poundal>() - 2_225.).abs() < 1e-9);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lut1<X, Y, const N: usize> {
+    breakpoints: [X; N],
+    values: [Y; N],
+}
+
+impl<X, Y, const N: usize> Lut1<X, Y, N>
+where
+    X: PartialOrd + ops::Sub<Output = X> + ops::Div<Output = Ratio> + Copy,
+    Y: ops::Add<Output = Y> + ops::Sub<Output = Y> + ops::Mul<f64, Output = Y> + Copy,
+{
+    /// Constructs a lookup table from pairs of breakpoints and values
+    ///
+    /// # Panics
+    ///
+    /// Panics if no points are provided or the breakpoints are not strictly
+    /// increasing.
+    pub fn new(points: [(X, Y); N]) -> Self {
+        let breakpoints = points.map(|(x, _)| x);
+        validate_breakpoints(&breakpoints);
+
+        Self {
+            breakpoints,
+            values: points.map(|(_, y)| y),
+        }
+    }
+
+    /// Looks up the value at `x`, interpolating between breakpoints and
+    /// clamping beyond the edges of the table
+    pub fn lookup(&self, x: X) -> Y {
+        let (lower, upper, fraction) = bracket(&self.breakpoints, x);
+        lerp(self.values[lower], self.values[upper], fraction)
+    }
+}
+
+/// A two-dimensional lookup table
+///
+/// Values are arranged in rows by the first input, with each row containing a
+/// value for each breakpoint of the second input.
+///
+/// # Example
+///
+/// ```
+/// use wt_systems::table::Lut2;
+/// use uom::si::f64::{Force, Length, Ratio};
+/// use uom::si::{force::poundal, length::foot, ratio::ratio};
+///
+/// let schedule = Lut2::new(
+///     [Length::new::<foot>(0.), Length::new::<foot>(10_000.)],
+///     [Ratio::new::<ratio>(0.), Ratio::new::<ratio>(0.8)],
+///     [
+///         [Force::new::<poundal>(3_000.), Force::new::<poundal>(2_600.)],
+///         [Force::new::<poundal>(2_400.), Force::new::<poundal>(2_000.)],
+///     ],
+/// );
+///
+/// let thrust = schedule.lookup(Length::new::<foot>(5_000.), Ratio::new::<ratio>(0.4));
+/// assert!((thrust.get::<poundal>() - 2_500.).abs() < 1e-9);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lut2<X1, X2, Y, const N: usize, const M: usize> {
+    breakpoints1: [X1; N],
+    breakpoints2: [X2; M],
+    values: [[Y; M]; N],
+}
+
+impl<X1, X2, Y, const N: usize, const M: usize> Lut2<X1, X2, Y, N, M>
+where
+    X1: PartialOrd + ops::Sub<Output = X1> + ops::Div<Output = Ratio> + Copy,
+    X2: PartialOrd + ops::Sub<Output = X2> + ops::Div<Output = Ratio> + Copy,
+    Y: ops::Add<Output = Y> + ops::Sub<Output = Y> + ops::Mul<f64, Output = Y> + Copy,
+{
+    /// Constructs a lookup table from the breakpoints for each input and the
+    /// values at each pair of breakpoints
+    ///
+    /// # Panics
+    ///
+    /// Panics if either set of breakpoints is empty or not strictly
+    /// increasing.
+    pub fn new(breakpoints1: [X1; N], breakpoints2: [X2; M], values: [[Y; M]; N]) -> Self {
+        validate_breakpoints(&breakpoints1);
+        validate_breakpoints(&breakpoints2);
+
+        Self {
+            breakpoints1,
+            breakpoints2,
+            values,
+        }
+    }
+
+    /// Looks up the value at (`x1`, `x2`), interpolating between breakpoints
+    /// and clamping beyond the edges of the table
+    pub fn lookup(&self, x1: X1, x2: X2) -> Y {
+        let (lower1, upper1, fraction1) = bracket(&self.breakpoints1, x1);
+        let (lower2, upper2, fraction2) = bracket(&self.breakpoints2, x2);
+
+        let lower = lerp(
+            self.values[lower1][lower2],
+            self.values[lower1][upper2],
+            fraction2,
+        );
+        let upper = lerp(
+            self.values[upper1][lower2],
+            self.values[upper1][upper2],
+            fraction2,
+        );
+
+        lerp(lower, upper, fraction1)
+    }
+}
+
+fn validate_breakpoints<X: PartialOrd>(breakpoints: &[X]) {
+    assert!(
+        !breakpoints.is_empty(),
+        "lookup tables require at least one breakpoint"
+    );
+    assert!(
+        breakpoints.windows(2).all(|w| w[0] < w[1]),
+        "lookup table breakpoints must be strictly increasing"
+    );
+}
+
+/// Finds the breakpoints surrounding `x` and the fraction of the distance
+/// between them, clamping to the edges of the table
+fn bracket<X>(breakpoints: &[X], x: X) -> (usize, usize, f64)
+where
+    X: PartialOrd + ops::Sub<Output = X> + ops::Div<Output = Ratio> + Copy,
+{
+    let last = breakpoints.len() - 1;
+    if x <= breakpoints[0] {
+        return (0, 0, 0.);
+    }
+    if x >= breakpoints[last] {
+        return (last, last, 0.);
+    }
+
+    let upper = breakpoints.iter().position(|&b| x < b).unwrap_or(last);
+    let lower = upper - 1;
+    let fraction = (x - breakpoints[lower]) / (breakpoints[upper] - breakpoints[lower]);
+
+    (lower, upper, fraction.get::<ratio>())
+}
+
+fn lerp<Y>(lower: Y, upper: Y, fraction: f64) -> Y
+where
+    Y: ops::Add<Output = Y> + ops::Sub<Output = Y> + ops::Mul<f64, Output = Y> + Copy,
+{
+    lower + (upper - lower) * fraction
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use uom::si::f64::{Force, Length};
+    use uom::si::{force::poundal, length::foot};
+
+    fn lut1() -> Lut1<Length, Force, 3> {
+        Lut1::new([
+            (Length::new::<foot>(0.), Force::new::<poundal>(2_400.)),
+            (Length::new::<foot>(7_000.), Force::new::<poundal>(2_050.)),
+            (Length::new::<foot>(35_000.), Force::new::<poundal>(1_800.)),
+        ])
+    }
+
+    fn lut2() -> Lut2<Length, Ratio, Force, 2, 3> {
+        Lut2::new(
+            [Length::new::<foot>(0.), Length::new::<foot>(10_000.)],
+            [
+                Ratio::new::<ratio>(0.),
+                Ratio::new::<ratio>(0.4),
+                Ratio::new::<ratio>(0.8),
+            ],
+            [
+                [
+                    Force::new::<poundal>(3_000.),
+                    Force::new::<poundal>(2_800.),
+                    Force::new::<poundal>(2_600.),
+                ],
+                [
+                    Force::new::<poundal>(2_400.),
+                    Force::new::<poundal>(2_200.),
+                    Force::new::<poundal>(2_000.),
+                ],
+            ],
+        )
+    }
+
+    fn assert_poundals(expected: f64, actual: Force) {
+        testing::assert_equal_within_epsilon(expected, actual.get::<poundal>(), 1e-9);
+    }
+
+    #[test]
+    fn lut1_matches_breakpoints() {
+        let table = lut1();
+        assert_poundals(2_400., table.lookup(Length::new::<foot>(0.)));
+        assert_poundals(2_050., table.lookup(Length::new::<foot>(7_000.)));
+        assert_poundals(1_800., table.lookup(Length::new::<foot>(35_000.)));
+    }
+
+    #[test]
+    fn lut1_interpolates_between_breakpoints() {
+        let table = lut1();
+        assert_poundals(2_225., table.lookup(Length::new::<foot>(3_500.)));
+        assert_poundals(1_925., table.lookup(Length::new::<foot>(21_000.)));
+    }
+
+    #[test]
+    fn lut1_clamps_beyond_edges() {
+        let table = lut1();
+        assert_poundals(2_400., table.lookup(Length::new::<foot>(-1_000.)));
+        assert_poundals(1_800., table.lookup(Length::new::<foot>(45_000.)));
+    }
+
+    #[test]
+    fn lut1_with_single_point_is_constant() {
+        let table = Lut1::new([(Length::new::<foot>(0.), Force::new::<poundal>(10.))]);
+        assert_poundals(10., table.lookup(Length::new::<foot>(-5.)));
+        assert_poundals(10., table.lookup(Length::new::<foot>(5.)));
+    }
+
+    #[test]
+    #[should_panic(expected = "strictly increasing")]
+    fn lut1_rejects_unordered_breakpoints() {
+        Lut1::new([
+            (Length::new::<foot>(10.), Force::new::<poundal>(1.)),
+            (Length::new::<foot>(0.), Force::new::<poundal>(2.)),
+        ]);
+    }
+
+    #[test]
+    fn lut2_matches_breakpoints() {
+        let table = lut2();
+        assert_poundals(
+            2_800.,
+            table.lookup(Length::new::<foot>(0.), Ratio::new::<ratio>(0.4)),
+        );
+        assert_poundals(
+            2_000.,
+            table.lookup(Length::new::<foot>(10_000.), Ratio::new::<ratio>(0.8)),
+        );
+    }
+
+    #[test]
+    fn lut2_interpolates_bilinearly() {
+        let table = lut2();
+        assert_poundals(
+            2_600.,
+            table.lookup(Length::new::<foot>(5_000.), Ratio::new::<ratio>(0.2)),
+        );
+        assert_poundals(
+            2_300.,
+            table.lookup(Length::new::<foot>(7_500.), Ratio::new::<ratio>(0.5)),
+        );
+    }
+
+    #[test]
+    fn lut2_clamps_beyond_edges() {
+        let table = lut2();
+        assert_poundals(
+            3_000.,
+            table.lookup(Length::new::<foot>(-1_000.), Ratio::new::<ratio>(-0.1)),
+        );
+        assert_poundals(
+            2_000.,
+            table.lookup(Length::new::<foot>(20_000.), Ratio::new::<ratio>(1.2)),
+        );
+        assert_poundals(
+            2_250.,
+            table.lookup(Length::new::<foot>(20_000.), Ratio::new::<ratio>(0.3)),
+        );
+    }
+}
@@ -6,6 +6,11 @@ pub mod pid {
     pub use crate::pid_step_tests as step_tests;
 }
 
+#[doc(inline)]
+pub use crate::assert_quantity_eq;
+
+use uom::si::{Dimension, Quantity, Units};
+
 /// Compares two values by equalizing their magnitudes and determining whether
 /// the values are equal over the requested number of significant figures
 ///
@@ -44,7 +49,7 @@ pub fn are_equal_in_significant_figures(expected: f64, actual: f64, figures: usi
     }
     .trunc();
 
-    if expected_power - actual_power > std::f64::EPSILON {
+    if expected_power - actual_power > f64::EPSILON {
         return false;
     }
 
@@ -124,3 +129,136 @@ pub fn assert_equal_within_epsilon(expected: f64, actual: f64, epsilon: f64) {
         panic!("Expected and actual values differ by more than {}", epsilon);
     }
 }
+
+/// Checks whether two quantities are equal within a certain tolerance
+///
+/// The quantities are compared in base units, so the units used to construct
+/// them do not matter. A difference exactly equal to the tolerance is accepted.
+///
+/// ## Examples
+///
+/// ```
+/// # use wt_systems::testing::are_quantities_equal_within;
+/// use uom::si::{f64::Force, force::{newton, pound_force}};
+/// let expected = Force::new::<pound_force>(1.);
+/// assert!(are_quantities_equal_within(expected, Force::new::<newton>(4.45), Force::new::<newton>(0.01)));
+/// assert!(!are_quantities_equal_within(expected, Force::new::<newton>(4.4), Force::new::<newton>(0.01)));
+/// ```
+pub fn are_quantities_equal_within<D, U>(
+    expected: Quantity<D, U, f64>,
+    actual: Quantity<D, U, f64>,
+    tolerance: Quantity<D, U, f64>,
+) -> bool
+where
+    D: Dimension + ?Sized,
+    U: Units<f64> + ?Sized,
+{
+    (expected.value - actual.value).abs() <= tolerance.value
+}
+
+/// Asserts that two quantities are equal within a certain tolerance
+///
+/// The expected value, actual value, and tolerance must all share the same
+/// dimension, which is checked at compile time. On failure, the expected and
+/// actual quantities and their difference are printed before panicking.
+///
+/// ## Examples
+///
+/// Successes:
+///
+/// ```
+/// # use wt_systems::assert_quantity_eq;
+/// use uom::si::{f64::{Ratio, Time}, ratio::percent, time::{millisecond, second}};
+/// assert_quantity_eq!(Time::new::<second>(1.), Time::new::<millisecond>(1000.4), Time::new::<millisecond>(0.5));
+/// assert_quantity_eq!(Ratio::new::<percent>(50.), Ratio::new::<percent>(50.), Ratio::new::<percent>(0.));
+/// ```
+///
+/// Failures:
+///
+/// ```should_panic
+/// # use wt_systems::assert_quantity_eq;
+/// use uom::si::{f64::Time, time::{millisecond, second}};
+/// assert_quantity_eq!(Time::new::<second>(1.), Time::new::<millisecond>(1000.6), Time::new::<millisecond>(0.5));
+/// ```
+///
+/// ```should_panic
+/// # use wt_systems::assert_quantity_eq;
+/// use uom::si::{f64::Ratio, ratio::percent};
+/// assert_quantity_eq!(Ratio::new::<percent>(50.), Ratio::new::<percent>(f64::NAN), Ratio::new::<percent>(1.));
+/// ```
+#[macro_export]
+macro_rules! assert_quantity_eq {
+    ($expected:expr, $actual:expr, $tolerance:expr $(,)?) => {{
+        let expected = $expected;
+        let actual = $actual;
+        let tolerance = $tolerance;
+        if !$crate::testing::are_quantities_equal_within(expected, actual, tolerance) {
+            println!("Expected:   {:?}", expected);
+            println!("Actual:     {:?}", actual);
+            println!("Difference: {:?}", expected - actual);
+            panic!(
+                "Expected and actual quantities differ by more than {:?}",
+                tolerance
+            );
+        }
+    }};
+}
+
+/// A deterministic source of noise for perturbing test inputs
+///
+/// Values are drawn from a SplitMix64 generator, so the same seed always
+/// produces the same sequence, on any platform.
+///
+/// ## Examples
+///
+/// ```
+/// # use wt_systems::testing::NoiseSource;
+/// use uom::si::{f64::Force, force::pound_force};
+/// let amplitude = Force::new::<pound_force>(25.);
+/// let mut first = NoiseSource::new(42);
+/// let mut second = NoiseSource::new(42);
+/// for _ in 0..100 {
+///     let noise = first.next_quantity(amplitude);
+///     assert_eq!(noise, second.next_quantity(amplitude));
+///     assert!(noise.abs() <= amplitude);
+/// }
+/// assert_ne!(NoiseSource::new(1).next_u64(), NoiseSource::new(2).next_u64());
+/// ```
+#[derive(Clone, Debug)]
+pub struct NoiseSource {
+    state: u64,
+}
+
+impl NoiseSource {
+    /// Creates a noise source from a seed
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next raw value in the sequence
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value uniformly distributed in the range `[-1, 1)`
+    pub fn next_unit(&mut self) -> f64 {
+        // The top 53 bits fill the mantissa of a value in [0, 1)
+        let fraction = (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64;
+        2. * fraction - 1.
+    }
+
+    /// Returns a perturbation uniformly distributed within `±amplitude`
+    pub fn next_quantity<D, U>(&mut self, amplitude: Quantity<D, U, f64>) -> Quantity<D, U, f64>
+    where
+        D: Dimension + ?Sized,
+        U: Units<f64> + ?Sized,
+    {
+        let mut perturbation = amplitude;
+        perturbation.value *= self.next_unit();
+        perturbation
+    }
+}